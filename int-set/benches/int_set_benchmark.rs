@@ -77,6 +77,31 @@ pub fn ordered_extend_benchmark(c: &mut Criterion) {
     }
 }
 
+pub fn from_sorted_iter_benchmark(c: &mut Criterion) {
+    const NUM_VALUES: u32 = 100_000;
+
+    for density in [1, 8, 64] {
+        let values: Vec<u32> = (0..NUM_VALUES).map(|i| i * density).collect();
+        let mut group = c.benchmark_group(format!("BM_SetFromSorted_100000/{density}"));
+        group.bench_with_input("insert", &values, |b, values| {
+            b.iter(|| {
+                let mut s = IntSet::<u32>::empty();
+                for v in values {
+                    s.insert(*v);
+                }
+                s
+            })
+        });
+        group.bench_with_input("from_iter", &values, |b, values| {
+            b.iter(|| values.iter().copied().collect::<IntSet<u32>>())
+        });
+        group.bench_with_input("from_sorted_iter", &values, |b, values| {
+            b.iter(|| IntSet::<u32>::from_sorted_iter(black_box(values).iter().copied()))
+        });
+        group.finish();
+    }
+}
+
 pub fn lookup_random_benchmark(c: &mut Criterion) {
     let inputs = set_parameters();
 
@@ -134,12 +159,150 @@ pub fn iteration_benchmark(c: &mut Criterion) {
     }
 }
 
+pub fn insert_range_benchmark(c: &mut Criterion) {
+    let inputs = set_parameters();
+
+    for input in inputs {
+        c.bench_with_input(
+            BenchmarkId::new("BM_SetInsertRange_1000", &input),
+            &input,
+            |b, p: &SetTest| {
+                let set = random_set(p.set_size, p.max_value());
+                let start = p.max_value() / 2;
+                b.iter_batched(
+                    || set.clone(),
+                    |mut s| {
+                        s.insert_range(start..=start + 1000);
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+}
+
+pub fn iteration_by_size_benchmark(c: &mut Criterion) {
+    for set_size in [100, 10_000, 1_000_000] {
+        // density of 4
+        let set = random_set(set_size, set_size * 4);
+        c.bench_with_input(
+            BenchmarkId::new("BM_SetIteration/size", set_size),
+            &set,
+            |b, s: &IntSet<u32>| {
+                b.iter(|| {
+                    for v in s.iter() {
+                        black_box(v);
+                    }
+                })
+            },
+        );
+    }
+}
+
+pub fn intersect_benchmark(c: &mut Criterion) {
+    const SET_SIZE: u32 = 32_768;
+    // the same number of elements, spread over a large or small range
+    let sparse = random_set(SET_SIZE, SET_SIZE * 512);
+    let dense = random_set(SET_SIZE, SET_SIZE * 2);
+
+    for (name, a, b) in [
+        (
+            "sparse_sparse",
+            &sparse,
+            random_set(SET_SIZE, SET_SIZE * 512),
+        ),
+        ("sparse_dense", &sparse, dense.clone()),
+        ("dense_dense", &dense, random_set(SET_SIZE, SET_SIZE * 2)),
+    ] {
+        c.bench_with_input(
+            BenchmarkId::new("BM_SetIntersect", name),
+            &b,
+            |bencher, other: &IntSet<u32>| {
+                bencher.iter_batched(
+                    || a.clone(),
+                    |mut s| {
+                        s.intersect(other);
+                        s
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+}
+
+pub fn len_benchmark(c: &mut Criterion) {
+    let inputs = set_parameters();
+
+    for input in inputs {
+        let set = random_set(input.set_size, input.max_value());
+        let member = set.first().unwrap();
+        c.bench_with_input(
+            BenchmarkId::new("BM_SetLen/cached", &input),
+            &set,
+            |b, s: &IntSet<u32>| {
+                // computes and caches the length
+                black_box(s.len());
+                b.iter(|| s.len())
+            },
+        );
+        c.bench_with_input(
+            BenchmarkId::new("BM_SetLen/uncached", &input),
+            &set,
+            |b, s: &IntSet<u32>| {
+                b.iter_batched(
+                    || {
+                        // inserting invalidates the cached length, even if the value is present
+                        let mut s = s.clone();
+                        s.insert(member);
+                        s
+                    },
+                    |s| s.len(),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+}
+
+pub fn contains_benchmark(c: &mut Criterion) {
+    let inputs = set_parameters();
+
+    for input in inputs {
+        let set = random_set(input.set_size, input.max_value());
+        let members = set.iter().collect::<Vec<_>>();
+        let non_members = (0..input.max_value())
+            .filter(|v| !set.contains(*v))
+            .take(members.len())
+            .collect::<Vec<_>>();
+        for (name, needles) in [("hit", members), ("miss", non_members)] {
+            let mut i = 0;
+            c.bench_with_input(
+                BenchmarkId::new(format!("BM_SetContains/{name}"), &input),
+                &set,
+                |b, s: &IntSet<u32>| {
+                    b.iter(|| {
+                        i = (i + 1) % needles.len();
+                        s.contains(needles[i])
+                    })
+                },
+            );
+        }
+    }
+}
+
 criterion_group!(
     benches,
     insert_benchmark,
     ordered_extend_benchmark,
+    from_sorted_iter_benchmark,
     lookup_random_benchmark,
     lookup_ordered_benchmark,
     iteration_benchmark,
+    insert_range_benchmark,
+    iteration_by_size_benchmark,
+    intersect_benchmark,
+    len_benchmark,
+    contains_benchmark,
 );
 criterion_main!(benches);
@@ -0,0 +1,67 @@
+//! Compares `IntSet` with the standard library sets for a typical subsetter
+//! workload: build a set from unsorted values, then iterate it in order.
+
+use std::collections::{BTreeSet, HashSet};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use int_set::IntSet;
+use rand::Rng;
+
+const NUM_VALUES: usize = 1000;
+
+fn random_values(max_value: u32) -> Vec<u32> {
+    let mut rng = rand::thread_rng();
+    (0..NUM_VALUES)
+        .map(|_| rng.gen::<u32>() % max_value)
+        .collect()
+}
+
+pub fn build_and_iterate_benchmark(c: &mut Criterion) {
+    // glyph ids, codepoints, and the full u32 range
+    for max_value in [u16::MAX as u32, 0x10FFFF, u32::MAX] {
+        let values = random_values(max_value);
+        c.bench_with_input(
+            BenchmarkId::new("BM_BuildAndIterate/IntSet", max_value),
+            &values,
+            |b, values: &Vec<u32>| {
+                b.iter(|| {
+                    let mut set = IntSet::<u32>::empty();
+                    set.extend_unsorted(values.iter().copied());
+                    for v in set.iter() {
+                        black_box(v);
+                    }
+                })
+            },
+        );
+        c.bench_with_input(
+            BenchmarkId::new("BM_BuildAndIterate/BTreeSet", max_value),
+            &values,
+            |b, values: &Vec<u32>| {
+                b.iter(|| {
+                    let set = values.iter().copied().collect::<BTreeSet<_>>();
+                    for v in set.iter() {
+                        black_box(v);
+                    }
+                })
+            },
+        );
+        c.bench_with_input(
+            BenchmarkId::new("BM_BuildAndIterate/HashSet", max_value),
+            &values,
+            |b, values: &Vec<u32>| {
+                b.iter(|| {
+                    // iteration in order requires sorting
+                    let set = values.iter().copied().collect::<HashSet<_>>();
+                    let mut sorted = set.into_iter().collect::<Vec<_>>();
+                    sorted.sort_unstable();
+                    for v in sorted {
+                        black_box(v);
+                    }
+                })
+            },
+        );
+    }
+}
+
+criterion_group!(benches, build_and_iterate_benchmark);
+criterion_main!(benches);
@@ -65,5 +65,20 @@ pub fn decode_benchmark(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, decode_benchmark);
+pub fn decode_bf32_leaves_benchmark(c: &mut Criterion) {
+    // Dense sets where most of the decoding work happens in the leaf nodes.
+    for density in [2, 3, 4] {
+        let set: IntSet<u32> = (0..65_536u32).step_by(density).collect();
+        let encoded = to_sparse_bit_set_with_bf::<32>(&set);
+        c.bench_with_input(
+            BenchmarkId::new("BM_SparseSetDecode_bf32_leaves", density),
+            &encoded,
+            |b, encoded: &Vec<u8>| {
+                b.iter(|| black_box(IntSet::<u32>::from_sparse_bit_set(encoded)));
+            },
+        );
+    }
+}
+
+criterion_group!(benches, decode_benchmark, decode_bf32_leaves_benchmark);
 criterion_main!(benches);
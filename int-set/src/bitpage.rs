@@ -108,6 +108,15 @@ impl BitPage {
         self.mark_dirty();
     }
 
+    /// Marks the values start + i as members of this set for each bit i set in bits.
+    ///
+    /// start must be a multiple of 32.
+    pub(crate) fn insert_bits(&mut self, start: u32, bits: u32) {
+        debug_assert_eq!(start % 32, 0);
+        *self.element_mut(start) |= (bits as Element) << (start & ELEM_MASK);
+        self.mark_dirty();
+    }
+
     /// Marks all values [first, last] as members of this set.
     pub(crate) fn insert_range(&mut self, first: u32, last: u32) {
         let first = first & PAGE_MASK;
@@ -403,6 +412,24 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_insert_bits() {
+        let mut page = BitPage::new_zeroes();
+        page.insert_bits(0, 0b101);
+        page.insert_bits(96, 1 << 31);
+        page.insert_bits(480, u32::MAX);
+        assert_eq!(page.len(), 35);
+        assert_eq!(
+            page.iter().take(4).collect::<Vec<u32>>(),
+            vec![0, 2, 127, 480]
+        );
+        assert_eq!(page.iter().next_back(), Some(511));
+
+        // existing members are retained.
+        page.insert_bits(0, 0b10);
+        assert_eq!(page.iter().take(3).collect::<Vec<u32>>(), vec![0, 1, 2]);
+    }
+
     #[test]
     fn test_iter_bit_indices() {
         let items: Vec<_> = Iter::new(0).collect();
@@ -152,6 +152,40 @@ impl BitSet {
         }
     }
 
+    /// Builds a new set from an iterator of values in ascending order.
+    ///
+    /// Pages are appended to the end of the page map as values move past the current page,
+    /// so no page lookups are needed for sorted input. Out of order values are still
+    /// inserted correctly but fall back to a page lookup.
+    pub(crate) fn from_sorted_iter<I: IntoIterator<Item = u32>>(iter: I) -> BitSet {
+        let mut set = BitSet::empty();
+        let mut last_page_index = usize::MAX;
+        let mut last_major_value: Option<u32> = None;
+        for val in iter {
+            let major_value = Self::get_major_value(val);
+            if Some(major_value) != last_major_value {
+                last_page_index = match set.page_map.last() {
+                    Some(last) if major_value <= last.major_value => {
+                        set.ensure_page_index_for_major(major_value)
+                    }
+                    _ => {
+                        let page_index = set.pages.len();
+                        set.pages.push(BitPage::new_zeroes());
+                        set.page_map.push(PageInfo {
+                            index: page_index as u32,
+                            major_value,
+                        });
+                        page_index
+                    }
+                };
+                last_major_value = Some(major_value);
+            }
+            set.pages[last_page_index].insert_no_return(val);
+        }
+        set.mark_dirty();
+        set
+    }
+
     /// Remove all members from this set.
     pub(crate) fn clear(&mut self) {
         self.pages.clear();
@@ -177,6 +211,17 @@ impl BitSet {
         self.len.get()
     }
 
+    /// Returns the number of pages allocated by this set.
+    pub(crate) fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns an estimate of the heap memory, in bytes, used by this set.
+    pub(crate) fn memory_usage_bytes(&self) -> usize {
+        self.pages.capacity() * std::mem::size_of::<BitPage>()
+            + self.page_map.capacity() * std::mem::size_of::<PageInfo>()
+    }
+
     /// Sets the members of this set to the union of self and other.
     pub(crate) fn union(&mut self, other: &BitSet) {
         self.process(BitPage::union, other);
@@ -613,6 +658,18 @@ impl<'a> BitSetBuilder<'a> {
         }
     }
 
+    /// Inserts start + i for each bit i set in bits. start must be a multiple of 32.
+    pub(crate) fn insert_bits(&mut self, start: u32, bits: u32) {
+        let major_value = BitSet::get_major_value(start);
+        if major_value != self.last_major_value {
+            self.last_page_index = self.set.ensure_page_index_for_major(major_value);
+            self.last_major_value = major_value;
+        };
+        if let Some(page) = self.set.pages.get_mut(self.last_page_index) {
+            page.insert_bits(start, bits);
+        }
+    }
+
     pub(crate) fn finish(&mut self) {
         self.set.mark_dirty();
     }
@@ -932,6 +989,23 @@ mod test {
         assert_eq!(s4.len(), 7);
     }
 
+    #[test]
+    fn from_sorted_iter() {
+        let values = [3, 8, 534, 700, 10000, 10001, 10002];
+        let values_unsorted = [10000, 3, 534, 700, 8, 10001, 10002, 10000, 534];
+
+        let s1 = BitSet::from_sorted_iter(values.iter().copied());
+        let s2 = BitSet::from_sorted_iter(values_unsorted.iter().copied());
+
+        assert_eq!(s1.iter().collect::<Vec<u32>>(), values);
+        assert_eq!(s2.iter().collect::<Vec<u32>>(), values);
+        assert_eq!(s1.len(), 7);
+        assert_eq!(s2.len(), 7);
+        assert_eq!(s1, s2);
+        assert_eq!(s1.pages.len(), 3);
+        assert_eq!(s2.pages.len(), 3);
+    }
+
     #[test]
     fn insert_unordered() {
         let mut bitset = BitSet::empty();
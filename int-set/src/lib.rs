@@ -16,12 +16,15 @@
 //! For a type to be stored in the IntSet it must implement the [`Domain`] trait, and all
 //! unique values of that type must be able to be mapped to and from a unique `u32` value.
 //! See the [`Domain`] trait for more information.
+//!
+//! Sets of `u64` values, which can't be mapped onto `u32`, are supported by [`U64Set`].
 
 mod bitpage;
 mod bitset;
 mod input_bit_stream;
 mod output_bit_stream;
 pub mod sparse_bit_set;
+mod u64_set;
 
 use bitset::BitSet;
 use font_types::{GlyphId, GlyphId16};
@@ -29,7 +32,9 @@ use std::hash::Hash;
 use std::marker::PhantomData;
 use std::ops::RangeInclusive;
 
-/// A fast & efficient invertible ordered set for small (up to 32-bit) unsigned integer types.
+pub use u64_set::U64Set;
+
+/// A fast & efficient invertible ordered set for small (up to 32-bit) integer types.
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct IntSet<T>(Membership, PhantomData<T>);
 
@@ -98,6 +103,7 @@ impl InDomain {
     }
 }
 
+/// The default set is empty, equivalent to [`IntSet::empty`].
 impl<T: Domain<T>> Default for IntSet<T> {
     fn default() -> IntSet<T> {
         IntSet::empty()
@@ -215,6 +221,19 @@ impl<T: Domain<T>> IntSet<T> {
         }
     }
 
+    /// Creates a new set from an iterator of values in sorted (ascending) order.
+    ///
+    /// This is the most efficient way to construct a set from known values. Unsorted
+    /// values will still produce the correct set, but more slowly.
+    pub fn from_sorted_iter<U: IntoIterator<Item = T>>(iter: U) -> IntSet<T> {
+        IntSet(
+            Membership::Inclusive(BitSet::from_sorted_iter(
+                iter.into_iter().map(|v| v.to_u32()),
+            )),
+            PhantomData::<T>,
+        )
+    }
+
     /// An alternate version of extend() which is optimized for inserting an unsorted iterator of values.
     pub fn extend_unsorted<U: IntoIterator<Item = T>>(&mut self, iter: U) {
         let iter = iter.into_iter().map(|v| v.to_u32());
@@ -344,6 +363,26 @@ impl<T: Domain<T>> IntSet<T> {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Returns the number of pages allocated by this set.
+    ///
+    /// Each page covers a fixed size range of values. An inverted set
+    /// allocates pages for its non-members.
+    pub fn page_count(&self) -> usize {
+        match &self.0 {
+            Membership::Inclusive(s) | Membership::Exclusive(s) => s.page_count(),
+        }
+    }
+
+    /// Returns an estimate of the heap memory, in bytes, used by this set.
+    ///
+    /// This includes the bit arrays of the pages and the metadata used to
+    /// look them up.
+    pub fn memory_usage_bytes(&self) -> usize {
+        match &self.0 {
+            Membership::Inclusive(s) | Membership::Exclusive(s) => s.memory_usage_bytes(),
+        }
+    }
 }
 
 impl IntSet<u32> {
@@ -787,6 +826,33 @@ impl Domain<u32> for u32 {
     }
 }
 
+impl Domain<i32> for i32 {
+    fn to_u32(&self) -> u32 {
+        // Shift by i32::MIN so that i32::MIN maps to 0, preserving ordering.
+        (*self as u32) ^ 0x8000_0000
+    }
+
+    fn from_u32(member: InDomain) -> i32 {
+        (member.value() ^ 0x8000_0000) as i32
+    }
+
+    fn is_continuous() -> bool {
+        true
+    }
+
+    fn ordered_values() -> impl DoubleEndedIterator<Item = u32> {
+        u32::MIN..=u32::MAX
+    }
+
+    fn ordered_values_range(range: RangeInclusive<i32>) -> impl DoubleEndedIterator<Item = u32> {
+        range.start().to_u32()..=range.end().to_u32()
+    }
+
+    fn count() -> usize {
+        (u32::MAX as usize) - (u32::MIN as usize) + 1
+    }
+}
+
 impl Domain<u16> for u16 {
     fn to_u32(&self) -> u32 {
         *self as u32
@@ -1016,6 +1082,21 @@ mod test {
         assert_eq!(set, expected);
     }
 
+    #[test]
+    fn default_is_empty() {
+        #[derive(Default)]
+        struct Sets {
+            gids: IntSet<u32>,
+            even: IntSet<EvenInts>,
+        }
+
+        let sets = Sets::default();
+        assert!(sets.gids.is_empty());
+        assert!(!sets.gids.is_inverted());
+        assert!(sets.even.is_empty());
+        assert_eq!(sets.gids, IntSet::<u32>::empty());
+    }
+
     #[test]
     fn insert() {
         let mut empty = IntSet::<u32>::empty();
@@ -1067,6 +1148,25 @@ mod test {
         assert!(!empty.is_empty());
     }
 
+    #[test]
+    fn page_count_and_memory_usage() {
+        let mut set = IntSet::<u32>::empty();
+        assert_eq!(set.page_count(), 0);
+        assert_eq!(set.memory_usage_bytes(), 0);
+
+        set.insert(13);
+        set.insert(14);
+        assert_eq!(set.page_count(), 1);
+        set.insert(crate::bitpage::PAGE_BITS + 13);
+        set.insert(100_000);
+        assert_eq!(set.page_count(), 3);
+        assert!(set.memory_usage_bytes() >= 3 * (crate::bitpage::PAGE_BITS as usize / 8));
+
+        // inverting doesn't change the underlying pages
+        set.invert();
+        assert_eq!(set.page_count(), 3);
+    }
+
     #[test]
     fn first() {
         let set = IntSet::<u16>::empty();
@@ -1523,6 +1623,17 @@ mod test {
         assert_eq!(s, expected);
     }
 
+    #[test]
+    fn from_sorted_iter() {
+        let s = IntSet::<u32>::from_sorted_iter([3, 8, 12, 589, 1000, 70_000]);
+        assert_eq!(s, [3, 8, 12, 589, 1000, 70_000].into_iter().collect());
+        assert_eq!(s.len(), 6);
+
+        let s = IntSet::<EvenInts>::from_sorted_iter([EvenInts(2), EvenInts(8)]);
+        assert!(s.contains(EvenInts(8)));
+        assert!(!s.is_inverted());
+    }
+
     #[test]
     fn from_int_set_iterator() {
         let s1: IntSet<u32> = [3, 8, 12, 589].into_iter().collect();
@@ -1894,6 +2005,58 @@ mod test {
         assert_eq!(it.next(), Some(6));
     }
 
+    #[test]
+    fn with_i32() {
+        let mut set = IntSet::<i32>::empty();
+
+        set.insert(-5);
+        set.insert(8);
+        set.insert(i32::MIN);
+        set.insert(i32::MAX);
+        set.insert_range(-2..=1);
+
+        assert!(set.contains(-5));
+        assert!(!set.contains(-4));
+        assert!(set.contains(-2));
+        assert!(set.contains(0));
+        assert!(set.contains(1));
+        assert!(!set.contains(2));
+        assert!(set.contains(i32::MIN));
+        assert!(set.contains(i32::MAX));
+
+        assert_eq!(set.first(), Some(i32::MIN));
+        assert_eq!(set.last(), Some(i32::MAX));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![i32::MIN, -5, -2, -1, 0, 1, 8, i32::MAX]
+        );
+        assert_eq!(
+            set.iter_ranges().collect::<Vec<_>>(),
+            vec![
+                i32::MIN..=i32::MIN,
+                -5..=-5,
+                -2..=1,
+                8..=8,
+                i32::MAX..=i32::MAX
+            ]
+        );
+        assert_eq!(
+            set.iter_after(-2).collect::<Vec<_>>(),
+            vec![-1, 0, 1, 8, i32::MAX]
+        );
+
+        set.remove(i32::MIN);
+        set.remove(i32::MAX);
+        set.invert();
+        assert!(!set.contains(0));
+        assert!(set.contains(i32::MIN));
+        assert_eq!(
+            set.iter_ranges().collect::<Vec<_>>(),
+            vec![i32::MIN..=-6, -4..=-3, 2..=7, 9..=i32::MAX]
+        );
+        assert_eq!(set.len(), u32::MAX as usize + 1 - 6);
+    }
+
     #[test]
     fn with_glyph_id_16() {
         let mut set = IntSet::<font_types::GlyphId16>::empty();
@@ -129,6 +129,14 @@ impl IntSet<u32> {
                 }
             }
 
+            if BF == 32 && next.depth == height {
+                // Leaf nodes with a branch factor of 32 start on a multiple of 32, so the node
+                // bits can be written directly into the page instead of one value at a time.
+                let start = u32::try_from(next.start).or(Err(DecodingError))?;
+                builder.insert_bits(start, bits);
+                continue;
+            }
+
             let exp = height - next.depth;
             let next_node_size = (BF as u64).pow(exp);
             loop {
@@ -166,34 +174,58 @@ impl IntSet<u32> {
     /// Sparse bit sets are a specialized, compact encoding of bit sets defined in the IFT specification:
     /// <https://w3c.github.io/IFT/Overview.html#sparse-bit-set-decoding>
     pub fn to_sparse_bit_set(&self) -> Vec<u8> {
-        // TODO(garretrieger): use the heuristic approach from the incxfer
-        // implementation to guess the optimal size. Building the set 4 times
-        // is costly.
-        let mut candidates: Vec<Vec<u8>> = vec![];
-
         let Some(max_value) = self.last() else {
             return OutputBitStream::new(BranchFactor::Two, 0).into_bytes();
         };
 
-        if BranchFactor::Two.tree_height_for(max_value) <= BranchFactor::Two.max_height() {
-            candidates.push(to_sparse_bit_set_with_bf::<2>(self));
-        }
+        // Encoding is costly, so rather than trying every branch factor only try the
+        // heuristic choice and its neighbours.
+        let guess = heuristic_branch_factor(self) as usize;
+        let first = guess.saturating_sub(1);
+        let last = (guess + 1).min(BranchFactor::ALL.len() - 1);
+        BranchFactor::ALL[first..=last]
+            .iter()
+            .filter(|bf| bf.tree_height_for(max_value) <= bf.max_height())
+            .map(|bf| match bf {
+                BranchFactor::Two => to_sparse_bit_set_with_bf::<2>(self),
+                BranchFactor::Four => to_sparse_bit_set_with_bf::<4>(self),
+                BranchFactor::Eight => to_sparse_bit_set_with_bf::<8>(self),
+                BranchFactor::ThirtyTwo => to_sparse_bit_set_with_bf::<32>(self),
+            })
+            .min_by_key(|f| f.len())
+            .unwrap()
+    }
+}
 
-        if BranchFactor::Four.tree_height_for(max_value) <= BranchFactor::Four.max_height() {
-            candidates.push(to_sparse_bit_set_with_bf::<4>(self));
-        }
+/// Estimates which branch factor will produce the smallest encoding of set, without encoding it.
+///
+/// The estimate is based on the average length of runs of consecutive values, and the density
+/// of the set (the number of members relative to the max value).
+pub(crate) fn heuristic_branch_factor(set: &IntSet<u32>) -> BranchFactor {
+    let Some(max_value) = set.last() else {
+        return BranchFactor::Two;
+    };
 
-        if BranchFactor::Eight.tree_height_for(max_value) <= BranchFactor::Eight.max_height() {
-            candidates.push(to_sparse_bit_set_with_bf::<8>(self));
-        }
+    let len = set.len() as u64;
+    let domain = max_value as u64 + 1;
+    let num_ranges = set.iter_ranges().count() as u64;
+    if len >= num_ranges * 16 {
+        // Long runs are encoded as filled nodes, which are cheapest with small nodes.
+        return BranchFactor::Two;
+    }
 
-        if BranchFactor::ThirtyTwo.tree_height_for(max_value)
-            <= BranchFactor::ThirtyTwo.max_height()
-        {
-            candidates.push(to_sparse_bit_set_with_bf::<32>(self));
-        }
+    if max_value < 256 && len * 16 < domain {
+        // Small and sparse.
+        return BranchFactor::Two;
+    }
 
-        candidates.into_iter().min_by_key(|f| f.len()).unwrap()
+    if len * 2 >= domain {
+        // Dense leaves are cheapest with large nodes.
+        BranchFactor::ThirtyTwo
+    } else if len * 4 >= domain {
+        BranchFactor::Eight
+    } else {
+        BranchFactor::Four
     }
 }
 
@@ -360,6 +392,14 @@ struct Node {
 }
 
 impl BranchFactor {
+    /// All branch factors in ascending order.
+    const ALL: [BranchFactor; 4] = [
+        BranchFactor::Two,
+        BranchFactor::Four,
+        BranchFactor::Eight,
+        BranchFactor::ThirtyTwo,
+    ];
+
     pub(crate) fn value(&self) -> u32 {
         match self {
             BranchFactor::Two => 2,
@@ -775,6 +815,24 @@ mod test {
         check_round_trip::<32>(&s2);
     }
 
+    #[test]
+    fn decode_bf32_leaves() {
+        // Leaves which cover both halves of a page element and span several pages.
+        let mut s: IntSet<u32> = [0, 31, 32, 63, 70, 511, 512, 1000, 100_000]
+            .iter()
+            .copied()
+            .collect();
+        s.extend((2048..4096).step_by(3));
+        check_round_trip::<32>(&s);
+
+        let bytes = to_sparse_bit_set_with_bf::<32>(&s);
+        assert!(IntSet::<u32>::from_sparse_bit_set_bounded(&bytes, s.len() as u64).is_ok());
+        assert_eq!(
+            IntSet::<u32>::from_sparse_bit_set_bounded(&bytes, s.len() as u64 - 1),
+            Err(DecodingError)
+        );
+    }
+
     fn check_round_trip<const BF: u8>(s: &IntSet<u32>) {
         let bytes = to_sparse_bit_set_with_bf::<BF>(s);
         let s_prime = IntSet::<u32>::from_sparse_bit_set(&bytes).unwrap();
@@ -799,6 +857,60 @@ mod test {
         assert_eq!(vec![0b0_00001_11], bytes[0..1]);
     }
 
+    #[test]
+    fn test_heuristic_branch_factor() {
+        assert_eq!(
+            heuristic_branch_factor(&IntSet::<u32>::empty()),
+            BranchFactor::Two
+        );
+
+        // small and sparse
+        let s: IntSet<u32> = [3, 70, 200].iter().copied().collect();
+        assert_eq!(heuristic_branch_factor(&s), BranchFactor::Two);
+
+        // long runs
+        let mut s = IntSet::<u32>::empty();
+        s.insert_range(100..=1000);
+        s.insert_range(50_000..=60_000);
+        assert_eq!(heuristic_branch_factor(&s), BranchFactor::Two);
+
+        // dense
+        let s: IntSet<u32> = (0..10_000).step_by(2).collect();
+        assert_eq!(heuristic_branch_factor(&s), BranchFactor::ThirtyTwo);
+        let s: IntSet<u32> = (0..10_000).step_by(3).collect();
+        assert_eq!(heuristic_branch_factor(&s), BranchFactor::Eight);
+
+        // sparse
+        let s: IntSet<u32> = (0..100_000).step_by(97).collect();
+        assert_eq!(heuristic_branch_factor(&s), BranchFactor::Four);
+    }
+
+    #[test]
+    fn to_sparse_bit_set_matches_smallest() {
+        let mut sets: Vec<IntSet<u32>> = vec![
+            [11, 74, 9358].iter().copied().collect(),
+            (0..10_000).step_by(2).collect(),
+            (0..100_000).step_by(97).collect(),
+        ];
+        let mut s = IntSet::<u32>::empty();
+        s.insert_range(100..=1000);
+        s.insert_range(50_000..=60_000);
+        sets.push(s);
+
+        for s in sets {
+            let smallest = [
+                to_sparse_bit_set_with_bf::<2>(&s).len(),
+                to_sparse_bit_set_with_bf::<4>(&s).len(),
+                to_sparse_bit_set_with_bf::<8>(&s).len(),
+                to_sparse_bit_set_with_bf::<32>(&s).len(),
+            ]
+            .into_iter()
+            .min()
+            .unwrap();
+            assert_eq!(s.to_sparse_bit_set().len(), smallest);
+        }
+    }
+
     #[test]
     fn encode_maxu32() {
         let s: IntSet<u32> = [1, u32::MAX].iter().copied().collect();
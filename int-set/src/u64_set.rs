@@ -0,0 +1,303 @@
+//! A sparse, ordered set of u64 values.
+//!
+//! [`IntSet`] maps its members onto `u32` values, so it can't represent the full `u64` space.
+//! [`U64Set`] instead uses a two level structure: the high 32 bits of each value select an
+//! entry in an ordered map, and the low 32 bits are stored in an [`IntSet<u32>`] for that entry.
+//! Values which share their high bits are therefore stored with the same compact page based
+//! representation used by [`IntSet`].
+//!
+//! Unlike [`IntSet`] this set is always inclusive, it does not support inversion.
+
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+use crate::IntSet;
+
+/// A sparse ordered set of `u64` values.
+///
+/// Empty low level sets are never retained, which keeps equality and hashing consistent
+/// with set membership.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct U64Set {
+    pages: BTreeMap<u32, IntSet<u32>>,
+    len: u64,
+}
+
+fn split(value: u64) -> (u32, u32) {
+    ((value >> 32) as u32, value as u32)
+}
+
+fn join(high: u32, low: u32) -> u64 {
+    ((high as u64) << 32) | low as u64
+}
+
+impl U64Set {
+    /// Create a new empty set.
+    pub fn empty() -> U64Set {
+        U64Set::default()
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// Returns `true` if the value was newly inserted.
+    pub fn insert(&mut self, val: u64) -> bool {
+        let (high, low) = split(val);
+        let inserted = self.pages.entry(high).or_default().insert(low);
+        if inserted {
+            self.len += 1;
+        }
+        inserted
+    }
+
+    /// Add all values in range as members of this set.
+    pub fn insert_range(&mut self, range: RangeInclusive<u64>) {
+        let (start, end) = (*range.start(), *range.end());
+        if start > end {
+            return;
+        }
+        let (start_high, start_low) = split(start);
+        let (end_high, end_low) = split(end);
+        for high in start_high..=end_high {
+            let first = if high == start_high { start_low } else { 0 };
+            let last = if high == end_high { end_low } else { u32::MAX };
+            let page = self.pages.entry(high).or_default();
+            let before = page.len();
+            page.insert_range(first..=last);
+            self.len += (page.len() - before) as u64;
+        }
+    }
+
+    /// Removes a value from the set. Returns whether the value was present in the set.
+    pub fn remove(&mut self, val: u64) -> bool {
+        let (high, low) = split(val);
+        let Some(page) = self.pages.get_mut(&high) else {
+            return false;
+        };
+        let removed = page.remove(low);
+        if removed {
+            self.len -= 1;
+            if page.is_empty() {
+                self.pages.remove(&high);
+            }
+        }
+        removed
+    }
+
+    /// Returns `true` if the set contains a value.
+    pub fn contains(&self, val: u64) -> bool {
+        let (high, low) = split(val);
+        self.pages
+            .get(&high)
+            .map(|page| page.contains(low))
+            .unwrap_or(false)
+    }
+
+    /// Returns an iterator over all members of the set in sorted ascending order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = u64> + '_ {
+        self.pages
+            .iter()
+            .flat_map(|(high, page)| page.iter().map(move |low| join(*high, low)))
+    }
+
+    /// Returns an iterator over all disjoint ranges of values within the set in sorted ascending order.
+    pub fn iter_ranges(&self) -> impl Iterator<Item = RangeInclusive<u64>> + '_ {
+        let mut ranges = self.pages.iter().flat_map(|(high, page)| {
+            page.iter_ranges()
+                .map(move |r| join(*high, *r.start())..=join(*high, *r.end()))
+        });
+        // Ranges which end at the top of one page and resume at the bottom of the
+        // next page are a single range.
+        let mut current = ranges.next();
+        std::iter::from_fn(move || {
+            let mut range = current.take()?;
+            for next in ranges.by_ref() {
+                if range.end().checked_add(1) == Some(*next.start()) {
+                    range = *range.start()..=*next.end();
+                } else {
+                    current = Some(next);
+                    break;
+                }
+            }
+            Some(range)
+        })
+    }
+
+    /// Returns first element in the set, if any. This element is always the minimum of all elements in the set.
+    pub fn first(&self) -> Option<u64> {
+        self.iter().next()
+    }
+
+    /// Returns the last element in the set, if any. This element is always the maximum of all elements in the set.
+    pub fn last(&self) -> Option<u64> {
+        self.iter().next_back()
+    }
+
+    /// Sets the members of this set to the union of self and other.
+    pub fn union(&mut self, other: &U64Set) {
+        for (high, other_page) in other.pages.iter() {
+            let page = self.pages.entry(*high).or_default();
+            let before = page.len();
+            page.union(other_page);
+            self.len += (page.len() - before) as u64;
+        }
+    }
+
+    /// Sets the members of this set to the intersection of self and other.
+    pub fn intersect(&mut self, other: &U64Set) {
+        self.pages.retain(|high, page| {
+            let Some(other_page) = other.pages.get(high) else {
+                return false;
+            };
+            page.intersect(other_page);
+            !page.is_empty()
+        });
+        self.len = self.pages.values().map(|page| page.len() as u64).sum();
+    }
+
+    /// Returns the number of members in this set.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Return true if there are no members in this set.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Clears the set, removing all values.
+    pub fn clear(&mut self) {
+        self.pages.clear();
+        self.len = 0;
+    }
+
+    /// Encode this set as a sparse bit set byte encoding.
+    ///
+    /// The sparse bit set encoding is only defined for `u32` values, see
+    /// [`IntSet::to_sparse_bit_set`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the set contains any value greater than `u32::MAX`.
+    pub fn to_sparse_bit_set(&self) -> Vec<u8> {
+        assert!(
+            self.pages.keys().all(|high| *high == 0),
+            "sparse bit sets can only encode values up to u32::MAX"
+        );
+        self.pages
+            .get(&0)
+            .map(|page| page.to_sparse_bit_set())
+            .unwrap_or_else(|| IntSet::<u32>::empty().to_sparse_bit_set())
+    }
+}
+
+impl FromIterator<u64> for U64Set {
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut s = U64Set::empty();
+        s.extend(iter);
+        s
+    }
+}
+
+impl Extend<u64> for U64Set {
+    fn extend<U: IntoIterator<Item = u64>>(&mut self, iter: U) {
+        for val in iter {
+            self.insert(val);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BIG: u64 = 1 << 40;
+
+    #[test]
+    fn insert_remove_contains() {
+        let mut set = U64Set::empty();
+        assert!(set.is_empty());
+        assert!(set.insert(5));
+        assert!(set.insert(BIG));
+        assert!(set.insert(u64::MAX));
+        assert!(!set.insert(BIG));
+        assert_eq!(set.len(), 3);
+
+        assert!(set.contains(5));
+        assert!(set.contains(BIG));
+        assert!(set.contains(u64::MAX));
+        assert!(!set.contains(BIG + 1));
+        assert!(!set.contains(6));
+
+        assert!(set.remove(BIG));
+        assert!(!set.remove(BIG));
+        assert!(!set.contains(BIG));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set, [5, u64::MAX].into_iter().collect());
+    }
+
+    #[test]
+    fn iter_and_bounds() {
+        let set: U64Set = [u64::MAX, 7, BIG + 3, BIG, 1 << 33].into_iter().collect();
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![7, 1 << 33, BIG, BIG + 3, u64::MAX]
+        );
+        assert_eq!(
+            set.iter().rev().collect::<Vec<_>>(),
+            vec![u64::MAX, BIG + 3, BIG, 1 << 33, 7]
+        );
+        assert_eq!(set.first(), Some(7));
+        assert_eq!(set.last(), Some(u64::MAX));
+        assert_eq!(U64Set::empty().first(), None);
+    }
+
+    #[test]
+    fn insert_range_across_pages() {
+        let mut set = U64Set::empty();
+        let start = (1 << 32) - 2;
+        set.insert_range(start..=start + 4);
+        set.insert_range(BIG..=BIG + 1);
+        assert_eq!(set.len(), 7);
+        assert!(set.contains(u32::MAX as u64));
+        assert!(set.contains(1 << 32));
+        assert_eq!(
+            set.iter_ranges().collect::<Vec<_>>(),
+            vec![start..=start + 4, BIG..=BIG + 1]
+        );
+    }
+
+    #[test]
+    fn union_and_intersect() {
+        let mut a: U64Set = [1, 2, BIG, BIG + 1].into_iter().collect();
+        let b: U64Set = [2, 3, BIG + 1, u64::MAX].into_iter().collect();
+
+        let mut union = a.clone();
+        union.union(&b);
+        assert_eq!(
+            union.iter().collect::<Vec<_>>(),
+            vec![1, 2, 3, BIG, BIG + 1, u64::MAX]
+        );
+        assert_eq!(union.len(), 6);
+
+        a.intersect(&b);
+        assert_eq!(a.iter().collect::<Vec<_>>(), vec![2, BIG + 1]);
+        assert_eq!(a.len(), 2);
+
+        a.intersect(&[2].into_iter().collect());
+        assert_eq!(a, [2].into_iter().collect());
+    }
+
+    #[test]
+    fn sparse_bit_set_of_u32_values() {
+        let set: U64Set = [3, 17, 1000].into_iter().collect();
+        let expected: IntSet<u32> = [3, 17, 1000].into_iter().collect();
+        assert_eq!(set.to_sparse_bit_set(), expected.to_sparse_bit_set());
+    }
+
+    #[test]
+    #[should_panic]
+    fn sparse_bit_set_rejects_large_values() {
+        let set: U64Set = [3, BIG].into_iter().collect();
+        set.to_sparse_bit_set();
+    }
+}
@@ -0,0 +1,103 @@
+//! Property based tests comparing `IntSet` against `BTreeSet`.
+
+use std::collections::BTreeSet;
+
+use int_set::{
+    sparse_bit_set::{to_sparse_bit_set_with_bf, DecodingError},
+    IntSet,
+};
+use proptest::prelude::*;
+
+#[derive(Clone, Debug)]
+enum Op {
+    Insert(u32),
+    Remove(u32),
+    InsertRange(u32, u32),
+    Union(Vec<u32>),
+    Intersect(Vec<u32>),
+    Difference(Vec<u32>),
+}
+
+/// Values are mostly small, so that operations frequently hit the same pages.
+fn value() -> impl Strategy<Value = u32> {
+    prop_oneof![
+        4 => 0u32..4096,
+        1 => any::<u32>(),
+    ]
+}
+
+fn values() -> impl Strategy<Value = Vec<u32>> {
+    prop::collection::vec(value(), 0..64)
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        value().prop_map(Op::Insert),
+        value().prop_map(Op::Remove),
+        (value(), 0u32..1024)
+            .prop_map(|(start, len)| Op::InsertRange(start, start.saturating_add(len))),
+        values().prop_map(Op::Union),
+        values().prop_map(Op::Intersect),
+        values().prop_map(Op::Difference),
+    ]
+}
+
+fn apply(op: &Op, set: &mut IntSet<u32>, reference: &mut BTreeSet<u32>) {
+    match op {
+        Op::Insert(value) => {
+            assert_eq!(set.insert(*value), reference.insert(*value));
+        }
+        Op::Remove(value) => {
+            assert_eq!(set.remove(*value), reference.remove(value));
+        }
+        Op::InsertRange(start, end) => {
+            set.insert_range(*start..=*end);
+            reference.extend(*start..=*end);
+        }
+        Op::Union(values) => {
+            set.union(&values.iter().copied().collect());
+            reference.extend(values.iter().copied());
+        }
+        Op::Intersect(values) => {
+            set.intersect(&values.iter().copied().collect());
+            let other = values.iter().copied().collect::<BTreeSet<_>>();
+            reference.retain(|value| other.contains(value));
+        }
+        Op::Difference(values) => {
+            set.remove_all(values.iter().copied());
+            for value in values {
+                reference.remove(value);
+            }
+        }
+    }
+}
+
+fn round_trip<const BF: u8>(set: &IntSet<u32>) -> Result<IntSet<u32>, DecodingError> {
+    IntSet::<u32>::from_sparse_bit_set(&to_sparse_bit_set_with_bf::<BF>(set))
+}
+
+proptest! {
+    #[test]
+    fn operations_match_btree_set(ops in prop::collection::vec(op(), 1..32)) {
+        let mut set = IntSet::<u32>::empty();
+        let mut reference = BTreeSet::new();
+        for op in &ops {
+            apply(op, &mut set, &mut reference);
+            prop_assert_eq!(set.len(), reference.len());
+            prop_assert_eq!(set.first(), reference.first().copied());
+            prop_assert_eq!(set.last(), reference.last().copied());
+            prop_assert!(set.iter().eq(reference.iter().copied()));
+        }
+    }
+
+    #[test]
+    fn sparse_bit_set_round_trip(values in values()) {
+        let set = values.into_iter().collect::<IntSet<u32>>();
+        prop_assert_eq!(&round_trip::<2>(&set).unwrap(), &set);
+        prop_assert_eq!(&round_trip::<4>(&set).unwrap(), &set);
+        prop_assert_eq!(&round_trip::<8>(&set).unwrap(), &set);
+        prop_assert_eq!(&round_trip::<32>(&set).unwrap(), &set);
+        let bytes = set.to_sparse_bit_set();
+        prop_assert_eq!(&IntSet::<u32>::from_sparse_bit_set(&bytes).unwrap(), &set);
+    }
+}
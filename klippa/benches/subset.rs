@@ -0,0 +1,124 @@
+//! Benchmarks for planning and subsetting.
+//!
+//! Before benchmarking, the size of every subset font is checked against a
+//! reference size, to catch changes that make the output larger.
+
+use std::path::{Path, PathBuf};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use int_set::IntSet;
+use klippa::{parse_unicodes, subset_font, Plan};
+use skrifa::MetadataProvider;
+use write_fonts::read::FontRef;
+
+/// Fonts with different outline formats, from the shared test data, as
+/// `(name, font, reference size)`.
+///
+/// Klippa only supports CFF2 (not CFF) outlines, so a CFF2 font is used for
+/// the CFF case.
+///
+/// The reference size is the length in bytes of the subset made from
+/// [`half_of_mapped_unicodes`]. These were recorded from klippa's own output,
+/// since there is no hb-subset output for these fonts in the test data yet;
+/// they should be replaced by the size of
+/// `hb-subset --no-layout-closure --unicodes=<same codepoints>` output.
+const TEST_FONTS: &[(&str, &[u8], usize)] = &[
+    ("glyf_variable", font_test_data::VAZIRMATN_VAR, 1188),
+    ("cff2_variable", font_test_data::CANTARELL_VF_TRIMMED, 2184),
+    ("colrv1", font_test_data::COLRV0V1, 21040),
+];
+
+/// Subsets with a reference output, as `(font, unicodes, expected file)`.
+///
+/// The expected files are in the format used by the integration tests, and
+/// were produced by hb-subset.
+const REFERENCE_SUBSETS: &[(&str, &str, &str)] = &[
+    (
+        "Roboto-Regular.abc.ttf",
+        "61,62,63",
+        "basics/Roboto-Regular.abc.61,62,63.ttf",
+    ),
+    (
+        "Roboto-Regular.abc.ttf",
+        "61,63",
+        "basics/Roboto-Regular.abc.61,63.ttf",
+    ),
+    (
+        "Roboto-Regular.abc.ttf",
+        "62",
+        "basics/Roboto-Regular.abc.62.ttf",
+    ),
+];
+
+fn test_data_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data")
+}
+
+/// Every other codepoint mapped by the font, so that some glyphs are dropped.
+fn half_of_mapped_unicodes(font: &FontRef) -> IntSet<u32> {
+    font.charmap()
+        .mappings()
+        .map(|(cp, _)| cp)
+        .step_by(2)
+        .collect()
+}
+
+fn subset(font: &FontRef, unicodes: &IntSet<u32>) -> Vec<u8> {
+    let plan = Plan::builder(font).with_unicodes(unicodes).build().unwrap();
+    subset_font(font.clone(), &plan).unwrap()
+}
+
+/// Panics if klippa's output is larger than the reference size.
+fn check_output_size(name: &str, output: &[u8], reference_len: usize) {
+    assert!(
+        output.len() <= reference_len,
+        "subset of {name} is {} bytes, but the reference is {reference_len}",
+        output.len()
+    );
+}
+
+/// Panics if any of klippa's outputs is larger than its reference subset.
+fn check_output_sizes() {
+    for (font_file, unicodes, expected_file) in REFERENCE_SUBSETS {
+        let font_bytes = std::fs::read(test_data_dir().join("fonts").join(font_file)).unwrap();
+        let font = FontRef::new(&font_bytes).unwrap();
+        let output = subset(&font, &parse_unicodes(unicodes).unwrap());
+        let expected_len = std::fs::metadata(test_data_dir().join("expected").join(expected_file))
+            .unwrap()
+            .len() as usize;
+        check_output_size(
+            &format!("{font_file} with {unicodes}"),
+            &output,
+            expected_len,
+        );
+    }
+    for (name, font_bytes, reference_len) in TEST_FONTS {
+        let font = FontRef::new(font_bytes).unwrap();
+        let output = subset(&font, &half_of_mapped_unicodes(&font));
+        check_output_size(name, &output, *reference_len);
+    }
+}
+
+pub fn subset_benchmark(c: &mut Criterion) {
+    check_output_sizes();
+
+    let mut group = c.benchmark_group("BM_Subset");
+    for (name, font_bytes, _) in TEST_FONTS {
+        let font = FontRef::new(font_bytes).unwrap();
+        let unicodes = half_of_mapped_unicodes(&font);
+        group.bench_function(BenchmarkId::new("plan", name), |b| {
+            b.iter(|| black_box(Plan::builder(&font).with_unicodes(&unicodes).build()))
+        });
+        let plan = Plan::builder(&font)
+            .with_unicodes(&unicodes)
+            .build()
+            .unwrap();
+        group.bench_function(BenchmarkId::new("subset", name), |b| {
+            b.iter(|| black_box(subset_font(font.clone(), &plan)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, subset_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,30 @@
+//! instance avar table
+
+use crate::{Plan, SubsetError};
+use write_fonts::{
+    from_obj::FromTableRef,
+    read::{FontRef, TableProvider, TopLevelTable},
+    tables::avar::Avar,
+};
+
+/// Instance the avar table according to the plan.
+///
+/// The segment maps of pinned axes are removed. Returns `None` if the table
+/// can be dropped, which is the case when no segment maps remain.
+pub fn subset_avar(font: &FontRef, plan: &Plan) -> Result<Option<Vec<u8>>, SubsetError> {
+    let avar = font
+        .avar()
+        .map_err(|e| SubsetError::table_subset_failed(Avar::TAG, e))?;
+    let mut avar = Avar::from_table_ref(&avar);
+    // there is one segment map for each axis, in fvar order
+    let location = plan.pinned_location(font);
+    let mut pinned = location.iter().map(Option::is_some);
+    avar.axis_segment_maps
+        .retain(|_| !pinned.next().unwrap_or_default());
+    if avar.axis_segment_maps.is_empty() {
+        return Ok(None);
+    }
+    write_fonts::dump_table(&avar)
+        .map(Some)
+        .map_err(|e| SubsetError::table_subset_failed(Avar::TAG, e))
+}
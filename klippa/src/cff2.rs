@@ -0,0 +1,1486 @@
+//! subset CFF2 table
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
+
+use crate::variations::instance_region;
+use crate::{Plan, SubsetError};
+use thiserror::Error;
+use write_fonts::{
+    read::{
+        tables::{
+            cff2::Cff2,
+            postscript::{
+                charstring::{self, Token as CharstringToken, NESTING_DEPTH_LIMIT},
+                dict::{self, Operator, Token as DictToken},
+                subr_bias, Error as PsError, FdSelect, Index, Number,
+            },
+            variations::ItemVariationStore,
+        },
+        FontData, FontRead, ReadError, TopLevelTable,
+    },
+    tables::variations::{
+        ItemVariationData, ItemVariationStore as WriteItemVariationStore,
+        VariationRegion as WriteVariationRegion, VariationRegionList,
+    },
+    types::{F2Dot14, GlyphId},
+};
+
+// charstring operators
+const HSTEM: u16 = 1;
+const VSTEM: u16 = 3;
+const CALLSUBR: u16 = 10;
+const RETURN: u16 = 11;
+const ESCAPE: u8 = 12;
+const ENDCHAR: u16 = 14;
+const VSINDEX: u16 = 15;
+const BLEND: u16 = 16;
+const HSTEMHM: u16 = 18;
+const HINTMASK: u16 = 19;
+const CNTRMASK: u16 = 20;
+const VSTEMHM: u16 = 23;
+const CALLGSUBR: u16 = 29;
+
+// DICT operators that are written when subsetting
+const DICT_CHARSTRINGS: u16 = 17;
+const DICT_PRIVATE: u16 = 18;
+const DICT_SUBRS: u16 = 19;
+const DICT_BLEND: u16 = 23;
+const DICT_VSTORE: u16 = 24;
+const DICT_FDARRAY: u16 = 0x0C00 | 36;
+const DICT_FDSELECT: u16 = 0x0C00 | 37;
+
+const HEADER_SIZE: u8 = 5;
+
+#[derive(Debug, Error)]
+enum Cff2Error {
+    #[error(transparent)]
+    Read(#[from] ReadError),
+    #[error("{0}")]
+    PostScript(PsError),
+    #[error("malformed CFF2 table: {0}")]
+    Malformed(&'static str),
+    #[error("unsupported CFF2 data: {0}")]
+    Unsupported(&'static str),
+    #[error("value {0} can't be encoded in a charstring")]
+    CharstringOperandOutOfRange(i32),
+}
+
+impl From<PsError> for Cff2Error {
+    fn from(value: PsError) -> Self {
+        Cff2Error::PostScript(value)
+    }
+}
+
+/// Subset the CFF2 table according to the plan.
+///
+/// Only the charstrings of retained glyphs are kept. Global and local
+/// subroutines that are no longer called are dropped and the remaining ones
+/// are renumbered, and font DICTs that are no longer used by any glyph are
+/// removed.
+///
+/// If axes are pinned in the plan, blends in charstrings and private DICTs
+/// are instanced: deltas for the pinned axes are applied to the default
+/// values, and the variation store is rebuilt for the remaining axes (or
+/// dropped if all axes are pinned). Since blend operands may be split across
+/// subroutines, the charstrings are desubroutinized in this case.
+pub fn subset_cff2(cff2_bytes: &[u8], plan: &Plan) -> Result<Vec<u8>, SubsetError> {
+    subset_cff2_impl(cff2_bytes, plan, &plan.normalized_location)
+        .map_err(|e| SubsetError::table_subset_failed(Cff2::TAG, e))
+}
+
+fn subset_cff2_impl(
+    data: &[u8],
+    plan: &Plan,
+    location: &[Option<F2Dot14>],
+) -> Result<Vec<u8>, Cff2Error> {
+    let cff2 = Cff2::read(FontData::new(data))?;
+    let top_dict = TopDict::parse(cff2.top_dict_data())?;
+    let charstrings = Index::new(table_data(data, top_dict.charstrings)?, true)?;
+    let global_subrs: Index = cff2.global_subrs().into();
+
+    let var_store = top_dict
+        .var_store
+        .map(|offset| VarStore::read(data, offset))
+        .transpose()?;
+    let region_counts = match &var_store {
+        Some(var_store) => var_store.region_counts()?,
+        None => Vec::new(),
+    };
+    let instancer = match &var_store {
+        Some(var_store) if location.iter().any(Option::is_some) => {
+            Some(Instancer::new(&var_store.store, location)?)
+        }
+        _ => None,
+    };
+
+    let fd_array = Index::new(
+        table_data(
+            data,
+            top_dict
+                .fd_array
+                .ok_or(Cff2Error::Malformed("missing FDArray"))?,
+        )?,
+        true,
+    )?;
+    let font_dicts = (0..fd_array.count() as usize)
+        .map(|i| FontDict::parse(data, fd_array.get(i)?, &region_counts))
+        .collect::<Result<Vec<_>, _>>()?;
+    let fd_select = top_dict
+        .fd_select
+        .map(|offset| Ok::<_, Cff2Error>(FdSelect::read(FontData::new(table_data(data, offset)?))?))
+        .transpose()?;
+
+    // interpret the charstrings of all retained glyphs to find the subroutines
+    // they use. When instancing, the charstrings are flattened instead.
+    let mut routines = HashMap::new();
+    let mut glyphs = Vec::new();
+    let mut flattened = Vec::new();
+    for gid in plan.output_glyphs() {
+        let Some(gid) = gid else {
+            glyphs.push(None);
+            flattened.push(Vec::new());
+            continue;
+        };
+        let fd = match &fd_select {
+            Some(fd_select) => fd_select
+                .font_index(gid)
+                .ok_or(Cff2Error::Malformed("glyph is missing from FDSelect"))?,
+            None => 0,
+        };
+        let font_dict = font_dicts
+            .get(fd as usize)
+            .ok_or(Cff2Error::Malformed("invalid font DICT index"))?;
+        let mut interpreter = Interpreter {
+            global_subrs: &global_subrs,
+            local_subrs: font_dict.local_subrs.as_ref(),
+            fd,
+            region_counts: &region_counts,
+            stack: Vec::new(),
+            stem_count: 0,
+            vsindex: font_dict.vsindex,
+            routines: &mut routines,
+            flatten: instancer.as_ref(),
+            flattened: Vec::new(),
+        };
+        let charstring = charstrings.get(gid.to_u32() as usize)?;
+        interpreter.run(RoutineKey::Glyph(gid), charstring, 0)?;
+        flattened.push(interpreter.finish());
+        glyphs.push(Some((gid, fd)));
+    }
+
+    // renumber the retained font DICTs and subroutines
+    let fd_map: BTreeMap<u16, u16> = glyphs
+        .iter()
+        .flatten()
+        .map(|(_, fd)| *fd)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .enumerate()
+        .map(|(new, old)| (old, new as u16))
+        .collect();
+    let mut global_used = BTreeSet::new();
+    let mut local_used: BTreeMap<u16, BTreeSet<u32>> = BTreeMap::new();
+    if instancer.is_none() {
+        for key in routines.keys() {
+            match key {
+                RoutineKey::Global(index) => {
+                    global_used.insert(*index);
+                }
+                RoutineKey::Local(fd, index) => {
+                    local_used.entry(*fd).or_default().insert(*index);
+                }
+                RoutineKey::Glyph(_) => (),
+            }
+        }
+    }
+    let renumbering = Renumbering {
+        routines: &routines,
+        global: SubrMap::new(&global_used),
+        local: local_used
+            .iter()
+            .map(|(fd, used)| (*fd, SubrMap::new(used)))
+            .collect(),
+    };
+
+    // charstrings, with an empty charstring for glyphs that were dropped
+    let new_charstrings = match instancer {
+        Some(_) => flattened,
+        None => glyphs
+            .iter()
+            .map(|glyph| match glyph {
+                Some((gid, _)) => renumbering.rewrite(
+                    RoutineKey::Glyph(*gid),
+                    charstrings.get(gid.to_u32() as usize)?,
+                ),
+                None => Ok(Vec::new()),
+            })
+            .collect::<Result<Vec<_>, Cff2Error>>()?,
+    };
+    let new_global_subrs = global_used
+        .iter()
+        .map(|index| {
+            renumbering.rewrite(
+                RoutineKey::Global(*index),
+                global_subrs.get(*index as usize)?,
+            )
+        })
+        .collect::<Result<Vec<_>, Cff2Error>>()?;
+
+    // private DICTs, each followed by its local subroutines
+    let mut private_dicts = Vec::new();
+    for old_fd in fd_map.keys() {
+        let font_dict = &font_dicts[*old_fd as usize];
+        let local_subrs = match (local_used.get(old_fd), font_dict.local_subrs.as_ref()) {
+            (Some(used), Some(subrs)) => used
+                .iter()
+                .map(|index| {
+                    renumbering.rewrite(
+                        RoutineKey::Local(*old_fd, *index),
+                        subrs.get(*index as usize)?,
+                    )
+                })
+                .collect::<Result<Vec<_>, Cff2Error>>()?,
+            _ => Vec::new(),
+        };
+        let mut private_dict =
+            rewrite_private_dict(font_dict.private_dict, &region_counts, instancer.as_ref())?;
+        if !local_subrs.is_empty() {
+            // the offset to the local subroutines is relative to the private DICT,
+            // which they immediately follow.
+            let len = private_dict.len() + FIXED_OPERAND_LEN + 1;
+            encode_fixed_dict_operand(len, &mut private_dict);
+            encode_dict_operator(DICT_SUBRS, &mut private_dict);
+        }
+        let local_subrs = if local_subrs.is_empty() {
+            Vec::new()
+        } else {
+            write_index(&local_subrs)
+        };
+        private_dicts.push((private_dict, local_subrs));
+    }
+
+    let fd_select = (fd_map.len() > 1).then(|| {
+        let fds = glyphs
+            .iter()
+            .map(|glyph| glyph.map(|(_, fd)| fd_map[&fd]).unwrap_or_default());
+        write_fd_select(fds, fd_map.len())
+    });
+    let var_store = match (&var_store, &instancer) {
+        (Some(_), Some(instancer)) => instancer.store.as_ref().map(write_var_store).transpose()?,
+        (Some(var_store), None) => Some(var_store.data.to_vec()),
+        (None, _) => None,
+    };
+
+    // lay out the table: the global subroutines must immediately follow the
+    // top DICT, and the offsets in DICTs are always written using five bytes
+    // so that their length does not depend on their values.
+    let mut top_dict_len = top_dict.retained.len() + 2 * FIXED_OPERAND_LEN + 3;
+    if fd_select.is_some() {
+        top_dict_len += FIXED_OPERAND_LEN + 2;
+    }
+    if var_store.is_some() {
+        top_dict_len += FIXED_OPERAND_LEN + 1;
+    }
+    let global_subrs = write_index(&new_global_subrs);
+    let charstrings = write_index(&new_charstrings);
+    let mut offset = HEADER_SIZE as usize + top_dict_len + global_subrs.len();
+    let var_store_offset = offset;
+    offset += var_store.as_ref().map(Vec::len).unwrap_or_default();
+    let charstrings_offset = offset;
+    offset += charstrings.len();
+    let fd_select_offset = offset;
+    offset += fd_select.as_ref().map(Vec::len).unwrap_or_default();
+    let fd_array_offset = offset;
+    // each font DICT is the retained entries followed by the private DICT entry
+    let font_dict_lens = fd_map
+        .keys()
+        .map(|fd| font_dicts[*fd as usize].retained.len() + 2 * FIXED_OPERAND_LEN + 1)
+        .collect::<Vec<_>>();
+    offset += index_len(&font_dict_lens);
+    let mut new_font_dicts = Vec::new();
+    for (old_fd, (private_dict, local_subrs)) in fd_map.keys().zip(&private_dicts) {
+        let mut font_dict = font_dicts[*old_fd as usize].retained.clone();
+        encode_fixed_dict_operand(private_dict.len(), &mut font_dict);
+        encode_fixed_dict_operand(offset, &mut font_dict);
+        encode_dict_operator(DICT_PRIVATE, &mut font_dict);
+        new_font_dicts.push(font_dict);
+        offset += private_dict.len() + local_subrs.len();
+    }
+
+    let mut new_top_dict = top_dict.retained.clone();
+    encode_fixed_dict_operand(charstrings_offset, &mut new_top_dict);
+    encode_dict_operator(DICT_CHARSTRINGS, &mut new_top_dict);
+    encode_fixed_dict_operand(fd_array_offset, &mut new_top_dict);
+    encode_dict_operator(DICT_FDARRAY, &mut new_top_dict);
+    if fd_select.is_some() {
+        encode_fixed_dict_operand(fd_select_offset, &mut new_top_dict);
+        encode_dict_operator(DICT_FDSELECT, &mut new_top_dict);
+    }
+    if var_store.is_some() {
+        encode_fixed_dict_operand(var_store_offset, &mut new_top_dict);
+        encode_dict_operator(DICT_VSTORE, &mut new_top_dict);
+    }
+    debug_assert_eq!(new_top_dict.len(), top_dict_len);
+    let top_dict_len =
+        u16::try_from(top_dict_len).map_err(|_| Cff2Error::Unsupported("top DICT is too large"))?;
+
+    let mut out = Vec::with_capacity(offset);
+    out.extend([2, 0, HEADER_SIZE]);
+    out.extend(top_dict_len.to_be_bytes());
+    out.extend(new_top_dict);
+    out.extend(global_subrs);
+    out.extend(var_store.unwrap_or_default());
+    out.extend(charstrings);
+    out.extend(fd_select.unwrap_or_default());
+    out.extend(write_index(&new_font_dicts));
+    for (private_dict, local_subrs) in private_dicts {
+        out.extend(private_dict);
+        out.extend(local_subrs);
+    }
+    debug_assert_eq!(out.len(), offset);
+    Ok(out)
+}
+
+fn table_data(data: &[u8], offset: usize) -> Result<&[u8], Cff2Error> {
+    data.get(offset..)
+        .ok_or(Cff2Error::Read(ReadError::OutOfBounds))
+}
+
+/// The entries of the top DICT.
+struct TopDict {
+    /// The raw bytes of all entries that are not offsets.
+    retained: Vec<u8>,
+    charstrings: usize,
+    fd_array: Option<usize>,
+    fd_select: Option<usize>,
+    var_store: Option<usize>,
+}
+
+impl TopDict {
+    fn parse(data: &[u8]) -> Result<Self, Cff2Error> {
+        let mut retained = Vec::new();
+        let mut charstrings = None;
+        let mut fd_array = None;
+        let mut fd_select = None;
+        let mut var_store = None;
+        for entry in dict_entries(data)? {
+            let offset = || entry.last_operand_offset();
+            match entry.operator {
+                Operator::CharstringsOffset => charstrings = Some(offset()?),
+                Operator::FdArrayOffset => fd_array = Some(offset()?),
+                Operator::FdSelectOffset => fd_select = Some(offset()?),
+                Operator::VariationStoreOffset => var_store = Some(offset()?),
+                _ => retained.extend_from_slice(&data[entry.range]),
+            }
+        }
+        Ok(TopDict {
+            retained,
+            charstrings: charstrings.ok_or(Cff2Error::Malformed("missing CharStrings"))?,
+            fd_array,
+            fd_select,
+            var_store,
+        })
+    }
+}
+
+/// A font DICT and the parts of its private DICT needed for subsetting.
+struct FontDict<'a> {
+    /// The raw bytes of all entries other than Private.
+    retained: Vec<u8>,
+    private_dict: &'a [u8],
+    local_subrs: Option<Index<'a>>,
+    /// The default variation store index for charstrings.
+    vsindex: u16,
+}
+
+impl<'a> FontDict<'a> {
+    fn parse(data: &'a [u8], font_dict: &[u8], region_counts: &[usize]) -> Result<Self, Cff2Error> {
+        let mut retained = Vec::new();
+        let mut private_range = None;
+        for entry in dict_entries(font_dict)? {
+            if entry.operator == Operator::PrivateDictRange {
+                let [size, offset] = entry.operands[..] else {
+                    return Err(Cff2Error::Malformed("invalid Private entry"));
+                };
+                let (size, offset) = (to_offset(size)?, to_offset(offset)?);
+                private_range = Some(offset..offset + size);
+            } else {
+                retained.extend_from_slice(&font_dict[entry.range]);
+            }
+        }
+        let private_range = private_range.ok_or(PsError::MissingPrivateDict)?;
+        let private_start = private_range.start;
+        let private_dict = data
+            .get(private_range)
+            .ok_or(Cff2Error::Read(ReadError::OutOfBounds))?;
+        let mut local_subrs = None;
+        let mut vsindex = 0;
+        for entry in dict_entries(private_dict)? {
+            match entry.operator {
+                Operator::SubrsOffset => {
+                    let offset = private_start + entry.last_operand_offset()?;
+                    local_subrs = Some(Index::new(table_data(data, offset)?, true)?);
+                }
+                Operator::VariationStoreIndex => {
+                    vsindex = to_vsindex(entry.last_operand()?, region_counts)?;
+                }
+                _ => (),
+            }
+        }
+        Ok(FontDict {
+            retained,
+            private_dict,
+            local_subrs,
+            vsindex,
+        })
+    }
+}
+
+/// The variation store of the CFF2 table.
+struct VarStore<'a> {
+    store: ItemVariationStore<'a>,
+    /// The raw data, including the length prefix.
+    data: &'a [u8],
+}
+
+impl<'a> VarStore<'a> {
+    fn read(data: &'a [u8], offset: usize) -> Result<Self, Cff2Error> {
+        let len = FontData::new(data).read_at::<u16>(offset)? as usize;
+        let data = data
+            .get(offset..offset + 2 + len)
+            .ok_or(Cff2Error::Read(ReadError::OutOfBounds))?;
+        let store = ItemVariationStore::read(FontData::new(&data[2..]))?;
+        Ok(VarStore { store, data })
+    }
+
+    /// The number of regions referenced by each ItemVariationData subtable,
+    /// which is the number of deltas per value in a blend.
+    fn region_counts(&self) -> Result<Vec<usize>, ReadError> {
+        self.store
+            .item_variation_data()
+            .iter()
+            .map(|data| {
+                Ok(data
+                    .transpose()?
+                    .map(|data| data.region_index_count() as usize)
+                    .unwrap_or_default())
+            })
+            .collect()
+    }
+}
+
+fn write_var_store(store: &WriteItemVariationStore) -> Result<Vec<u8>, Cff2Error> {
+    let store = write_fonts::dump_table(store)
+        .map_err(|_| Cff2Error::Unsupported("failed to write the variation store"))?;
+    let len = u16::try_from(store.len())
+        .map_err(|_| Cff2Error::Unsupported("variation store is too large"))?;
+    let mut out = len.to_be_bytes().to_vec();
+    out.extend(store);
+    Ok(out)
+}
+
+/// Instances the blends of a CFF2 table at a location where some or all
+/// axes are pinned.
+struct Instancer {
+    /// For each variation store index, the scalar for each region at the
+    /// pinned location and the index of the new region it maps to. Regions
+    /// without a new region only apply to the default values.
+    regions: Vec<Vec<(f32, Option<usize>)>>,
+    /// For each variation store index, the number of regions after instancing.
+    new_region_counts: Vec<usize>,
+    /// The new variation store; `None` if all axes are pinned.
+    store: Option<WriteItemVariationStore>,
+}
+
+/// The values of a blend after instancing.
+struct InstancedBlend {
+    defaults: Vec<f64>,
+    deltas: Vec<f64>,
+}
+
+impl Instancer {
+    fn new(store: &ItemVariationStore, location: &[Option<F2Dot14>]) -> Result<Self, ReadError> {
+        let region_list = store.variation_region_list()?;
+        let axis_count = region_list.axis_count() as usize;
+        let source_regions = region_list.variation_regions();
+        let mut new_regions: Vec<WriteVariationRegion> = Vec::new();
+        let mut regions = Vec::new();
+        let mut new_region_counts = Vec::new();
+        let mut item_data = Vec::new();
+        for data in store.item_variation_data().iter() {
+            let Some(data) = data.transpose()? else {
+                regions.push(Vec::new());
+                new_region_counts.push(0);
+                item_data.push(None);
+                continue;
+            };
+            let mut region_indexes: Vec<u16> = Vec::new();
+            let mut mapping = Vec::new();
+            for index in data.region_indexes() {
+                let region = source_regions.get(index.get() as usize)?;
+                let (scalar, new_region) = instance_region(&region, location);
+                let target = new_region.map(|new_region| {
+                    let global_index = match new_regions.iter().position(|r| *r == new_region) {
+                        Some(index) => index,
+                        None => {
+                            new_regions.push(new_region);
+                            new_regions.len() - 1
+                        }
+                    } as u16;
+                    match region_indexes.iter().position(|ix| *ix == global_index) {
+                        Some(index) => index,
+                        None => {
+                            region_indexes.push(global_index);
+                            region_indexes.len() - 1
+                        }
+                    }
+                });
+                mapping.push((scalar, target));
+            }
+            regions.push(mapping);
+            new_region_counts.push(region_indexes.len());
+            item_data.push(Some(ItemVariationData::new(
+                0,
+                0,
+                region_indexes,
+                Vec::new(),
+            )));
+        }
+        let pinned_count = location
+            .iter()
+            .take(axis_count)
+            .filter(|coord| coord.is_some())
+            .count();
+        let store = (pinned_count < axis_count).then(|| {
+            WriteItemVariationStore::new(
+                VariationRegionList::new((axis_count - pinned_count) as u16, new_regions),
+                item_data,
+            )
+        });
+        Ok(Instancer {
+            regions,
+            new_region_counts,
+            store,
+        })
+    }
+
+    /// Returns `true` if variation store indices are removed when instancing.
+    fn drops_vsindex(&self) -> bool {
+        self.store.is_none()
+    }
+
+    /// Instance a blend of `n` values, given its operands (the defaults followed
+    /// by the deltas for each value).
+    fn instance_blend(
+        &self,
+        vsindex: u16,
+        n: usize,
+        operands: &[f64],
+    ) -> Result<InstancedBlend, Cff2Error> {
+        let regions = self
+            .regions
+            .get(vsindex as usize)
+            .ok_or(Cff2Error::Malformed("invalid vsindex"))?;
+        let new_count = self.new_region_counts[vsindex as usize];
+        let (defaults, deltas) = operands.split_at(n);
+        let mut defaults = defaults.to_vec();
+        let mut new_deltas = vec![0.0; n * new_count];
+        for (i, default) in defaults.iter_mut().enumerate() {
+            let deltas = &deltas[i * regions.len()..(i + 1) * regions.len()];
+            for (delta, (scalar, target)) in deltas.iter().zip(regions) {
+                let delta = delta * *scalar as f64;
+                match target {
+                    Some(target) => new_deltas[i * new_count + target] += delta,
+                    None => *default += delta,
+                }
+            }
+        }
+        Ok(InstancedBlend {
+            defaults,
+            deltas: new_deltas,
+        })
+    }
+}
+
+impl InstancedBlend {
+    /// Write the values, followed by a blend operator if there are any deltas.
+    fn write(
+        &self,
+        blend_operator: u16,
+        encode_operand: fn(i32, &mut Vec<u8>) -> Result<(), Cff2Error>,
+        out: &mut Vec<u8>,
+    ) -> Result<(), Cff2Error> {
+        for value in &self.defaults {
+            encode_operand(value.round() as i32, out)?;
+        }
+        if self.deltas.is_empty() {
+            return Ok(());
+        }
+        for value in &self.deltas {
+            encode_operand(value.round() as i32, out)?;
+        }
+        encode_operand(self.defaults.len() as i32, out)?;
+        encode_operator(blend_operator, out);
+        Ok(())
+    }
+}
+
+/// Identifies a charstring or subroutine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum RoutineKey {
+    Glyph(GlyphId),
+    Global(u32),
+    /// A local subroutine of the font DICT at the given index.
+    Local(u16, u32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TokenKind {
+    Number(f64),
+    Operator(u16),
+    /// The mask bytes following a hintmask or cntrmask operator.
+    Mask,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    range: Range<usize>,
+}
+
+/// The tokens of a charstring or subroutine and the subroutines it calls.
+#[derive(Default)]
+struct Routine {
+    tokens: Vec<Token>,
+    /// The subroutines called with each operand token.
+    calls: HashMap<usize, BTreeSet<RoutineKey>>,
+}
+
+/// A value on the charstring stack.
+#[derive(Clone, Debug, Default)]
+struct StackValue {
+    /// `None` for the results of blends.
+    value: Option<f64>,
+    /// The routine and token index of a literal number.
+    source: Option<(RoutineKey, usize)>,
+    /// The encoded value, when flattening.
+    bytes: Vec<u8>,
+}
+
+/// Interprets a charstring just enough to find the subroutines it calls.
+///
+/// When instancing, the charstring is also flattened: subroutine calls are
+/// replaced by the subroutines they call, which lets us instance blends
+/// whose operands are split across subroutines.
+struct Interpreter<'a, 'b> {
+    global_subrs: &'b Index<'a>,
+    local_subrs: Option<&'b Index<'a>>,
+    fd: u16,
+    region_counts: &'b [usize],
+    stack: Vec<StackValue>,
+    stem_count: usize,
+    vsindex: u16,
+    routines: &'b mut HashMap<RoutineKey, Routine>,
+    flatten: Option<&'b Instancer>,
+    flattened: Vec<u8>,
+}
+
+impl<'a> Interpreter<'a, '_> {
+    fn run(&mut self, key: RoutineKey, data: &'a [u8], depth: u32) -> Result<(), Cff2Error> {
+        if depth > NESTING_DEPTH_LIMIT {
+            return Err(PsError::CharstringNestingDepthLimitExceeded.into());
+        }
+        let mut routine = self.routines.remove(&key).unwrap_or_default();
+        let result = self.run_routine(key, data, &mut routine, depth);
+        self.routines.insert(key, routine);
+        result
+    }
+
+    fn run_routine(
+        &mut self,
+        key: RoutineKey,
+        data: &'a [u8],
+        routine: &mut Routine,
+        depth: u32,
+    ) -> Result<(), Cff2Error> {
+        let mut ix = 0;
+        let mut pos = 0;
+        // the routine is tokenized on its first visit; the length of hint
+        // masks depends on the stem count, so this is done while interpreting.
+        while ix < routine.tokens.len() || pos < data.len() {
+            if ix == routine.tokens.len() {
+                routine.tokens.push(parse_charstring_token(data, pos)?);
+            }
+            let token = routine.tokens[ix].clone();
+            pos = token.range.end;
+            ix += 1;
+            let op = match token.kind {
+                TokenKind::Number(value) => {
+                    self.stack.push(StackValue {
+                        value: Some(value),
+                        source: Some((key, ix - 1)),
+                        bytes: match self.flatten {
+                            Some(_) => data[token.range].to_vec(),
+                            None => Vec::new(),
+                        },
+                    });
+                    continue;
+                }
+                TokenKind::Mask => unreachable!("masks are consumed with their operator"),
+                TokenKind::Operator(op) => op,
+            };
+            match op {
+                HSTEM | VSTEM | HSTEMHM | VSTEMHM => {
+                    self.stem_count += self.stack.len() / 2;
+                    self.emit(op);
+                }
+                HINTMASK | CNTRMASK => {
+                    // operands before a mask are an implicit vstem
+                    self.stem_count += self.stack.len() / 2;
+                    self.emit(op);
+                    let end = pos + self.stem_count.div_ceil(8);
+                    if ix == routine.tokens.len() {
+                        if end > data.len() {
+                            return Err(Cff2Error::Read(ReadError::OutOfBounds));
+                        }
+                        routine.tokens.push(Token {
+                            kind: TokenKind::Mask,
+                            range: pos..end,
+                        });
+                    } else if routine.tokens[ix].range.end != end {
+                        return Err(Cff2Error::Unsupported(
+                            "subroutine is used with different numbers of stems",
+                        ));
+                    }
+                    if self.flatten.is_some() {
+                        self.flattened.extend_from_slice(&data[pos..end]);
+                    }
+                    pos = end;
+                    ix += 1;
+                }
+                CALLSUBR | CALLGSUBR => {
+                    let operand = self.stack.pop().ok_or(PsError::StackUnderflow)?;
+                    let value = operand.value.ok_or(Cff2Error::Unsupported(
+                        "subroutine index is not a literal number",
+                    ))?;
+                    let subrs = if op == CALLGSUBR {
+                        self.global_subrs
+                    } else {
+                        self.local_subrs.ok_or(PsError::MissingSubroutines)?
+                    };
+                    let index = u32::try_from(value as i32 + subrs.subr_bias())
+                        .ok()
+                        .filter(|index| *index < subrs.count())
+                        .ok_or(Cff2Error::Malformed("invalid subroutine index"))?;
+                    let child = if op == CALLGSUBR {
+                        RoutineKey::Global(index)
+                    } else {
+                        RoutineKey::Local(self.fd, index)
+                    };
+                    // when not flattening, the operand is rewritten with the
+                    // new index, so it must be a literal in this routine.
+                    match operand.source {
+                        Some((source, token)) if source == key => {
+                            routine.calls.entry(token).or_default().insert(child);
+                        }
+                        _ if self.flatten.is_some() => (),
+                        _ => {
+                            return Err(Cff2Error::Unsupported(
+                                "subroutine index is not a literal number",
+                            ))
+                        }
+                    }
+                    self.run(child, subrs.get(index as usize)?, depth + 1)?;
+                }
+                VSINDEX => {
+                    let value = self
+                        .stack
+                        .last()
+                        .and_then(|operand| operand.value)
+                        .ok_or(Cff2Error::Unsupported("vsindex is not a literal number"))?;
+                    self.vsindex = to_vsindex(value, self.region_counts)?;
+                    if self.flatten.is_some_and(Instancer::drops_vsindex) {
+                        self.stack.clear();
+                    } else {
+                        self.emit(op);
+                    }
+                }
+                BLEND => self.blend()?,
+                RETURN => break,
+                ENDCHAR => {
+                    self.emit(op);
+                    break;
+                }
+                _ => self.emit(op),
+            }
+        }
+        Ok(())
+    }
+
+    /// Clear the stack, writing its values and the operator when flattening.
+    fn emit(&mut self, op: u16) {
+        if self.flatten.is_some() {
+            for value in self.stack.drain(..) {
+                self.flattened.extend(value.bytes);
+            }
+            encode_operator(op, &mut self.flattened);
+        } else {
+            self.stack.clear();
+        }
+    }
+
+    fn blend(&mut self) -> Result<(), Cff2Error> {
+        let n = self
+            .stack
+            .pop()
+            .and_then(|n| n.value)
+            .ok_or(Cff2Error::Unsupported(
+                "blend count is not a literal number",
+            ))? as usize;
+        let region_count = *self
+            .region_counts
+            .get(self.vsindex as usize)
+            .ok_or(Cff2Error::Malformed("invalid vsindex"))?;
+        let count = n * (region_count + 1);
+        if self.stack.len() < count {
+            return Err(PsError::StackUnderflow.into());
+        }
+        let operands = self.stack.split_off(self.stack.len() - count);
+        let Some(instancer) = self.flatten else {
+            self.stack
+                .extend(std::iter::repeat_n(StackValue::default(), n));
+            return Ok(());
+        };
+        let operands = operands
+            .iter()
+            .map(|operand| {
+                operand.value.ok_or(Cff2Error::Unsupported(
+                    "blend operand is not a literal number",
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut bytes = Vec::new();
+        instancer
+            .instance_blend(self.vsindex, n, &operands)?
+            .write(BLEND, encode_charstring_operand, &mut bytes)?;
+        // the results are written together, so the first value holds all
+        // the bytes.
+        self.stack.push(StackValue {
+            bytes,
+            ..Default::default()
+        });
+        self.stack.extend(std::iter::repeat_n(
+            StackValue::default(),
+            n.saturating_sub(1),
+        ));
+        Ok(())
+    }
+
+    /// Finish flattening a glyph, returning the charstring.
+    fn finish(&mut self) -> Vec<u8> {
+        for value in self.stack.drain(..) {
+            self.flattened.extend(value.bytes);
+        }
+        std::mem::take(&mut self.flattened)
+    }
+}
+
+/// Maps the indices of retained subroutines to their new indices.
+struct SubrMap {
+    new_indices: HashMap<u32, u32>,
+    bias: i32,
+}
+
+impl SubrMap {
+    fn new(used: &BTreeSet<u32>) -> Self {
+        SubrMap {
+            new_indices: used
+                .iter()
+                .enumerate()
+                .map(|(new, old)| (*old, new as u32))
+                .collect(),
+            bias: subr_bias(used.len() as u32),
+        }
+    }
+
+    /// The biased operand used to call the subroutine with the given old index.
+    fn operand(&self, index: u32) -> i32 {
+        self.new_indices[&index] as i32 - self.bias
+    }
+}
+
+/// Rewrites subroutine calls for the renumbered subroutines.
+struct Renumbering<'a> {
+    routines: &'a HashMap<RoutineKey, Routine>,
+    global: SubrMap,
+    /// Keyed by the old font DICT index.
+    local: BTreeMap<u16, SubrMap>,
+}
+
+impl Renumbering<'_> {
+    /// The new operand for a call to the given subroutines.
+    ///
+    /// A call in a global subroutine may resolve to a different local
+    /// subroutine for each font DICT; these must all end up with the same
+    /// new index.
+    fn call_operand(&self, calls: &BTreeSet<RoutineKey>) -> Result<i32, Cff2Error> {
+        let mut operands = calls.iter().map(|call| match call {
+            RoutineKey::Global(index) => self.global.operand(*index),
+            RoutineKey::Local(fd, index) => self.local[fd].operand(*index),
+            RoutineKey::Glyph(_) => unreachable!("glyphs are never called"),
+        });
+        let first = operands
+            .next()
+            .ok_or(Cff2Error::Malformed("subroutine call without a target"))?;
+        if operands.any(|operand| operand != first) {
+            return Err(Cff2Error::Unsupported(
+                "global subroutine calls different local subroutines",
+            ));
+        }
+        Ok(first)
+    }
+
+    /// Copy a charstring or subroutine, with the new subroutine indices.
+    fn rewrite(&self, key: RoutineKey, data: &[u8]) -> Result<Vec<u8>, Cff2Error> {
+        let routine = &self.routines[&key];
+        let mut out = Vec::with_capacity(data.len());
+        for (ix, token) in routine.tokens.iter().enumerate() {
+            match routine.calls.get(&ix) {
+                Some(calls) => encode_charstring_operand(self.call_operand(calls)?, &mut out)?,
+                None => out.extend_from_slice(&data[token.range.clone()]),
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Copy the private DICT, without the offset to the local subroutines and
+/// with instanced blends.
+fn rewrite_private_dict(
+    data: &[u8],
+    region_counts: &[usize],
+    instancer: Option<&Instancer>,
+) -> Result<Vec<u8>, Cff2Error> {
+    // the encoded operands on the stack, with their values if they are
+    // literal numbers.
+    let mut stack: Vec<(Option<f64>, Range<usize>)> = Vec::new();
+    let mut instanced: HashMap<usize, Vec<u8>> = HashMap::new();
+    let mut vsindex = 0;
+    let mut out = Vec::with_capacity(data.len());
+    for token in dict::tokens_with_ranges(data) {
+        let (token, range) = token?;
+        let op = match token {
+            DictToken::Operand(number) => {
+                stack.push((Some(number_value(number)), range));
+                continue;
+            }
+            DictToken::Operator(op) => op,
+        };
+        match (op, instancer) {
+            (Operator::Blend, Some(instancer)) => {
+                let n = match stack.pop() {
+                    Some((Some(n), _)) => n as usize,
+                    _ => return Err(Cff2Error::Unsupported("blend count is not a literal")),
+                };
+                let region_count = *region_counts
+                    .get(vsindex as usize)
+                    .ok_or(Cff2Error::Malformed("invalid vsindex"))?;
+                let count = n * (region_count + 1);
+                if stack.len() < count {
+                    return Err(PsError::StackUnderflow.into());
+                }
+                let operands = stack
+                    .split_off(stack.len() - count)
+                    .into_iter()
+                    .map(|(value, _)| {
+                        value.ok_or(Cff2Error::Unsupported("blend operand is not a literal"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut encoded = Vec::new();
+                instancer.instance_blend(vsindex, n, &operands)?.write(
+                    DICT_BLEND,
+                    encode_dict_operand,
+                    &mut encoded,
+                )?;
+                // the blend results are written as one entry holding all
+                // the bytes, followed by empty entries for the other values.
+                instanced.insert(range.start, encoded);
+                stack.push((None, range.start..range.start));
+                stack.extend(std::iter::repeat_n((None, 0..0), n.saturating_sub(1)));
+            }
+            (Operator::Blend, None) => {
+                let range = stack
+                    .first()
+                    .map(|(_, range)| range.start)
+                    .unwrap_or(range.start)..range.end;
+                stack.clear();
+                stack.push((None, range));
+            }
+            (Operator::SubrsOffset, _) => stack.clear(),
+            (Operator::VariationStoreIndex, _) => {
+                let value = match stack.last() {
+                    Some((Some(value), _)) => *value,
+                    _ => return Err(Cff2Error::Unsupported("vsindex is not a literal")),
+                };
+                vsindex = to_vsindex(value, region_counts)?;
+                if instancer.is_some_and(Instancer::drops_vsindex) {
+                    stack.clear();
+                } else {
+                    write_dict_entry(data, &mut stack, &instanced, &range, &mut out);
+                }
+            }
+            _ => write_dict_entry(data, &mut stack, &instanced, &range, &mut out),
+        }
+    }
+    Ok(out)
+}
+
+fn write_dict_entry(
+    data: &[u8],
+    stack: &mut Vec<(Option<f64>, Range<usize>)>,
+    instanced: &HashMap<usize, Vec<u8>>,
+    operator: &Range<usize>,
+    out: &mut Vec<u8>,
+) {
+    for (_, range) in stack.drain(..) {
+        match instanced.get(&range.start).filter(|_| range.is_empty()) {
+            Some(bytes) => out.extend_from_slice(bytes),
+            None => out.extend_from_slice(&data[range]),
+        }
+    }
+    out.extend_from_slice(&data[operator.clone()]);
+}
+
+/// An entry in a DICT.
+struct DictEntry {
+    operator: Operator,
+    operands: Vec<f64>,
+    /// The range of the operands and the operator.
+    range: Range<usize>,
+}
+
+impl DictEntry {
+    fn last_operand(&self) -> Result<f64, Cff2Error> {
+        self.operands
+            .last()
+            .copied()
+            .ok_or(PsError::StackUnderflow.into())
+    }
+
+    fn last_operand_offset(&self) -> Result<usize, Cff2Error> {
+        to_offset(self.last_operand()?)
+    }
+}
+
+fn to_offset(value: f64) -> Result<usize, Cff2Error> {
+    (value >= 0.0 && value.fract() == 0.0)
+        .then_some(value as usize)
+        .ok_or(Cff2Error::Malformed("invalid offset"))
+}
+
+/// Check that a vsindex operand selects one of the ItemVariationData
+/// subtables, given the number of regions of each.
+fn to_vsindex(value: f64, region_counts: &[usize]) -> Result<u16, Cff2Error> {
+    (value >= 0.0 && value.fract() == 0.0 && (value as usize) < region_counts.len())
+        .then_some(value as u16)
+        .ok_or(Cff2Error::Malformed("invalid vsindex"))
+}
+
+fn number_value(number: Number) -> f64 {
+    match number {
+        Number::I32(value) => value as f64,
+        Number::Fixed(value) => value.to_f64(),
+    }
+}
+
+/// Split a DICT into entries.
+///
+/// Blends do not end an entry, so the operands of entries with blends
+/// include the blend operands; these are only used for entries that never
+/// have blends.
+fn dict_entries(data: &[u8]) -> Result<Vec<DictEntry>, Cff2Error> {
+    let mut entries = Vec::new();
+    let mut operands = Vec::new();
+    let mut start = 0;
+    for token in dict::tokens_with_ranges(data) {
+        let (token, range) = token?;
+        match token {
+            DictToken::Operand(number) => operands.push(number_value(number)),
+            DictToken::Operator(Operator::Blend) => (),
+            DictToken::Operator(operator) => {
+                entries.push(DictEntry {
+                    operator,
+                    operands: std::mem::take(&mut operands),
+                    range: start..range.end,
+                });
+                start = range.end;
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_charstring_token(data: &[u8], pos: usize) -> Result<Token, Cff2Error> {
+    let (token, len) = charstring::read_token(data.get(pos..).unwrap_or_default())?;
+    let kind = match token {
+        CharstringToken::Operand(number) => TokenKind::Number(number_value(number)),
+        CharstringToken::Operator(op) => TokenKind::Operator(op),
+    };
+    Ok(Token {
+        kind,
+        range: pos..pos + len,
+    })
+}
+
+/// The length of a DICT operand written with [`encode_fixed_dict_operand`].
+const FIXED_OPERAND_LEN: usize = 5;
+
+/// Encode an offset or length in a DICT, always using five bytes.
+fn encode_fixed_dict_operand(value: usize, out: &mut Vec<u8>) {
+    out.push(29);
+    out.extend((value as i32).to_be_bytes());
+}
+
+fn encode_dict_operator(operator: u16, out: &mut Vec<u8>) {
+    encode_operator(operator, out)
+}
+
+fn encode_operator(operator: u16, out: &mut Vec<u8>) {
+    if operator >> 8 == ESCAPE as u16 {
+        out.extend([ESCAPE, operator as u8]);
+    } else {
+        out.push(operator as u8);
+    }
+}
+
+/// Encode an integer using the shortest of the encodings shared by
+/// charstrings and DICTs, if one applies.
+fn encode_short_int(value: i32, out: &mut Vec<u8>) -> bool {
+    match value {
+        -107..=107 => out.push((value + 139) as u8),
+        108..=1131 => {
+            let value = value - 108;
+            out.extend([(value >> 8) as u8 + 247, value as u8]);
+        }
+        -1131..=-108 => {
+            let value = -value - 108;
+            out.extend([(value >> 8) as u8 + 251, value as u8]);
+        }
+        -32768..=32767 => {
+            out.push(28);
+            out.extend((value as i16).to_be_bytes());
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Encode an integer in a charstring.
+///
+/// Charstrings have no 32-bit integers, and a 16.16 fixed value has the same
+/// range as the 16-bit encoding, so larger values can't be written.
+fn encode_charstring_operand(value: i32, out: &mut Vec<u8>) -> Result<(), Cff2Error> {
+    if encode_short_int(value, out) {
+        Ok(())
+    } else {
+        Err(Cff2Error::CharstringOperandOutOfRange(value))
+    }
+}
+
+fn encode_dict_operand(value: i32, out: &mut Vec<u8>) -> Result<(), Cff2Error> {
+    if !encode_short_int(value, out) {
+        out.push(29);
+        out.extend(value.to_be_bytes());
+    }
+    Ok(())
+}
+
+/// The size of an INDEX containing objects with the given lengths.
+fn index_len(lens: &[usize]) -> usize {
+    if lens.is_empty() {
+        return 4;
+    }
+    let data_len: usize = lens.iter().sum();
+    4 + 1 + (lens.len() + 1) * offset_size(data_len + 1) + data_len
+}
+
+fn offset_size(max_offset: usize) -> usize {
+    match max_offset {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x10000..=0xFFFFFF => 3,
+        _ => 4,
+    }
+}
+
+/// Write a CFF2 INDEX.
+fn write_index(objects: &[Vec<u8>]) -> Vec<u8> {
+    let lens = objects.iter().map(Vec::len).collect::<Vec<_>>();
+    let mut out = Vec::with_capacity(index_len(&lens));
+    out.extend((objects.len() as u32).to_be_bytes());
+    if objects.is_empty() {
+        return out;
+    }
+    let off_size = offset_size(lens.iter().sum::<usize>() + 1);
+    out.push(off_size as u8);
+    let mut offset = 1usize;
+    for len in std::iter::once(0).chain(lens) {
+        offset += len;
+        out.extend_from_slice(&(offset as u32).to_be_bytes()[4 - off_size..]);
+    }
+    for object in objects {
+        out.extend_from_slice(object);
+    }
+    out
+}
+
+/// Write an FDSelect in format 3, or format 4 if there are too many font
+/// DICTs for format 3.
+fn write_fd_select(fds: impl Iterator<Item = u16>, fd_count: usize) -> Vec<u8> {
+    let mut ranges: Vec<(u32, u16)> = Vec::new();
+    let mut glyph_count = 0;
+    for (gid, fd) in fds.enumerate() {
+        if ranges.last().map(|(_, last)| *last) != Some(fd) {
+            ranges.push((gid as u32, fd));
+        }
+        glyph_count = gid as u32 + 1;
+    }
+    let mut out = Vec::new();
+    if fd_count <= 256 {
+        out.push(3);
+        out.extend((ranges.len() as u16).to_be_bytes());
+        for (first, fd) in ranges {
+            out.extend((first as u16).to_be_bytes());
+            out.push(fd as u8);
+        }
+        out.extend((glyph_count as u16).to_be_bytes());
+    } else {
+        out.push(4);
+        out.extend((ranges.len() as u32).to_be_bytes());
+        for (first, fd) in ranges {
+            out.extend(first.to_be_bytes());
+            out.extend(fd.to_be_bytes());
+        }
+        out.extend(glyph_count.to_be_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::subset_font;
+    use int_set::IntSet;
+    use skrifa::{
+        instance::{LocationRef, Size},
+        outline::{DrawSettings, OutlinePen},
+        MetadataProvider,
+    };
+    use write_fonts::{
+        read::{FontRef, TableProvider},
+        types::Tag,
+    };
+
+    /// Records the outline commands and their coordinates.
+    #[derive(Default, Debug, PartialEq)]
+    struct RecordingPen(Vec<(char, Vec<f32>)>);
+
+    impl OutlinePen for RecordingPen {
+        fn move_to(&mut self, x: f32, y: f32) {
+            self.0.push(('M', vec![x, y]));
+        }
+
+        fn line_to(&mut self, x: f32, y: f32) {
+            self.0.push(('L', vec![x, y]));
+        }
+
+        fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+            self.0.push(('Q', vec![cx0, cy0, x, y]));
+        }
+
+        fn curve_to(&mut self, cx0: f32, cy0: f32, cx1: f32, cy1: f32, x: f32, y: f32) {
+            self.0.push(('C', vec![cx0, cy0, cx1, cy1, x, y]));
+        }
+
+        fn close(&mut self) {
+            self.0.push(('Z', vec![]));
+        }
+    }
+
+    fn draw(font: &FontRef, gid: u32, location: LocationRef) -> RecordingPen {
+        let mut pen = RecordingPen::default();
+        font.outline_glyphs()
+            .get(GlyphId::new(gid))
+            .unwrap()
+            .draw(DrawSettings::unhinted(Size::unscaled(), location), &mut pen)
+            .unwrap();
+        pen
+    }
+
+    fn subset(
+        gids: &[u32],
+        configure: impl Fn(crate::PlanBuilder) -> crate::PlanBuilder,
+    ) -> Vec<u8> {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let gids: IntSet<u32> = gids.iter().copied().collect();
+        let plan = configure(Plan::builder(&font).with_gids(&gids))
+            .build()
+            .unwrap();
+        subset_font(font, &plan).unwrap()
+    }
+
+    fn local_subr_count(cff2_data: &[u8]) -> u32 {
+        let cff2 = Cff2::read(FontData::new(cff2_data)).unwrap();
+        let top_dict = TopDict::parse(cff2.top_dict_data()).unwrap();
+        let fd_array = Index::new(&cff2_data[top_dict.fd_array.unwrap()..], true).unwrap();
+        let region_counts = top_dict
+            .var_store
+            .map(|offset| {
+                VarStore::read(cff2_data, offset)
+                    .unwrap()
+                    .region_counts()
+                    .unwrap()
+            })
+            .unwrap_or_default();
+        let font_dict =
+            FontDict::parse(cff2_data, fd_array.get(0).unwrap(), &region_counts).unwrap();
+        font_dict
+            .local_subrs
+            .map(|subrs| subrs.count())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn subset_glyphs_and_subroutines() {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let bytes = subset(&[3, 5], |builder| builder);
+        let subset = FontRef::new(&bytes).unwrap();
+        let cff2 = subset.cff2().unwrap();
+        let top_dict = TopDict::parse(cff2.top_dict_data()).unwrap();
+        let charstrings =
+            Index::new(&cff2.offset_data().as_bytes()[top_dict.charstrings..], true).unwrap();
+        assert_eq!(charstrings.count(), 3);
+        // subroutines that are not called by the retained glyphs are dropped
+        let font_cff2 = font.cff2().unwrap();
+        assert_eq!(local_subr_count(font_cff2.offset_data().as_bytes()), 31);
+        assert_eq!(local_subr_count(cff2.offset_data().as_bytes()), 17);
+
+        for (new_gid, old_gid) in [(0, 0), (1, 3), (2, 5)] {
+            for coords in [[-1.0], [0.0], [0.5], [1.0]] {
+                let coords = coords.map(F2Dot14::from_f32);
+                assert_eq!(
+                    draw(&subset, new_gid, LocationRef::new(&coords)),
+                    draw(&font, old_gid, LocationRef::new(&coords)),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn subset_table_bytes() {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let gids = [3, 5].into_iter().collect();
+        let plan = Plan::builder(&font).with_gids(&gids).build().unwrap();
+        let cff2_bytes = font.data_for_tag(Cff2::TAG).unwrap();
+        let table = subset_cff2(cff2_bytes.as_bytes(), &plan).unwrap();
+        // the same table is written when subsetting the font
+        let subset = subset_font(font.clone(), &plan).unwrap();
+        let subset = FontRef::new(&subset).unwrap();
+        assert_eq!(subset.data_for_tag(Cff2::TAG).unwrap().as_bytes(), table);
+
+        assert!(subset_cff2(&cff2_bytes.as_bytes()[..10], &plan).is_err());
+    }
+
+    #[test]
+    fn blend_out_of_charstring_range() {
+        let blend = InstancedBlend {
+            defaults: vec![100.0, 40000.0],
+            deltas: Vec::new(),
+        };
+        let mut out = Vec::new();
+        assert!(matches!(
+            blend.write(BLEND, encode_charstring_operand, &mut out),
+            Err(Cff2Error::CharstringOperandOutOfRange(40000))
+        ));
+        // DICTs can hold 32-bit integers
+        out.clear();
+        blend
+            .write(DICT_BLEND, encode_dict_operand, &mut out)
+            .unwrap();
+        assert_eq!(out, [239, 29, 0, 0, 0x9C, 0x40]);
+    }
+
+    #[test]
+    fn invalid_vsindex() {
+        let region_counts = [2, 3];
+        assert_eq!(to_vsindex(1.0, &region_counts).unwrap(), 1);
+        for value in [-1.0, 0.5, 2.0, 70000.0] {
+            assert!(matches!(
+                to_vsindex(value, &region_counts),
+                Err(Cff2Error::Malformed("invalid vsindex"))
+            ));
+        }
+    }
+
+    #[test]
+    fn call_without_target() {
+        let routines = HashMap::new();
+        let renumbering = Renumbering {
+            routines: &routines,
+            global: SubrMap::new(&BTreeSet::new()),
+            local: BTreeMap::new(),
+        };
+        assert!(matches!(
+            renumbering.call_operand(&BTreeSet::new()),
+            Err(Cff2Error::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn subset_with_retain_gids() {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let bytes = subset(&[4], |builder| builder.retain_gids(true));
+        let subset = FontRef::new(&bytes).unwrap();
+        let cff2 = subset.cff2().unwrap();
+        let top_dict = TopDict::parse(cff2.top_dict_data()).unwrap();
+        let charstrings =
+            Index::new(&cff2.offset_data().as_bytes()[top_dict.charstrings..], true).unwrap();
+        assert_eq!(charstrings.count(), 5);
+        for gid in 1..4 {
+            assert!(charstrings.get(gid).unwrap().is_empty());
+        }
+        assert_eq!(
+            draw(&subset, 4, LocationRef::default()),
+            draw(&font, 4, LocationRef::default())
+        );
+    }
+
+    #[test]
+    fn subset_with_pinned_axis() {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let wght = font.axes().get(0).unwrap();
+        assert_eq!(wght.tag(), Tag::new(b"wght"));
+        let value = (wght.default_value() + wght.max_value()) / 2.0;
+        let bytes = subset(&[2, 3], |builder| builder.pin_axis(wght.tag(), value));
+        let subset = FontRef::new(&bytes).unwrap();
+        let cff2 = subset.cff2().unwrap();
+        // the only axis is pinned, so the variation store is dropped
+        let top_dict = TopDict::parse(cff2.top_dict_data()).unwrap();
+        assert!(top_dict.var_store.is_none());
+
+        let location = font.axes().location([(wght.tag(), value)]);
+        for (new_gid, old_gid) in [(1, 2), (2, 3)] {
+            let expected = draw(&font, old_gid, (&location).into());
+            let actual = draw(&subset, new_gid, LocationRef::default());
+            assert_eq!(expected.0.len(), actual.0.len());
+            for ((op_a, coords_a), (op_b, coords_b)) in expected.0.iter().zip(&actual.0) {
+                assert_eq!(op_a, op_b);
+                for (a, b) in coords_a.iter().zip(coords_b) {
+                    // blended values are rounded
+                    assert!((a - b).abs() <= 1.0, "{a} != {b}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! instance fvar table
+
+use crate::{Plan, SubsetError};
+use write_fonts::{
+    from_obj::FromTableRef,
+    read::{FontRef, TableProvider, TopLevelTable},
+    tables::fvar::Fvar,
+};
+
+/// Instance the fvar table according to the plan.
+///
+/// Pinned axes are removed, along with the named instances that are not at
+/// the pinned location. Returns `None` if the table can be dropped, which is
+/// the case when every axis is pinned.
+pub fn subset_fvar(font: &FontRef, plan: &Plan) -> Result<Option<Vec<u8>>, SubsetError> {
+    let fvar = font
+        .fvar()
+        .map_err(|e| SubsetError::table_subset_failed(Fvar::TAG, e))?;
+    let mut fvar = Fvar::from_table_ref(&fvar);
+    let pinned = plan.pinned_user_location(font);
+
+    let arrays = &mut *fvar.axis_instance_arrays;
+    let keep_axis: Vec<_> = arrays
+        .axes
+        .iter()
+        .map(|axis| !pinned.contains_key(&axis.axis_tag))
+        .collect();
+    if !keep_axis.contains(&true) {
+        return Ok(None);
+    }
+    let axes = &arrays.axes;
+    arrays.instances.retain(|instance| {
+        axes.iter().zip(&instance.coordinates).all(|(axis, coord)| {
+            pinned
+                .get(&axis.axis_tag)
+                .is_none_or(|value| value == coord)
+        })
+    });
+    for instance in arrays.instances.iter_mut() {
+        let mut keep = keep_axis.iter();
+        instance
+            .coordinates
+            .retain(|_| keep.next().copied().unwrap_or_default());
+    }
+    let mut keep = keep_axis.iter();
+    arrays
+        .axes
+        .retain(|_| keep.next().copied().unwrap_or_default());
+
+    write_fonts::dump_table(&fvar)
+        .map(Some)
+        .map_err(|e| SubsetError::table_subset_failed(Fvar::TAG, e))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use write_fonts::types::Tag;
+
+    #[test]
+    fn subset_fvar_all_axes_pinned() {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let mut plan = Plan::default();
+        plan.axes_location.insert(Tag::new(b"wght"), 800.0);
+        assert!(subset_fvar(&font, &plan).unwrap().is_none());
+    }
+}
@@ -3,46 +3,136 @@
 use crate::{Plan, Subset, SubsetError};
 use write_fonts::read::TopLevelTable;
 use write_fonts::tables::{hmtx::Hmtx, hmtx::LongMetric};
+use write_fonts::types::Tag;
 
 impl Subset for Hmtx {
     fn subset(&mut self, plan: &Plan) -> Result<bool, SubsetError> {
-        let gids = &plan.glyphset;
-        if gids.is_empty() {
-            return Err(SubsetError::SubsetTableError(Hmtx::TAG));
-        }
+        let (metrics, side_bearings) = subset_long_metrics(
+            Hmtx::TAG,
+            &self.h_metrics,
+            &self.left_side_bearings,
+            plan.num_h_metrics,
+            plan,
+        )?;
+        self.h_metrics = metrics;
+        self.left_side_bearings = side_bearings;
+        Ok(true)
+    }
+}
 
-        let num_long_metrics = plan.num_h_metrics as usize;
-        let mut new_metrics = Vec::with_capacity(num_long_metrics);
-        let mut new_side_bearings = Vec::new();
-        for gid in gids.iter() {
-            let glyph_id = gid.to_u32() as usize;
-            let side_bearing =
-                get_gid_side_bearing(&self.h_metrics, &self.left_side_bearings, glyph_id);
-            if glyph_id < num_long_metrics {
-                let advance = get_gid_advance(&self.h_metrics, glyph_id);
-                new_metrics.push(LongMetric {
-                    advance,
-                    side_bearing,
-                });
-            } else {
-                new_side_bearings.push(side_bearing);
+/// Subset a list of long metrics followed by side bearings, as used by both
+/// hmtx and vmtx.
+///
+/// The first `num_long_metrics` output glyphs get a long metric, the others
+/// only a side bearing.
+pub(crate) fn subset_long_metrics(
+    tag: Tag,
+    metrics: &[LongMetric],
+    side_bearings: &[i16],
+    num_long_metrics: u16,
+    plan: &Plan,
+) -> Result<(Vec<LongMetric>, Vec<i16>), SubsetError> {
+    if plan.glyphset.is_empty() {
+        return Err(SubsetError::table_subset_failed(tag, "no glyphs to retain"));
+    }
+
+    let num_long_metrics = num_long_metrics as usize;
+    let mut new_metrics = Vec::with_capacity(num_long_metrics);
+    let mut new_side_bearings = Vec::new();
+    for (new_gid, old_gid) in plan.output_glyphs().enumerate() {
+        // glyphs that were dropped while retaining gids are left empty
+        let (advance, side_bearing) = match old_gid {
+            Some(gid) => {
+                let glyph_id = gid.to_u32() as usize;
+                (
+                    get_gid_advance(tag, metrics, glyph_id)?,
+                    get_gid_side_bearing(tag, metrics, side_bearings, glyph_id)?,
+                )
             }
+            None => (0, 0),
+        };
+        if new_gid < num_long_metrics {
+            new_metrics.push(LongMetric {
+                advance,
+                side_bearing,
+            });
+        } else {
+            new_side_bearings.push(side_bearing);
         }
-
-        self.h_metrics = new_metrics;
-        self.left_side_bearings = new_side_bearings;
-
-        Ok(true)
     }
+    Ok((new_metrics, new_side_bearings))
 }
 
-fn get_gid_advance(metrics: &[LongMetric], gid: usize) -> u16 {
-    metrics.get(gid).or_else(|| metrics.last()).unwrap().advance
+/// Give every glyph a long metric, so that advances can be changed per glyph.
+///
+/// Glyphs that only had a side bearing take the advance of the last long metric.
+pub(crate) fn expand_long_metrics(metrics: &mut Vec<LongMetric>, side_bearings: &mut Vec<i16>) {
+    let last_advance = metrics.last().map(|m| m.advance).unwrap_or_default();
+    metrics.extend(
+        std::mem::take(side_bearings)
+            .into_iter()
+            .map(|side_bearing| LongMetric {
+                advance: last_advance,
+                side_bearing,
+            }),
+    );
 }
 
-fn get_gid_side_bearing(metrics: &[LongMetric], side_bearings: &[i16], gid: usize) -> i16 {
+fn get_gid_advance(tag: Tag, metrics: &[LongMetric], gid: usize) -> Result<u16, SubsetError> {
+    metrics
+        .get(gid)
+        .or_else(|| metrics.last())
+        .map(|metric| metric.advance)
+        .ok_or_else(|| SubsetError::table_subset_failed(tag, "no long metrics"))
+}
+
+fn get_gid_side_bearing(
+    tag: Tag,
+    metrics: &[LongMetric],
+    side_bearings: &[i16],
+    gid: usize,
+) -> Result<i16, SubsetError> {
     match metrics.get(gid) {
-        Some(long_metric) => long_metric.side_bearing,
-        None => *side_bearings.get(gid - metrics.len()).unwrap(),
+        Some(long_metric) => Ok(long_metric.side_bearing),
+        None => side_bearings
+            .get(gid - metrics.len())
+            .copied()
+            .ok_or_else(|| {
+                SubsetError::table_subset_failed(tag, format!("no metrics for glyph {gid}"))
+            }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use write_fonts::types::GlyphId;
+
+    #[test]
+    fn subset_truncated_hmtx() {
+        let mut plan = Plan::default();
+        plan.glyphset.extend([0, 3].into_iter().map(GlyphId::new));
+        plan.num_output_glyphs = 2;
+        plan.num_h_metrics = 1;
+
+        // glyph 3 has neither a long metric nor a side bearing
+        let metric = LongMetric {
+            advance: 500,
+            side_bearing: 10,
+        };
+        let mut hmtx = Hmtx::new(vec![metric], vec![20]);
+        let err = hmtx.subset(&plan).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Subsetting table 'hmtx' failed: no metrics for glyph 3"
+        );
+
+        // ...and without any long metrics there is no advance
+        let mut hmtx = Hmtx::new(vec![], vec![10, 20, 30, 40]);
+        let err = hmtx.subset(&plan).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Subsetting table 'hmtx' failed: no long metrics"
+        );
     }
 }
@@ -0,0 +1,143 @@
+//! subset HVAR table
+
+use crate::hmtx::expand_long_metrics;
+use crate::variations::{build_delta_set_index_map, default_coords, VarStoreSubsetter};
+use crate::{Plan, SubsetError};
+use write_fonts::{
+    read::{tables::hvar::Hvar as ReadHvar, FontRef, ReadError, TableProvider, TopLevelTable},
+    tables::{hmtx::Hmtx, hvar::Hvar},
+    types::{F2Dot14, GlyphId, MajorMinor},
+};
+
+/// Subset the HVAR table according to the plan.
+///
+/// Delta sets are remapped to the new glyph order, unreferenced delta sets
+/// are dropped and any axes pinned in the plan are removed from the variation
+/// regions. Deltas at the pinned location are baked into the default advance
+/// widths by [`instance_advances`] when hmtx is subset.
+pub fn subset_hvar(font: &FontRef, plan: &Plan) -> Result<Vec<u8>, SubsetError> {
+    let hvar = font
+        .hvar()
+        .map_err(|e| SubsetError::table_subset_failed(Hvar::TAG, e))?;
+    let location = plan.pinned_location(font);
+    let hvar = subset_hvar_impl(&hvar, plan, &location)
+        .map_err(|e| SubsetError::table_subset_failed(Hvar::TAG, e))?;
+    write_fonts::dump_table(&hvar).map_err(|e| SubsetError::table_subset_failed(Hvar::TAG, e))
+}
+
+fn subset_hvar_impl(
+    hvar: &ReadHvar,
+    plan: &Plan,
+    location: &[Option<F2Dot14>],
+) -> Result<Hvar, ReadError> {
+    let mut store = VarStoreSubsetter::new(hvar.item_variation_store()?, location)?;
+
+    let advance_mapping = hvar.advance_width_mapping().transpose()?;
+    let advances = store.add_glyph_delta_sets(advance_mapping.as_ref(), plan)?;
+    let lsbs = match hvar.lsb_mapping().transpose()? {
+        Some(mapping) => Some(store.add_glyph_delta_sets(Some(&mapping), plan)?),
+        None => None,
+    };
+    let rsbs = match hvar.rsb_mapping().transpose()? {
+        Some(mapping) => Some(store.add_glyph_delta_sets(Some(&mapping), plan)?),
+        None => None,
+    };
+
+    let (store, remapping) = store.build();
+    Ok(Hvar::new(
+        MajorMinor::VERSION_1_0,
+        store,
+        Some(build_delta_set_index_map(&advances, &remapping)),
+        lsbs.map(|ids| build_delta_set_index_map(&ids, &remapping)),
+        rsbs.map(|ids| build_delta_set_index_map(&ids, &remapping)),
+    ))
+}
+
+/// Add the HVAR advance deltas at the pinned location to the default advance widths.
+///
+/// Since advances can diverge once deltas are applied, every glyph is given a
+/// long metric; the number of long metrics is recomputed when subsetting.
+/// This is a no-op if no axes are pinned or the font has no HVAR table.
+pub(crate) fn instance_advances(
+    font: &FontRef,
+    location: &[Option<F2Dot14>],
+    hmtx: &mut Hmtx,
+) -> Result<(), ReadError> {
+    if location.iter().all(Option::is_none) {
+        return Ok(());
+    }
+    let Ok(hvar) = font.hvar() else {
+        return Ok(());
+    };
+    let coords = default_coords(location);
+
+    expand_long_metrics(&mut hmtx.h_metrics, &mut hmtx.left_side_bearings);
+    for (gid, metric) in hmtx.h_metrics.iter_mut().enumerate() {
+        let delta = hvar
+            .advance_width_delta(GlyphId::new(gid as u32), &coords)?
+            .to_i32();
+        metric.advance = (metric.advance as i32 + delta).clamp(0, u16::MAX as i32) as u16;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use write_fonts::{from_obj::FromTableRef, read::FontRead, types::Tag};
+
+    #[test]
+    fn subset_hvar_remaps_glyphs() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let mut plan = Plan::default();
+        plan.glyphset.insert(GlyphId::NOTDEF);
+        plan.glyphset.insert(GlyphId::new(2));
+        plan.glyphset.insert(GlyphId::new(3));
+        plan.num_output_glyphs = 3;
+
+        let bytes = subset_hvar(&font, &plan).unwrap();
+        let subset = ReadHvar::read(bytes.as_slice().into()).unwrap();
+        let hvar = font.hvar().unwrap();
+
+        for (new_gid, old_gid) in plan.glyphset.iter().enumerate() {
+            for coord in [-1.0, -0.5, 0.5, 1.0] {
+                let coords = [F2Dot14::from_f32(coord)];
+                assert_eq!(
+                    subset
+                        .advance_width_delta(GlyphId::new(new_gid as u32), &coords)
+                        .unwrap(),
+                    hvar.advance_width_delta(old_gid, &coords).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn subset_hvar_with_pinned_axis() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let mut plan = Plan::default();
+        plan.glyphset.insert(GlyphId::NOTDEF);
+        plan.glyphset.insert(GlyphId::new(1));
+        plan.num_output_glyphs = 2;
+        plan.axes_location.insert(Tag::new(b"wght"), 900.0);
+
+        let bytes = subset_hvar(&font, &plan).unwrap();
+        let subset = ReadHvar::read(bytes.as_slice().into()).unwrap();
+        // the only axis is pinned, so no variation regions remain
+        let store = subset.item_variation_store().unwrap();
+        assert_eq!(store.variation_region_list().unwrap().axis_count(), 0);
+
+        // ...and the deltas at the pinned location end up in the advances
+        let location = plan.pinned_location(&font);
+        let mut hmtx = Hmtx::from_table_ref(&font.hmtx().unwrap());
+        let default_advance = hmtx.h_metrics[1].advance as i32;
+        instance_advances(&font, &location, &mut hmtx).unwrap();
+        let delta = font
+            .hvar()
+            .unwrap()
+            .advance_width_delta(GlyphId::new(1), &[F2Dot14::from_f32(1.0)])
+            .unwrap()
+            .to_i32();
+        assert_eq!(hmtx.h_metrics[1].advance as i32, default_advance + delta);
+    }
+}
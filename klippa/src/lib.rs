@@ -1,25 +1,65 @@
 //! try to define Subset trait so I can add methods for Hmtx
 //! TODO: make it generic for all tables
+mod avar;
+mod cff2;
+mod fvar;
 mod hhea;
 mod hmtx;
+mod hvar;
 mod maxp;
+mod mvar;
 mod parsing_util;
-pub use parsing_util::{parse_unicodes, populate_gids};
+mod plan_builder;
+mod stat;
+mod unicode_blocks;
+mod variations;
+mod vhea;
+mod vmtx;
+mod vvar;
+pub use avar::subset_avar;
+pub use cff2::subset_cff2;
+pub use fvar::subset_fvar;
+pub use hvar::subset_hvar;
+pub use mvar::subset_mvar;
+pub use parsing_util::{
+    parse_range_list, parse_unicodes, populate_gids, GidParseError, ParseError, UnicodeParseError,
+};
+pub use plan_builder::PlanBuilder;
+pub use stat::subset_stat;
+pub use unicode_blocks::expand_unicode_block;
+pub use vvar::subset_vvar;
 
 use int_set::IntSet;
 use skrifa::MetadataProvider;
-use std::collections::{BTreeSet, HashMap};
-use std::path::PathBuf;
+use std::collections::HashMap;
 use thiserror::Error;
 use write_fonts::read::{
+    tables::cff2::Cff2,
+    tables::cmap::Cmap,
     tables::glyf::{Glyf, Glyph},
     tables::loca::Loca,
-    FontRef, TableProvider, TopLevelTable,
+    FontRef, ReadError, TableProvider, TopLevelTable,
 };
 use write_fonts::types::GlyphId;
-use write_fonts::types::Tag;
+use write_fonts::types::{F2Dot14, Fixed, Tag};
 use write_fonts::{
-    from_obj::FromTableRef, tables::hhea::Hhea, tables::hmtx::Hmtx, tables::maxp::Maxp, FontBuilder,
+    from_obj::FromTableRef,
+    tables::{
+        avar::Avar,
+        fvar::Fvar,
+        hhea::Hhea,
+        hmtx::{Hmtx, LongMetric},
+        hvar::Hvar,
+        maxp::Maxp,
+        mvar::Mvar,
+        os2::Os2,
+        post::Post,
+        stat::Stat,
+        vhea::Vhea,
+        vmtx::Vmtx,
+        vvar::Vvar,
+    },
+    FontBuilder,
 };
 
 const MAX_COMPOSITE_OPERATIONS_PER_GLYPH: u8 = 64;
@@ -28,8 +68,16 @@ const MAX_NESTING_LEVEL: u8 = 64;
 // this causes tests to fail with 'subtract with overflow error'.
 // See <https://github.com/googlefonts/fontations/issues/997>
 const MAX_GID: GlyphId = GlyphId::new(0xFFFFFF);
+const HINTING_TABLES: [Tag; 7] = [
+    Tag::new(b"cvar"),
+    Tag::new(b"cvt "),
+    Tag::new(b"fpgm"),
+    Tag::new(b"hdmx"),
+    Tag::new(b"LTSH"),
+    Tag::new(b"prep"),
+    Tag::new(b"VDMX"),
+];
 
-#[allow(dead_code)]
 #[derive(Default)]
 pub struct Plan {
     unicodes: IntSet<u32>,
@@ -37,29 +85,153 @@ pub struct Plan {
     glyphset_colred: IntSet<GlyphId>,
     glyphset: IntSet<GlyphId>,
     num_h_metrics: u16,
+    num_v_metrics: u16,
     num_output_glyphs: u16,
     font_num_glyphs: usize,
     unicode_to_new_gid_list: Vec<(u32, GlyphId)>,
     codepoint_to_glyph: HashMap<u32, GlyphId>,
+    /// user space values for axes that should be pinned
+    axes_location: HashMap<Tag, f32>,
+    /// normalized coordinate of each axis of the font, or `None` if the axis
+    /// is not pinned
+    normalized_location: Vec<Option<F2Dot14>>,
+    /// GSUB/GPOS features to retain
+    layout_features: Vec<Tag>,
+    /// keep the original glyph ids, leaving dropped glyphs empty
+    retain_gids: bool,
+    /// remove hinting tables from the subset font
+    drop_hinting: bool,
+    /// requested codepoints that the font does not map to a glyph
+    unmapped_unicodes: IntSet<u32>,
 }
 
 impl Plan {
-    pub fn new(input_gids: &IntSet<GlyphId>, input_unicodes: &IntSet<u32>, font: &FontRef) -> Self {
-        let mut this = Plan {
-            font_num_glyphs: get_font_num_glyphs(font),
-            ..Default::default()
-        };
+    /// Start building a subset plan for `font`.
+    pub fn builder<'a>(font: &FontRef<'a>) -> PlanBuilder<'a> {
+        PlanBuilder::new(font)
+    }
 
-        this.populate_unicodes_to_retain(input_gids, input_unicodes, font);
-        this.populate_gids_to_retain(font);
-        this.num_output_glyphs = this.glyphset.len() as u16;
+    /// The requested codepoints that the font does not map to a glyph.
+    ///
+    /// These are ignored when subsetting, unless the plan was built with
+    /// [`PlanBuilder::strict_unicodes`], in which case building fails instead.
+    pub fn unmapped_unicodes(&self) -> &IntSet<u32> {
+        &self.unmapped_unicodes
+    }
+
+    /// The GSUB/GPOS features requested with [`PlanBuilder::preserve_features`].
+    pub fn layout_features(&self) -> &[Tag] {
+        &self.layout_features
+    }
+
+    /// Compute the glyphs to retain, and everything that depends on them.
+    fn populate(
+        &mut self,
+        input_gids: &IntSet<GlyphId>,
+        input_unicodes: &IntSet<u32>,
+        font: &FontRef,
+    ) -> Result<(), PlanError> {
+        self.font_num_glyphs = get_font_num_glyphs(font)?;
+
+        self.populate_unicodes_to_retain(input_gids, input_unicodes, font);
+        self.populate_gids_to_retain(font)?;
+        self.num_output_glyphs = self.compute_num_output_glyphs()?;
+
+        self.normalized_location = self.pinned_location(font);
 
         // compute new h_metrics
-        let hmtx = font.hmtx().expect("Error reading hmtx table");
-        let hmtx = Hmtx::from_table_ref(&hmtx);
-        this.num_h_metrics = compute_new_num_h_metrics(&hmtx, &this.glyphset);
+        let location = &self.normalized_location;
+        let hmtx = font
+            .hmtx()
+            .map_err(|e| PlanError::TableReadError(Hmtx::TAG, e))?;
+        let mut hmtx = Hmtx::from_table_ref(&hmtx);
+        hvar::instance_advances(font, location, &mut hmtx)
+            .map_err(|e| PlanError::TableReadError(Hvar::TAG, e))?;
+        self.num_h_metrics = compute_num_long_metrics(&hmtx.h_metrics, self.output_glyphs());
+
+        // ...and v_metrics, if the font has vertical metrics
+        if let Ok(vmtx) = font.vmtx() {
+            let mut vmtx = Vmtx::from_table_ref(&vmtx);
+            vvar::instance_advances(font, location, &mut vmtx)
+                .map_err(|e| PlanError::TableReadError(Vvar::TAG, e))?;
+            self.num_v_metrics = compute_num_long_metrics(&vmtx.v_metrics, self.output_glyphs());
+        }
+        Ok(())
+    }
+
+    /// The number of glyphs in the subset font, which must fit in a u16.
+    fn compute_num_output_glyphs(&self) -> Result<u16, PlanError> {
+        let num_glyphs = if self.retain_gids {
+            self.glyphset
+                .last()
+                .map(|gid| gid.to_u32() as usize + 1)
+                .unwrap_or_default()
+        } else {
+            self.glyphset.len()
+        };
+        u16::try_from(num_glyphs).map_err(|_| PlanError::TooManyGlyphs(num_glyphs))
+    }
 
-        this
+    /// Iterate over the glyphs of the subset font in new glyph id order, yielding
+    /// the glyph id each one had in the original font.
+    ///
+    /// If glyph ids are retained then glyphs that were dropped are `None`.
+    pub(crate) fn output_glyphs(&self) -> impl Iterator<Item = Option<GlyphId>> + '_ {
+        let retain_gids = self.retain_gids;
+        let mut glyphs = self.glyphset.iter().peekable();
+        (0..self.num_output_glyphs as u32).map(move |new_gid| {
+            if retain_gids {
+                glyphs.next_if_eq(&GlyphId::new(new_gid))
+            } else {
+                glyphs.next()
+            }
+        })
+    }
+
+    /// Create a plan directly from raw font bytes.
+    ///
+    /// This is a convenience for callers that only have access to the font
+    /// data (for example across a WASM boundary) and cannot easily hold a
+    /// [`FontRef`].
+    pub fn from_unicodes_and_gids(
+        unicodes: &[u32],
+        gids: &[u16],
+        font_bytes: &[u8],
+    ) -> Result<Self, SubsetError> {
+        let font = FontRef::new(font_bytes).map_err(SubsetError::InvalidFontBytes)?;
+        let input_unicodes = unicodes.iter().copied().collect::<IntSet<u32>>();
+        let input_gids = gids.iter().map(|gid| *gid as u32).collect::<IntSet<u32>>();
+        Ok(Plan::builder(&font)
+            .with_gids(&input_gids)
+            .with_unicodes(&input_unicodes)
+            .build()?)
+    }
+
+    /// Returns the normalized coordinate of each axis in the font, or `None`
+    /// if the axis is not pinned.
+    pub(crate) fn pinned_location(&self, font: &FontRef) -> Vec<Option<F2Dot14>> {
+        let axes = font.axes();
+        let location = axes.location(self.axes_location.iter().map(|(tag, value)| (*tag, *value)));
+        axes.iter()
+            .zip(location.coords())
+            .map(|(axis, coord)| {
+                self.axes_location
+                    .contains_key(&axis.tag())
+                    .then_some(*coord)
+            })
+            .collect()
+    }
+
+    /// Returns the user space value of each pinned axis, clamped to the axis range.
+    pub(crate) fn pinned_user_location(&self, font: &FontRef) -> HashMap<Tag, Fixed> {
+        font.axes()
+            .iter()
+            .filter_map(|axis| {
+                let value = self.axes_location.get(&axis.tag())?;
+                let value = value.clamp(axis.min_value(), axis.max_value());
+                Some((axis.tag(), Fixed::from_f64(value as f64)))
+            })
+            .collect()
     }
 
     pub fn populate_unicodes_to_retain(
@@ -126,12 +298,14 @@ impl Plan {
             .extend(self.unicode_to_new_gid_list.iter().map(|t| t.0));
     }
 
-    pub fn populate_gids_to_retain(&mut self, font: &FontRef) {
+    pub fn populate_gids_to_retain(&mut self, font: &FontRef) -> Result<(), PlanError> {
         //not-def
         self.glyphset_gsub.insert(GlyphId::NOTDEF);
 
         //glyph closure for cmap
-        let cmap = font.cmap().expect("Error reading cmap table");
+        let cmap = font
+            .cmap()
+            .map_err(|e| PlanError::TableReadError(Cmap::TAG, e))?;
         cmap.closure_glyphs(&self.unicodes, &mut self.glyphset_gsub);
         remove_invalid_gids(&mut self.glyphset_gsub, self.font_num_glyphs);
 
@@ -142,21 +316,25 @@ impl Plan {
         remove_invalid_gids(&mut self.glyphset_colred, self.font_num_glyphs);
 
         /* Populate a full set of glyphs to retain by adding all referenced composite glyphs. */
-        let loca = font.loca(None).expect("Error reading loca table");
-        let glyf = font.glyf().expect("Error reading glyf table");
-        let operation_count =
-            self.glyphset_gsub.len() * (MAX_COMPOSITE_OPERATIONS_PER_GLYPH as usize);
-        for gid in self.glyphset_colred.iter() {
-            glyf_closure_glyphs(
-                &loca,
-                &glyf,
-                gid,
-                &mut self.glyphset,
-                operation_count as i32,
-                0,
-            );
+        // CFF2 outlines have no components, so there is nothing to add
+        if let (Ok(loca), Ok(glyf)) = (font.loca(None), font.glyf()) {
+            let operation_count =
+                self.glyphset_gsub.len() * (MAX_COMPOSITE_OPERATIONS_PER_GLYPH as usize);
+            for gid in self.glyphset_colred.iter() {
+                glyf_closure_glyphs(
+                    &loca,
+                    &glyf,
+                    gid,
+                    &mut self.glyphset,
+                    operation_count as i32,
+                    0,
+                );
+            }
+        } else {
+            self.glyphset.union(&self.glyphset_colred);
         }
         remove_invalid_gids(&mut self.glyphset, self.font_num_glyphs);
+        Ok(())
     }
 
     fn colr_closure(&mut self, font: &FontRef) {
@@ -227,58 +405,112 @@ fn remove_invalid_gids(gids: &mut IntSet<GlyphId>, num_glyphs: usize) {
     gids.remove_range(GlyphId::new(num_glyphs as u32)..=MAX_GID);
 }
 
-fn get_font_num_glyphs(font: &FontRef) -> usize {
-    let loca = font.loca(None).expect("Error reading loca table");
-    let ret = loca.len();
-
-    let maxp = font.maxp().expect("Error reading maxp table");
-    ret.max(maxp.num_glyphs() as usize)
+fn get_font_num_glyphs(font: &FontRef) -> Result<usize, PlanError> {
+    let maxp = font
+        .maxp()
+        .map_err(|e| PlanError::TableReadError(Maxp::TAG, e))?;
+    let num_glyphs = maxp.num_glyphs() as usize;
+    Ok(match font.loca(None) {
+        Ok(loca) => loca.len().max(num_glyphs),
+        Err(_) => num_glyphs,
+    })
 }
 
-fn compute_new_num_h_metrics(hmtx_table: &Hmtx, glyph_ids: &IntSet<GlyphId>) -> u16 {
-    let num_long_metrics = glyph_ids.len().min(0xFFFF);
-    //TODO: we still need a BTreeSet here because we currently don't have max() and Iterator::rev() for IntSet
-    let gids: BTreeSet<GlyphId> = glyph_ids.iter().collect();
-    let last_gid = gids.last().unwrap().to_u32() as usize;
-    let last_advance = hmtx_table
-        .h_metrics
-        .get(last_gid)
-        .or_else(|| hmtx_table.h_metrics.last())
-        .unwrap()
-        .advance;
-
-    let num_skippable_glyphs = gids
+/// Compute the number of long metrics needed in the subset hmtx or vmtx table.
+fn compute_num_long_metrics(
+    metrics: &[LongMetric],
+    output_glyphs: impl Iterator<Item = Option<GlyphId>>,
+) -> u16 {
+    let advances: Vec<u16> = output_glyphs
+        .map(|gid| {
+            gid.map(|gid| {
+                // a table without long metrics can't be subset, which is
+                // reported when subsetting it
+                metrics
+                    .get(gid.to_u32() as usize)
+                    .or_else(|| metrics.last())
+                    .map(|metric| metric.advance)
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default()
+        })
+        .collect();
+    let Some(last_advance) = advances.last() else {
+        return 1;
+    };
+    // the first glyph of the trailing run of equal advances still needs a
+    // long metric, which the glyphs after it take their advance from
+    let num_skippable_glyphs = advances
         .iter()
         .rev()
-        .take_while(|gid| {
-            hmtx_table
-                .h_metrics
-                .get(gid.to_u32() as usize)
-                .or_else(|| hmtx_table.h_metrics.last())
-                .unwrap()
-                .advance
-                == last_advance
-        })
-        .count();
-    (num_long_metrics - num_skippable_glyphs).max(1) as u16
+        .take_while(|advance| *advance == last_advance)
+        .count()
+        - 1;
+    (advances.len() - num_skippable_glyphs).clamp(1, 0xFFFF) as u16
 }
 
 #[derive(Debug, Error)]
 pub enum SubsetError {
-    #[error("Invalid input gid {0}")]
-    InvalidGid(String),
+    #[error(transparent)]
+    InvalidGids(#[from] GidParseError),
+
+    #[error(transparent)]
+    InvalidUnicodes(#[from] UnicodeParseError),
+
+    #[error("Subsetting table '{tag}' failed: {source}")]
+    TableSubsetFailed {
+        tag: Tag,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("Invalid font data: {0}")]
+    InvalidFontBytes(ReadError),
+
+    #[error(transparent)]
+    InvalidPlan(#[from] PlanError),
+
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+impl SubsetError {
+    /// Create an error for a table that could not be subset.
+    pub(crate) fn table_subset_failed(
+        tag: Tag,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        SubsetError::TableSubsetFailed {
+            tag,
+            source: source.into(),
+        }
+    }
+}
 
-    #[error("Invalid gid range {start}-{end}")]
-    InvalidGidRange { start: u32, end: u32 },
+#[derive(Debug, Error)]
+pub enum PlanError {
+    #[error("Error reading table '{0}': {1}")]
+    TableReadError(Tag, ReadError),
+
+    #[error("Font has no variation axis '{0}'")]
+    UnknownAxis(Tag),
 
-    #[error("Invalid input unicode {0}")]
-    InvalidUnicode(String),
+    #[error("Font has no glyph for {}", format_unicodes(.0))]
+    UnmappedUnicodes(IntSet<u32>),
 
-    #[error("Invalid unicode range {start}-{end}")]
-    InvalidUnicodeRange { start: u32, end: u32 },
+    #[error("Subset font would have {0} glyphs, but at most 65535 are supported")]
+    TooManyGlyphs(usize),
 
-    #[error("Subsetting table '{0}' failed")]
-    SubsetTableError(Tag),
+    #[error("Pinning axes is not supported for fonts with variations in '{0}'")]
+    UnsupportedInstancing(Tag),
+}
+
+/// Formats codepoints for error messages, e.g. "U+0041, U+1F600".
+fn format_unicodes(unicodes: &IntSet<u32>) -> String {
+    unicodes
+        .iter()
+        .map(|cp| format!("U+{cp:04X}"))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 pub trait Subset {
@@ -286,40 +518,169 @@ pub trait Subset {
     fn subset(&mut self, plan: &Plan) -> Result<bool, SubsetError>;
 }
 
-pub fn subset_font(font: FontRef, plan: &Plan, output_file: &PathBuf) {
-    let hmtx = font.hmtx().expect("Error reading hmtx table");
-    let mut hmtx = Hmtx::from_table_ref(&hmtx);
-    hmtx.subset(plan).expect("SUbsetting failed");
-    let hmtx_bytes = write_fonts::dump_table(&hmtx).unwrap();
+/// Subset the font according to the plan, returning the bytes of the new font.
+pub fn subset_font(font: FontRef, plan: &Plan) -> Result<Vec<u8>, SubsetError> {
+    let location = plan.pinned_location(&font);
+    let instancing = location.iter().any(Option::is_some);
+    // variation tables that no longer vary once all axes are pinned
+    let fully_instanced = !location.is_empty() && location.iter().all(Option::is_some);
+    let mut dropped_tables = Vec::new();
+    if plan.drop_hinting {
+        dropped_tables.extend(HINTING_TABLES);
+    }
+    let pinned_metrics = mvar::PinnedMetrics::new(&font, &location)?;
 
-    let hhea = font.hhea().expect("Error reading hhea table");
+    let hmtx = font
+        .hmtx()
+        .map_err(|e| SubsetError::table_subset_failed(Hmtx::TAG, e))?;
+    let mut hmtx = Hmtx::from_table_ref(&hmtx);
+    hvar::instance_advances(&font, &location, &mut hmtx)
+        .map_err(|e| SubsetError::table_subset_failed(Hvar::TAG, e))?;
+    hmtx.subset(plan)?;
+    let hmtx_bytes = write_fonts::dump_table(&hmtx)
+        .map_err(|e| SubsetError::table_subset_failed(Hmtx::TAG, e))?;
+
+    let hhea = font
+        .hhea()
+        .map_err(|e| SubsetError::table_subset_failed(Hhea::TAG, e))?;
     let mut hhea = Hhea::from_table_ref(&hhea);
-    hhea.subset(plan).expect("Subsetting failed");
-    let hhea_bytes = write_fonts::dump_table(&hhea).unwrap();
-
-    let maxp = font.maxp().expect("Error reading maxp table");
+    hhea.subset(plan)?;
+    pinned_metrics.apply_to_hhea(&mut hhea);
+    let hhea_bytes = write_fonts::dump_table(&hhea)
+        .map_err(|e| SubsetError::table_subset_failed(Hhea::TAG, e))?;
+
+    let maxp = font
+        .maxp()
+        .map_err(|e| SubsetError::table_subset_failed(Maxp::TAG, e))?;
     let mut maxp = Maxp::from_table_ref(&maxp);
-    maxp.subset(plan).expect("Subsetting failed");
-    let maxp_bytes = write_fonts::dump_table(&maxp).unwrap();
+    maxp.subset(plan)?;
+    let maxp_bytes = write_fonts::dump_table(&maxp)
+        .map_err(|e| SubsetError::table_subset_failed(Maxp::TAG, e))?;
 
     let mut builder = FontBuilder::default();
     builder.add_raw(Hmtx::TAG, hmtx_bytes);
     builder.add_raw(Hhea::TAG, hhea_bytes);
     builder.add_raw(Maxp::TAG, maxp_bytes);
 
-    builder.copy_missing_tables(font);
+    if font.data_for_tag(Vmtx::TAG).is_some() {
+        let vmtx = font
+            .vmtx()
+            .map_err(|e| SubsetError::table_subset_failed(Vmtx::TAG, e))?;
+        let mut vmtx = Vmtx::from_table_ref(&vmtx);
+        vvar::instance_advances(&font, &location, &mut vmtx)
+            .map_err(|e| SubsetError::table_subset_failed(Vvar::TAG, e))?;
+        vmtx.subset(plan)?;
+        let vmtx_bytes = write_fonts::dump_table(&vmtx)
+            .map_err(|e| SubsetError::table_subset_failed(Vmtx::TAG, e))?;
+
+        let vhea = font
+            .vhea()
+            .map_err(|e| SubsetError::table_subset_failed(Vhea::TAG, e))?;
+        let mut vhea = Vhea::from_table_ref(&vhea);
+        vhea.subset(plan)?;
+        pinned_metrics.apply_to_vhea(&mut vhea);
+        let vhea_bytes = write_fonts::dump_table(&vhea)
+            .map_err(|e| SubsetError::table_subset_failed(Vhea::TAG, e))?;
+
+        builder.add_raw(Vmtx::TAG, vmtx_bytes);
+        builder.add_raw(Vhea::TAG, vhea_bytes);
+    }
+
+    if let Some(cff2) = font.data_for_tag(Cff2::TAG) {
+        builder.add_raw(Cff2::TAG, subset_cff2(cff2.as_bytes(), plan)?);
+    }
+    if font.data_for_tag(Hvar::TAG).is_some() {
+        if fully_instanced {
+            dropped_tables.push(Hvar::TAG);
+        } else {
+            builder.add_raw(Hvar::TAG, subset_hvar(&font, plan)?);
+        }
+    }
+    if font.data_for_tag(Vvar::TAG).is_some() {
+        if fully_instanced {
+            dropped_tables.push(Vvar::TAG);
+        } else {
+            builder.add_raw(Vvar::TAG, subset_vvar(&font, plan)?);
+        }
+    }
 
-    std::fs::write(output_file, builder.build()).unwrap();
+    if instancing {
+        if font.data_for_tag(Fvar::TAG).is_some() {
+            match subset_fvar(&font, plan)? {
+                Some(fvar_bytes) => {
+                    builder.add_raw(Fvar::TAG, fvar_bytes);
+                }
+                None => dropped_tables.push(Fvar::TAG),
+            }
+        }
+        if font.data_for_tag(Avar::TAG).is_some() {
+            match subset_avar(&font, plan)? {
+                Some(avar_bytes) => {
+                    builder.add_raw(Avar::TAG, avar_bytes);
+                }
+                None => dropped_tables.push(Avar::TAG),
+            }
+        }
+        if font.data_for_tag(Stat::TAG).is_some() {
+            builder.add_raw(Stat::TAG, subset_stat(&font, plan)?);
+        }
+    }
+
+    if font.data_for_tag(Mvar::TAG).is_some() {
+        match subset_mvar(&font, plan)? {
+            Some(mvar_bytes) => {
+                builder.add_raw(Mvar::TAG, mvar_bytes);
+            }
+            None => dropped_tables.push(Mvar::TAG),
+        }
+    }
+    if !pinned_metrics.is_empty() {
+        if let Ok(os2) = font.os2() {
+            let mut os2 = Os2::from_table_ref(&os2);
+            pinned_metrics.apply_to_os2(&mut os2);
+            let os2_bytes = write_fonts::dump_table(&os2)
+                .map_err(|e| SubsetError::table_subset_failed(Os2::TAG, e))?;
+            builder.add_raw(Os2::TAG, os2_bytes);
+        }
+        if let Ok(post) = font.post() {
+            let mut post = Post::from_table_ref(&post);
+            pinned_metrics.apply_to_post(&mut post);
+            let post_bytes = write_fonts::dump_table(&post)
+                .map_err(|e| SubsetError::table_subset_failed(Post::TAG, e))?;
+            builder.add_raw(Post::TAG, post_bytes);
+        }
+    }
+
+    for record in font.table_directory.table_records() {
+        let tag = record.tag();
+        if builder.contains(tag) || dropped_tables.contains(&tag) {
+            continue;
+        }
+        if let Some(data) = font.data_for_tag(tag) {
+            builder.add_raw(tag, data);
+        }
+    }
+    Ok(builder.build())
+}
+
+/// Subset the font contained in `font_bytes` according to the plan.
+///
+/// This is equivalent to [`subset_font`], but takes the raw font data instead
+/// of a [`FontRef`].
+pub fn subset_font_bytes(font_bytes: &[u8], plan: &Plan) -> Result<Vec<u8>, SubsetError> {
+    let font = FontRef::new(font_bytes).map_err(SubsetError::InvalidFontBytes)?;
+    subset_font(font, plan)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use write_fonts::types::NameId;
     #[test]
     fn populate_unicodes_wo_input_gid() {
         let mut plan = Plan::default();
         let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
-        plan.font_num_glyphs = get_font_num_glyphs(&font);
+        plan.font_num_glyphs = get_font_num_glyphs(&font).unwrap();
 
         let input_gids = IntSet::empty();
         let mut input_unicodes = IntSet::empty();
@@ -355,7 +716,7 @@ mod test {
     fn populate_unicodes_w_input_gid() {
         let mut plan = Plan::default();
         let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
-        plan.font_num_glyphs = get_font_num_glyphs(&font);
+        plan.font_num_glyphs = get_font_num_glyphs(&font).unwrap();
 
         let mut input_gids = IntSet::empty();
         let input_unicodes = IntSet::empty();
@@ -386,6 +747,233 @@ mod test {
         );
     }
 
+    #[test]
+    fn plan_from_font_bytes() {
+        let plan =
+            Plan::from_unicodes_and_gids(&[0x2c], &[4], font_test_data::GLYF_COMPONENTS).unwrap();
+        assert!(plan.unicodes.contains(0x2c_u32));
+        assert!(plan.unicodes.contains(0x31_u32));
+        assert!(plan.glyphset.contains(GlyphId::new(2)));
+        assert!(plan.glyphset.contains(GlyphId::new(4)));
+
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        assert_eq!(
+            subset_font_bytes(font_test_data::GLYF_COMPONENTS, &plan).unwrap(),
+            subset_font(font, &plan).unwrap()
+        );
+    }
+
+    #[test]
+    fn subset_invalid_font_bytes() {
+        let plan = Plan::default();
+        assert!(matches!(
+            subset_font_bytes(b"not a font", &plan),
+            Err(SubsetError::InvalidFontBytes(_))
+        ));
+        assert!(Plan::from_unicodes_and_gids(&[0x41], &[], b"not a font").is_err());
+    }
+
+    #[test]
+    fn subset_table_failure() {
+        // an empty plan has no glyphs, so hmtx can't be subset
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let err = subset_font(font, &Plan::default()).unwrap_err();
+        assert!(matches!(
+            err,
+            SubsetError::TableSubsetFailed { tag, .. } if tag == Hmtx::TAG
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Subsetting table 'hmtx' failed: no glyphs to retain"
+        );
+    }
+
+    #[test]
+    fn populate_without_required_tables() {
+        // a copy of the font without the given table
+        fn font_without(tag: Tag) -> Vec<u8> {
+            let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+            let mut builder = FontBuilder::default();
+            for record in font.table_directory.table_records() {
+                if record.tag() != tag {
+                    builder.add_raw(record.tag(), font.data_for_tag(record.tag()).unwrap());
+                }
+            }
+            builder.build()
+        }
+
+        for tag in [Maxp::TAG, Cmap::TAG, Hmtx::TAG] {
+            let font_bytes = font_without(tag);
+            let font = FontRef::new(&font_bytes).unwrap();
+            let result = Plan::default().populate(&IntSet::empty(), &IntSet::empty(), &font);
+            assert!(
+                matches!(result, Err(PlanError::TableReadError(missing, _)) if missing == tag),
+                "missing {tag} not reported"
+            );
+        }
+    }
+
+    #[test]
+    fn plan_builder() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let plan = Plan::builder(&font)
+            .with_gids(&[4].into_iter().collect())
+            .with_text(",")
+            .build()
+            .unwrap();
+        assert!(plan.unicodes.contains(0x2c_u32));
+        assert!(plan.unicodes.contains(0x31_u32));
+        // gid 4 is a composite glyph with a component gid 1
+        assert_eq!(plan.num_output_glyphs, 4);
+        assert_eq!(
+            plan.output_glyphs().collect::<Vec<_>>(),
+            [0, 1, 2, 4].map(|gid| Some(GlyphId::new(gid)))
+        );
+    }
+
+    #[test]
+    fn plan_builder_retain_gids() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let plan = Plan::builder(&font)
+            .with_gids(&[2, 4].into_iter().collect())
+            .retain_gids(true)
+            .build()
+            .unwrap();
+        assert_eq!(plan.num_output_glyphs, 5);
+        assert_eq!(
+            plan.output_glyphs().collect::<Vec<_>>(),
+            [Some(0), Some(1), Some(2), None, Some(4)].map(|gid| gid.map(GlyphId::new))
+        );
+    }
+
+    #[test]
+    fn plan_builder_preserve_features() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let plan = Plan::builder(&font).build().unwrap();
+        assert!(plan.layout_features().is_empty());
+
+        let kern = Tag::new(b"kern");
+        let liga = Tag::new(b"liga");
+        let plan = Plan::builder(&font)
+            .preserve_features(&[kern])
+            .preserve_features(&[liga, kern])
+            .build()
+            .unwrap();
+        assert_eq!(plan.layout_features(), [kern, liga]);
+    }
+
+    #[test]
+    fn too_many_output_glyphs() {
+        let mut plan = Plan::default();
+        plan.glyphset.insert(GlyphId::new(65534));
+        assert_eq!(plan.compute_num_output_glyphs().unwrap(), 1);
+        plan.retain_gids = true;
+        assert_eq!(plan.compute_num_output_glyphs().unwrap(), 65535);
+
+        plan.glyphset.insert(GlyphId::new(65535));
+        let err = plan.compute_num_output_glyphs().unwrap_err();
+        assert!(matches!(err, PlanError::TooManyGlyphs(65536)));
+        assert_eq!(
+            err.to_string(),
+            "Subset font would have 65536 glyphs, but at most 65535 are supported"
+        );
+    }
+
+    #[test]
+    fn plan_builder_unknown_axis() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let result = Plan::builder(&font)
+            .pin_axis(Tag::new(b"wght"), 400.0)
+            .build();
+        assert!(matches!(result, Err(PlanError::UnknownAxis(tag)) if tag == Tag::new(b"wght")));
+    }
+
+    #[test]
+    fn plan_builder_uninstanceable_font() {
+        // glyf outlines can't be instanced, since gvar isn't supported yet
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let result = Plan::builder(&font)
+            .pin_axis(Tag::new(b"wght"), 400.0)
+            .build();
+        assert!(
+            matches!(result, Err(PlanError::UnsupportedInstancing(tag)) if tag == Tag::new(b"gvar"))
+        );
+        // ...but the font can still be subset without pinning
+        assert!(Plan::builder(&font).with_text("a").build().is_ok());
+    }
+
+    #[test]
+    fn subset_with_all_axes_pinned() {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let plan = Plan::builder(&font)
+            .with_gids(&[1, 2].into_iter().collect())
+            .pin_axis(Tag::new(b"wght"), 800.0)
+            .build()
+            .unwrap();
+        let bytes = subset_font(font.clone(), &plan).unwrap();
+        let subset = FontRef::new(&bytes).unwrap();
+        for tag in [Fvar::TAG, Avar::TAG, Hvar::TAG, Mvar::TAG] {
+            assert!(subset.data_for_tag(tag).is_none(), "{tag} was not dropped");
+        }
+
+        // only the axis value for the pinned weight remains
+        let stat = font.stat().unwrap();
+        let subset_stat = subset.stat().unwrap();
+        let values = |stat: &write_fonts::read::tables::stat::Stat| {
+            stat.offset_to_axis_values()
+                .unwrap()
+                .unwrap()
+                .axis_values()
+                .iter()
+                .map(|value| value.unwrap().value_name_id())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(&stat).len(), 5);
+        // name id 261 is the axis value for weight 800
+        assert_eq!(values(&subset_stat), [NameId::new(261)]);
+    }
+
+    #[test]
+    fn plan_builder_unmapped_unicodes() {
+        let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
+        let unicodes = [0x2c, 0x1F600].into_iter().collect();
+        let plan = Plan::builder(&font)
+            .with_unicodes(&unicodes)
+            .build()
+            .unwrap();
+        assert!(plan.unicodes.contains(0x2c_u32));
+        assert_eq!(
+            plan.unmapped_unicodes().iter().collect::<Vec<_>>(),
+            [0x1F600]
+        );
+
+        let result = Plan::builder(&font)
+            .with_unicodes(&unicodes)
+            .strict_unicodes(true)
+            .build();
+        let Err(err) = result else {
+            panic!("expected an error for the unmapped codepoint");
+        };
+        assert!(matches!(err, PlanError::UnmappedUnicodes(_)));
+        assert_eq!(err.to_string(), "Font has no glyph for U+1F600");
+
+        // every codepoint is mapped, so strict mode has no effect
+        assert!(Plan::builder(&font)
+            .with_text(",")
+            .strict_unicodes(true)
+            .build()
+            .is_ok());
+
+        // requesting all codepoints retains whatever the font maps
+        let plan = Plan::builder(&font)
+            .with_unicodes(&IntSet::all())
+            .strict_unicodes(true)
+            .build()
+            .unwrap();
+        assert!(plan.unmapped_unicodes().is_empty());
+        assert!(plan.unicodes.contains(0x2c_u32));
+    }
+
     #[test]
     fn glyf_closure_composite_glyphs() {
         let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
@@ -403,14 +991,14 @@ mod test {
     fn populate_gids_wo_cmap_colr_layout() {
         let mut plan = Plan::default();
         let font = FontRef::new(font_test_data::GLYF_COMPONENTS).unwrap();
-        plan.font_num_glyphs = get_font_num_glyphs(&font);
+        plan.font_num_glyphs = get_font_num_glyphs(&font).unwrap();
         plan.unicodes.insert(0x2c_u32);
         plan.unicodes.insert(0x34_u32);
 
         plan.glyphset_gsub.insert(GlyphId::new(2));
         plan.glyphset_gsub.insert(GlyphId::new(7));
 
-        plan.populate_gids_to_retain(&font);
+        plan.populate_gids_to_retain(&font).unwrap();
         assert_eq!(plan.glyphset_gsub.len(), 3);
         assert!(plan.glyphset_gsub.contains(GlyphId::new(0)));
         assert!(plan.glyphset_gsub.contains(GlyphId::new(2)));
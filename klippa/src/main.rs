@@ -4,10 +4,17 @@
 //! font file containing only the data specified in the input.
 //!
 
-use clap::Parser;
-use klippa::{parse_unicodes, populate_gids, subset_font, Plan};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use int_set::IntSet;
+use klippa::{parse_unicodes, populate_gids, subset_font, Plan, SubsetError};
 use write_fonts::read::FontRef;
 
+/// The argument value meaning "read from stdin" or "write to stdout".
+const STDIO_ARG: &str = "-";
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
@@ -15,41 +22,189 @@ struct Args {
     #[arg(short, long)]
     path: std::path::PathBuf,
 
-    /// List of glyph ids
+    /// Comma separated list of glyph ids or ranges of glyph ids (e.g. 0-255,300,400-500),
+    /// or '-' to read them from stdin
     #[arg(short, long)]
     gids: Option<String>,
 
-    /// List of unicode codepoints
+    /// A file with one glyph id or range of glyph ids per line, or '-' to read them from stdin.
+    /// Blank lines and comments, starting with a '#' at the start of a line or after
+    /// whitespace, are ignored.
+    #[arg(long)]
+    gids_file: Option<std::path::PathBuf>,
+
+    /// Comma separated list of hex unicode codepoints, ranges or Unicode block names
+    /// (e.g. 0041-005A,U+0061,Cyrillic), or '-' to read them from stdin
     #[arg(short, long)]
     unicodes: Option<String>,
 
-    /// The output font file
+    /// A file with one unicode codepoint, range or block name per line, or '-' to read them
+    /// from stdin. Blank lines and comments, starting with a '#' at the start of a line or
+    /// after whitespace, are ignored.
+    #[arg(long)]
+    unicodes_file: Option<std::path::PathBuf>,
+
+    /// Text whose characters should be retained, or '-' to read it from stdin
+    #[arg(short, long)]
+    text: Option<String>,
+
+    /// The output font file, or '-' to write the font to stdout
     #[arg(short, long)]
     output_file: std::path::PathBuf,
+
+    /// Ignore requested codepoints that the font does not map to a glyph (the default).
+    /// A warning listing them is still printed.
+    #[arg(long, conflicts_with = "strict_unicodes")]
+    ignore_missing_unicodes: bool,
+
+    /// Fail if any requested codepoint is not mapped to a glyph by the font.
+    #[arg(long)]
+    strict_unicodes: bool,
 }
 
 fn main() {
     let args = Args::parse();
 
-    let gids = match populate_gids(&args.gids.unwrap_or_default()) {
-        Ok(gids) => gids,
-        Err(e) => {
-            eprintln!("{e}");
-            std::process::exit(1);
+    let from_stdin = [&args.gids, &args.unicodes, &args.text]
+        .iter()
+        .filter(|arg| arg.as_deref() == Some(STDIO_ARG))
+        .count()
+        + [&args.gids_file, &args.unicodes_file]
+            .iter()
+            .filter(|path| {
+                path.as_ref()
+                    .is_some_and(|path| path.as_os_str() == STDIO_ARG)
+            })
+            .count();
+    if from_stdin > 1 {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "Only one of --gids, --gids-file, --unicodes, --unicodes-file or --text can be read from stdin",
+            )
+            .exit();
+    }
+
+    if let Err(e) = run(args) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), SubsetError> {
+    let mut gids = populate_gids(read_arg(args.gids)?.trim())?;
+    if let Some(path) = &args.gids_file {
+        for line in list_file_entries(&read_file_arg(path)?) {
+            gids.union(&populate_gids(line)?);
+        }
+    }
+
+    let mut unicodes = parse_unicodes(&read_arg(args.unicodes)?)?;
+    if let Some(path) = &args.unicodes_file {
+        for line in list_file_entries(&read_file_arg(path)?) {
+            unicodes.union(&parse_unicodes(line)?);
         }
-    };
+    }
+    // a trailing line break (as added by `echo`) is not considered part of the text
+    let text = read_arg(args.text)?;
+    let text = text.trim_end_matches(['\n', '\r']);
 
-    let unicodes = match parse_unicodes(&args.unicodes.unwrap_or_default()) {
-        Ok(unicodes) => unicodes,
-        Err(e) => {
-            eprintln!("{e}");
-            std::process::exit(1);
+    let font_bytes = std::fs::read(&args.path).map_err(|e| with_path(e, &args.path))?;
+    let font = FontRef::new(&font_bytes).map_err(SubsetError::InvalidFontBytes)?;
+    let gids = gids.iter().map(|gid| gid.to_u32()).collect::<IntSet<u32>>();
+    let plan = Plan::builder(&font)
+        .with_gids(&gids)
+        .with_unicodes(&unicodes)
+        .with_text(text)
+        .strict_unicodes(args.strict_unicodes)
+        .build()?;
+    let unmapped = plan.unmapped_unicodes();
+    if !unmapped.is_empty() {
+        eprintln!(
+            "warning: ignoring {} requested codepoint(s) not mapped by the font",
+            unmapped.len()
+        );
+    }
+
+    let output_bytes = subset_font(font, &plan)?;
+    if args.output_file.as_os_str() == STDIO_ARG {
+        std::io::stdout().lock().write_all(&output_bytes)?;
+    } else {
+        std::fs::write(&args.output_file, output_bytes)
+            .map_err(|e| with_path(e, &args.output_file))?;
+    }
+    Ok(())
+}
+
+/// Returns the value of an optional argument, reading all of stdin if the value is '-'.
+fn read_arg(arg: Option<String>) -> Result<String, SubsetError> {
+    match arg {
+        Some(arg) if arg == STDIO_ARG => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            Ok(input)
         }
-    };
+        arg => Ok(arg.unwrap_or_default()),
+    }
+}
+
+/// Returns the contents of a file argument, reading all of stdin if the path is '-'.
+fn read_file_arg(path: &Path) -> Result<String, SubsetError> {
+    if path.as_os_str() == STDIO_ARG {
+        return read_arg(Some(STDIO_ARG.to_owned()));
+    }
+    std::fs::read_to_string(path).map_err(|e| with_path(e, path))
+}
+
+/// Adds the path of the file being accessed to an I/O error.
+fn with_path(error: std::io::Error, path: &Path) -> SubsetError {
+    std::io::Error::new(error.kind(), format!("'{}': {error}", path.display())).into()
+}
+
+/// Returns the non-empty lines of a list file, with '#' comments removed.
+///
+/// A '#' only starts a comment at the start of a line or after whitespace, so
+/// entries such as the HTML entity `&#x41;` are kept intact.
+fn list_file_entries(contents: &str) -> impl Iterator<Item = &str> {
+    contents
+        .lines()
+        .map(|line| strip_comment(line).trim())
+        .filter(|line| !line.is_empty())
+}
+
+fn strip_comment(line: &str) -> &str {
+    let comment_start = line.char_indices().find(|(i, c)| {
+        *c == '#'
+            && line[..*i]
+                .chars()
+                .next_back()
+                .is_none_or(char::is_whitespace)
+    });
+    match comment_start {
+        Some((i, _)) => &line[..i],
+        None => line,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
 
-    let font_bytes = std::fs::read(&args.path).expect("Invalid input font file found");
-    let font = FontRef::new(&font_bytes).expect("Error reading font bytes");
-    let plan = Plan::new(&gids, &unicodes, &font);
+    #[test]
+    fn list_file_comments() {
+        let contents = "# glyphs to keep\n1-5\n\n  7 # the seven\n9#not a comment\n";
+        assert_eq!(
+            list_file_entries(contents).collect::<Vec<_>>(),
+            ["1-5", "7", "9#not a comment"]
+        );
+    }
 
-    subset_font(font, &plan, &args.output_file);
+    #[test]
+    fn list_file_html_entities() {
+        let contents = "&#x41;\n&#66; # B\n\t# indented comment\n";
+        assert_eq!(
+            list_file_entries(contents).collect::<Vec<_>>(),
+            ["&#x41;", "&#66;"]
+        );
+    }
 }
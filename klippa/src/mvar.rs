@@ -0,0 +1,223 @@
+//! subset MVAR table
+
+use std::collections::HashMap;
+
+use crate::variations::{default_coords, VarStoreSubsetter};
+use crate::{Plan, SubsetError};
+use write_fonts::{
+    read::{
+        tables::mvar::{tags, Mvar as ReadMvar},
+        tables::variations::DeltaSetIndex,
+        FontRef, ReadError, TableProvider, TopLevelTable,
+    },
+    tables::{
+        hhea::Hhea,
+        mvar::{Mvar, ValueRecord},
+        os2::Os2,
+        post::Post,
+        vhea::Vhea,
+    },
+    types::{F2Dot14, FWord, MajorMinor, Tag},
+};
+
+/// Subset the MVAR table according to the plan.
+///
+/// Returns `None` if the table can be dropped, which is the case when every
+/// axis is pinned. Otherwise pinned axes are removed from the variation
+/// regions; the deltas at the pinned location are applied to the metrics
+/// themselves when the other tables are subset.
+pub fn subset_mvar(font: &FontRef, plan: &Plan) -> Result<Option<Vec<u8>>, SubsetError> {
+    let mvar = font
+        .mvar()
+        .map_err(|e| SubsetError::table_subset_failed(Mvar::TAG, e))?;
+    let location = plan.pinned_location(font);
+    if !location.is_empty() && location.iter().all(Option::is_some) {
+        return Ok(None);
+    }
+    let Some(mvar) = subset_mvar_impl(&mvar, &location)
+        .map_err(|e| SubsetError::table_subset_failed(Mvar::TAG, e))?
+    else {
+        return Ok(None);
+    };
+    write_fonts::dump_table(&mvar)
+        .map(Some)
+        .map_err(|e| SubsetError::table_subset_failed(Mvar::TAG, e))
+}
+
+fn subset_mvar_impl(
+    mvar: &ReadMvar,
+    location: &[Option<F2Dot14>],
+) -> Result<Option<Mvar>, ReadError> {
+    let Some(store) = mvar.item_variation_store().transpose()? else {
+        return Ok(None);
+    };
+    if mvar.value_records().is_empty() {
+        return Ok(None);
+    }
+
+    let mut store = VarStoreSubsetter::new(store, location)?;
+    let temp_ids = mvar
+        .value_records()
+        .iter()
+        .map(|record| {
+            let id = store.add_delta_set(DeltaSetIndex {
+                outer: record.delta_set_outer_index(),
+                inner: record.delta_set_inner_index(),
+            })?;
+            Ok((record.value_tag(), id))
+        })
+        .collect::<Result<Vec<_>, ReadError>>()?;
+
+    let (store, remapping) = store.build();
+    let value_records = temp_ids
+        .into_iter()
+        .map(|(tag, id)| {
+            let index = remapping.get(id).unwrap_or_default();
+            ValueRecord::new(
+                tag,
+                index.delta_set_outer_index,
+                index.delta_set_inner_index,
+            )
+        })
+        .collect();
+    Ok(Some(Mvar::new(
+        MajorMinor::VERSION_1_0,
+        Some(store),
+        value_records,
+    )))
+}
+
+/// The MVAR deltas at the pinned location, to be added to the default metrics.
+///
+/// gasp ranges are not instanced.
+#[derive(Default)]
+pub(crate) struct PinnedMetrics(HashMap<Tag, i32>);
+
+impl PinnedMetrics {
+    pub(crate) fn new(font: &FontRef, location: &[Option<F2Dot14>]) -> Result<Self, SubsetError> {
+        if location.iter().all(Option::is_none) {
+            return Ok(Self::default());
+        }
+        let Ok(mvar) = font.mvar() else {
+            return Ok(Self::default());
+        };
+        let coords = default_coords(location);
+        mvar.value_records()
+            .iter()
+            .map(|record| {
+                let tag = record.value_tag();
+                mvar.metric_delta(tag, &coords)
+                    .map(|delta| (tag, delta.to_i32()))
+                    .map_err(|e| SubsetError::table_subset_failed(Mvar::TAG, e))
+            })
+            .collect::<Result<_, _>>()
+            .map(PinnedMetrics)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.values().all(|delta| *delta == 0)
+    }
+
+    pub(crate) fn apply_to_hhea(&self, hhea: &mut Hhea) {
+        self.apply(tags::HCRS, &mut hhea.caret_slope_rise);
+        self.apply(tags::HCRN, &mut hhea.caret_slope_run);
+        self.apply(tags::HCOF, &mut hhea.caret_offset);
+    }
+
+    pub(crate) fn apply_to_vhea(&self, vhea: &mut Vhea) {
+        self.apply_fword(tags::VASC, &mut vhea.ascender);
+        self.apply_fword(tags::VDSC, &mut vhea.descender);
+        self.apply_fword(tags::VLGP, &mut vhea.line_gap);
+        self.apply(tags::VCRS, &mut vhea.caret_slope_rise);
+        self.apply(tags::VCRN, &mut vhea.caret_slope_run);
+        self.apply(tags::VCOF, &mut vhea.caret_offset);
+    }
+
+    pub(crate) fn apply_to_os2(&self, os2: &mut Os2) {
+        self.apply(tags::HASC, &mut os2.s_typo_ascender);
+        self.apply(tags::HDSC, &mut os2.s_typo_descender);
+        self.apply(tags::HLGP, &mut os2.s_typo_line_gap);
+        self.apply_unsigned(tags::HCLA, &mut os2.us_win_ascent);
+        self.apply_unsigned(tags::HCLD, &mut os2.us_win_descent);
+        if let Some(x_height) = os2.sx_height.as_mut() {
+            self.apply(tags::XHGT, x_height);
+        }
+        if let Some(cap_height) = os2.s_cap_height.as_mut() {
+            self.apply(tags::CPHT, cap_height);
+        }
+        self.apply(tags::SBXS, &mut os2.y_subscript_x_size);
+        self.apply(tags::SBYS, &mut os2.y_subscript_y_size);
+        self.apply(tags::SBXO, &mut os2.y_subscript_x_offset);
+        self.apply(tags::SBYO, &mut os2.y_subscript_y_offset);
+        self.apply(tags::SPXS, &mut os2.y_superscript_x_size);
+        self.apply(tags::SPYS, &mut os2.y_superscript_y_size);
+        self.apply(tags::SPXO, &mut os2.y_superscript_x_offset);
+        self.apply(tags::SPYO, &mut os2.y_superscript_y_offset);
+        self.apply(tags::STRS, &mut os2.y_strikeout_size);
+        self.apply(tags::STRO, &mut os2.y_strikeout_position);
+    }
+
+    pub(crate) fn apply_to_post(&self, post: &mut Post) {
+        self.apply_fword(tags::UNDO, &mut post.underline_position);
+        self.apply_fword(tags::UNDS, &mut post.underline_thickness);
+    }
+
+    fn apply(&self, tag: Tag, value: &mut i16) {
+        if let Some(delta) = self.0.get(&tag) {
+            *value = (*value as i32 + delta).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        }
+    }
+
+    fn apply_fword(&self, tag: Tag, value: &mut FWord) {
+        let mut raw = value.to_i16();
+        self.apply(tag, &mut raw);
+        *value = FWord::new(raw);
+    }
+
+    fn apply_unsigned(&self, tag: Tag, value: &mut u16) {
+        if let Some(delta) = self.0.get(&tag) {
+            *value = (*value as i32 + delta).clamp(0, u16::MAX as i32) as u16;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use write_fonts::{from_obj::FromTableRef, read::FontRead};
+
+    #[test]
+    fn subset_mvar_without_pins() {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let plan = Plan::default();
+
+        let bytes = subset_mvar(&font, &plan).unwrap().unwrap();
+        let subset = ReadMvar::read(bytes.as_slice().into()).unwrap();
+        let mvar = font.mvar().unwrap();
+        assert_eq!(subset.value_records().len(), 4);
+        for record in mvar.value_records() {
+            for coord in [-1.0, -0.25, 0.5, 1.0] {
+                let coords = [F2Dot14::from_f32(coord)];
+                assert_eq!(
+                    subset.metric_delta(record.value_tag(), &coords).unwrap(),
+                    mvar.metric_delta(record.value_tag(), &coords).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn subset_mvar_all_axes_pinned() {
+        let font = FontRef::new(font_test_data::CANTARELL_VF_TRIMMED).unwrap();
+        let mut plan = Plan::default();
+        plan.axes_location.insert(Tag::new(b"wght"), 800.0);
+        assert!(subset_mvar(&font, &plan).unwrap().is_none());
+
+        let metrics = PinnedMetrics::new(&font, &plan.pinned_location(&font)).unwrap();
+        assert!(!metrics.is_empty());
+        let mut os2 = Os2::from_table_ref(&font.os2().unwrap());
+        metrics.apply_to_os2(&mut os2);
+        assert_eq!(os2.sx_height, Some(490));
+        assert_eq!(os2.y_strikeout_size, 50);
+    }
+}
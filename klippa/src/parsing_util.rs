@@ -1,67 +1,142 @@
 //! subsetter input parsing util functions
-use int_set::IntSet;
+use std::str::FromStr;
+
+use int_set::{Domain, IntSet};
+use thiserror::Error;
 use write_fonts::types::GlyphId;
 
-use crate::SubsetError;
-pub fn populate_gids(gid_str: &str) -> Result<IntSet<GlyphId>, SubsetError> {
-    let mut result = IntSet::empty();
+use crate::unicode_blocks::expand_unicode_block;
 
-    if gid_str.is_empty() {
-        return Ok(result);
-    }
-    for gid in gid_str.split(',') {
-        if let Some((start, end)) = gid.split_once('-') {
-            let start: u32 = start
-                .parse::<u32>()
-                .map_err(|_| SubsetError::InvalidGid(start.to_owned()))?;
-            let end: u32 = end
-                .parse::<u32>()
-                .map_err(|_| SubsetError::InvalidGid(end.to_owned()))?;
+/// The largest valid Unicode codepoint.
+pub(crate) const MAX_CODEPOINT: u32 = 0x10FFFF;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ParseError<T> {
+    #[error("Invalid value {0}")]
+    InvalidValue(String),
+
+    #[error("Invalid range {start}-{end}")]
+    InvalidRange { start: T, end: T },
+}
+
+/// An error parsing a list of glyph ids, from [`populate_gids`].
+#[derive(Debug, Error, PartialEq)]
+pub enum GidParseError {
+    #[error("Invalid glyph id {0}")]
+    InvalidInteger(String),
+
+    #[error("Glyph id {0} is out of range")]
+    OutOfRange(u64),
+
+    #[error("Invalid glyph id range {0}")]
+    InvalidRange(String),
+}
+
+/// An error parsing a list of codepoints, from [`parse_unicodes`].
+///
+/// Each variant holds the item of the list that failed to parse, as it was
+/// written in the input.
+#[derive(Debug, Error, PartialEq)]
+pub enum UnicodeParseError {
+    #[error("Invalid hex codepoint or unknown Unicode block {0}")]
+    InvalidHex(String),
+
+    #[error("Invalid codepoint {0}")]
+    InvalidCodepoint(String),
+
+    #[error("Invalid codepoint range {0}")]
+    InvalidRange(String),
+}
+
+/// parse a comma/whitespace-separated list of values or inclusive ranges of values,
+/// for example: 0-255,300,400-500
+pub fn parse_range_list<T>(s: &str) -> Result<IntSet<T>, ParseError<T>>
+where
+    T: FromStr + Domain<T> + Ord,
+{
+    parse_range_list_with(s, |value| value.parse().ok())
+}
+
+fn parse_range_list_with<T>(
+    s: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<IntSet<T>, ParseError<T>>
+where
+    T: Domain<T> + Ord,
+{
+    let parse =
+        |value: &str| parse(value).ok_or_else(|| ParseError::InvalidValue(value.to_owned()));
+    let mut result = IntSet::empty();
+    for item in s
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+    {
+        if let Some((start, end)) = item.split_once('-') {
+            // report the whole item for "-3" or "3-", not an empty value
+            if start.is_empty() || end.is_empty() {
+                return Err(ParseError::InvalidValue(item.to_owned()));
+            }
+            let start = parse(start)?;
+            let end = parse(end)?;
             if start > end {
-                return Err(SubsetError::InvalidGidRange { start, end });
+                return Err(ParseError::InvalidRange { start, end });
             }
-            result.extend((start..=end).map(GlyphId::new));
+            result.insert_range(start..=end);
         } else {
-            let glyph_id: u32 = gid
-                .parse::<u32>()
-                .map_err(|_| SubsetError::InvalidGid(gid.to_owned()))?;
-            result.insert(GlyphId::new(glyph_id));
+            result.insert(parse(item)?);
         }
     }
     Ok(result)
 }
 
+/// parse input gids string, which is a comma/whitespace-separated list of glyph ids or ranges
+/// of glyph ids. For example: --gids=0-255,300,400-500
+pub fn populate_gids(gid_str: &str) -> Result<IntSet<GlyphId>, GidParseError> {
+    let gids = parse_range_list::<u32>(gid_str).map_err(|e| match e {
+        ParseError::InvalidValue(gid) => match gid.parse::<u64>() {
+            Ok(gid) => GidParseError::OutOfRange(gid),
+            Err(_) => GidParseError::InvalidInteger(gid),
+        },
+        ParseError::InvalidRange { start, end } => {
+            GidParseError::InvalidRange(format!("{start}-{end}"))
+        }
+    })?;
+    Ok(gids.iter().map(GlyphId::new).collect())
+}
+
 /// parse input unicodes string, which is a comma/whitespace-separated list of Unicode codepoints or ranges as hex numbers,
 /// optionally prefixed with 'U+', 'u', etc. For example: --unicodes=41-5a,61-7a adds ASCII letters, so does the more verbose --unicodes=U+0041-005A,U+0061-007A.
+/// Unicode block names can be used instead of codepoints, for example --unicodes=BasicLatin,Cyrillic; see [`expand_unicode_block`].
 /// The special strings '*' will choose all Unicode characters mapped by the font.
-pub fn parse_unicodes(unicode_str: &str) -> Result<IntSet<u32>, SubsetError> {
+pub fn parse_unicodes(unicode_str: &str) -> Result<IntSet<u32>, UnicodeParseError> {
     if unicode_str == "*" {
         let out = IntSet::<u32>::all();
         return Ok(out);
     }
-    let mut result = IntSet::empty();
-    if unicode_str.is_empty() {
-        return Ok(result);
-    }
     let re = regex::Regex::new(r"[><\+,;&#}{\\xXuUnNiI\n\t\v\f\r]").unwrap();
-    let s = re.replace_all(unicode_str, " ");
-    for cp in s.split_whitespace() {
-        if let Some((start, end)) = cp.split_once('-') {
-            let start: u32 = u32::from_str_radix(start, 16)
-                .map_err(|_| SubsetError::InvalidUnicode(start.to_owned()))?;
-            let end: u32 = u32::from_str_radix(end, 16)
-                .map_err(|_| SubsetError::InvalidUnicode(end.to_owned()))?;
-            if start > end {
-                return Err(SubsetError::InvalidUnicodeRange { start, end });
-            }
-            result.extend(start..=end);
-        } else {
-            let unicode: u32 = u32::from_str_radix(cp, 16)
-                .map_err(|_| SubsetError::InvalidUnicode(cp.to_owned()))?;
-            result.insert(unicode);
+    let mut unicodes = IntSet::empty();
+    for item in unicode_str
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+    {
+        // block names contain characters that are stripped from codepoints,
+        // so they are expanded first
+        if let Some(range) = expand_unicode_block(item) {
+            unicodes.insert_range(range);
+            continue;
+        }
+        let codepoints = re.replace_all(item, " ");
+        let codepoints = parse_range_list_with(&codepoints, |cp| u32::from_str_radix(cp, 16).ok())
+            .map_err(|e| match e {
+                ParseError::InvalidValue(_) => UnicodeParseError::InvalidHex(item.to_owned()),
+                ParseError::InvalidRange { .. } => UnicodeParseError::InvalidRange(item.to_owned()),
+            })?;
+        if codepoints.last().is_some_and(|cp| cp > MAX_CODEPOINT) {
+            return Err(UnicodeParseError::InvalidCodepoint(item.to_owned()));
         }
+        unicodes.union(&codepoints);
     }
-    Ok(result)
+    Ok(unicodes)
 }
 
 #[test]
@@ -95,3 +170,102 @@ fn test_parse_unicodes() {
     assert!(output.contains(102_u32));
     assert!(output.contains(103_u32));
 }
+
+#[test]
+fn test_parse_unicode_blocks() {
+    let output = parse_unicodes("BasicLatin,Cyrillic 3000").unwrap();
+    assert_eq!(output.len(), 0x80 + 0x100 + 1);
+    assert!(output.contains(0x41));
+    assert!(output.contains(0x4FF));
+    assert!(output.contains(0x3000));
+    assert!(!output.contains(0x80));
+
+    let output = parse_unicodes("latin-1_supplement,U+41").unwrap();
+    assert_eq!(output.len(), 0x80 + 1);
+
+    assert!(matches!(
+        parse_unicodes("NotABlock"),
+        Err(UnicodeParseError::InvalidHex(_))
+    ));
+}
+
+#[test]
+fn test_parse_unicodes_errors() {
+    // errors report the item as written, including prefixes
+    assert_eq!(
+        parse_unicodes("61,110000"),
+        Err(UnicodeParseError::InvalidCodepoint("110000".to_owned()))
+    );
+    assert_eq!(
+        parse_unicodes("U+10FFF0-110005"),
+        Err(UnicodeParseError::InvalidCodepoint(
+            "U+10FFF0-110005".to_owned()
+        ))
+    );
+    assert_eq!(
+        parse_unicodes("U+7A-61"),
+        Err(UnicodeParseError::InvalidRange("U+7A-61".to_owned()))
+    );
+    assert_eq!(
+        parse_unicodes("61,-63"),
+        Err(UnicodeParseError::InvalidHex("-63".to_owned()))
+    );
+    assert_eq!(
+        parse_unicodes("U+41,U+0041-00G5"),
+        Err(UnicodeParseError::InvalidHex("U+0041-00G5".to_owned()))
+    );
+    assert_eq!(
+        parse_unicodes("U+41;u+6q"),
+        Err(UnicodeParseError::InvalidHex("u+6q".to_owned()))
+    );
+    assert!(parse_unicodes("10FFFF").is_ok());
+}
+
+#[test]
+fn test_populate_gid_ranges() {
+    let output = populate_gids("0-2,5, 7-8").unwrap();
+    assert_eq!(
+        output.iter().map(|gid| gid.to_u32()).collect::<Vec<_>>(),
+        [0, 1, 2, 5, 7, 8]
+    );
+
+    assert_eq!(
+        populate_gids("5-2"),
+        Err(GidParseError::InvalidRange("5-2".to_owned()))
+    );
+    assert_eq!(
+        populate_gids("1,a"),
+        Err(GidParseError::InvalidInteger("a".to_owned()))
+    );
+    assert_eq!(
+        populate_gids("1,-3"),
+        Err(GidParseError::InvalidInteger("-3".to_owned()))
+    );
+    assert_eq!(
+        populate_gids("2- 3"),
+        Err(GidParseError::InvalidInteger("2-".to_owned()))
+    );
+    assert_eq!(
+        populate_gids("0-4294967296"),
+        Err(GidParseError::OutOfRange(4294967296))
+    );
+}
+
+#[test]
+fn test_parse_range_list() {
+    let output = parse_range_list::<u16>("1-3,10").unwrap();
+    assert_eq!(output.iter().collect::<Vec<_>>(), [1, 2, 3, 10]);
+    assert_eq!(parse_range_list::<u16>(""), Ok(IntSet::empty()));
+    assert_eq!(
+        parse_range_list::<u16>("70000"),
+        Err(ParseError::InvalidValue("70000".to_owned()))
+    );
+    assert_eq!(
+        parse_range_list::<u16>("3-1"),
+        Err(ParseError::InvalidRange { start: 3, end: 1 })
+    );
+    assert_eq!(
+        parse_range_list::<u16>("1,-3"),
+        Err(ParseError::InvalidValue("-3".to_owned()))
+    );
+}
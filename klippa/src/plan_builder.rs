@@ -0,0 +1,207 @@
+//! builder for subset [`Plan`]s
+
+use int_set::IntSet;
+use skrifa::MetadataProvider;
+use write_fonts::{
+    read::{
+        tables::{avar::Avar, cff2::Cff2, colr::Colr, gdef::Gdef},
+        FontRef, ReadError, TableProvider, TopLevelTable,
+    },
+    types::{GlyphId, Tag},
+};
+
+use crate::{parsing_util::MAX_CODEPOINT, Plan, PlanError};
+
+/// Collects the subset input and options for a [`Plan`].
+///
+/// Created with [`Plan::builder`].
+pub struct PlanBuilder<'a> {
+    font: FontRef<'a>,
+    gids: IntSet<GlyphId>,
+    unicodes: IntSet<u32>,
+    axes_location: Vec<(Tag, f32)>,
+    layout_features: Vec<Tag>,
+    retain_gids: bool,
+    drop_hinting: bool,
+    strict_unicodes: bool,
+}
+
+impl<'a> PlanBuilder<'a> {
+    pub(crate) fn new(font: &FontRef<'a>) -> Self {
+        PlanBuilder {
+            font: font.clone(),
+            gids: IntSet::empty(),
+            unicodes: IntSet::empty(),
+            axes_location: Vec::new(),
+            layout_features: Vec::new(),
+            retain_gids: false,
+            drop_hinting: false,
+            strict_unicodes: false,
+        }
+    }
+
+    /// Retain the glyphs with these glyph ids.
+    pub fn with_gids(mut self, gids: &IntSet<u32>) -> Self {
+        self.gids.extend(gids.iter().map(GlyphId::new));
+        self
+    }
+
+    /// Retain the glyphs mapped to these codepoints.
+    pub fn with_unicodes(mut self, unicodes: &IntSet<u32>) -> Self {
+        self.unicodes.union(unicodes);
+        self
+    }
+
+    /// Retain the glyphs mapped to the characters of `text`.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.unicodes.extend(text.chars().map(|c| c as u32));
+        self
+    }
+
+    /// Pin the axis with this tag to `value`, in user space coordinates.
+    ///
+    /// Values outside of the axis range are clamped. Building the plan fails
+    /// with [`PlanError::UnsupportedInstancing`] if the font has variations
+    /// that can't be instanced yet, such as glyf outline variations in gvar.
+    pub fn pin_axis(mut self, tag: Tag, value: f32) -> Self {
+        self.axes_location.push((tag, value));
+        self
+    }
+
+    /// Retain these GSUB/GPOS features.
+    ///
+    /// The tags are recorded on the plan, see [`Plan::layout_features`].
+    /// There is no layout closure yet, so GSUB, GPOS and GDEF are still
+    /// copied to the subset font unchanged.
+    pub fn preserve_features(mut self, tags: &[Tag]) -> Self {
+        for tag in tags {
+            if !self.layout_features.contains(tag) {
+                self.layout_features.push(*tag);
+            }
+        }
+        self
+    }
+
+    /// Keep the original glyph ids, instead of compacting the retained glyphs.
+    pub fn retain_gids(mut self, retain_gids: bool) -> Self {
+        self.retain_gids = retain_gids;
+        self
+    }
+
+    /// Remove hinting tables from the subset font.
+    pub fn drop_hinting(mut self, drop_hinting: bool) -> Self {
+        self.drop_hinting = drop_hinting;
+        self
+    }
+
+    /// Fail if any of the requested codepoints is not mapped by the font.
+    ///
+    /// By default, requested codepoints that are not mapped to a glyph by the
+    /// font's cmap are ignored. Either way, they are available from
+    /// [`Plan::unmapped_unicodes`]. Only valid Unicode codepoints are checked,
+    /// and an inverted set of codepoints is never considered unmapped.
+    pub fn strict_unicodes(mut self, strict_unicodes: bool) -> Self {
+        self.strict_unicodes = strict_unicodes;
+        self
+    }
+
+    /// Compute the plan.
+    pub fn build(self) -> Result<Plan, PlanError> {
+        let font = &self.font;
+        check_table(font.maxp())?;
+        // outlines are either in glyf (with loca) or in CFF2
+        if font.data_for_tag(Cff2::TAG).is_some() {
+            check_table(font.cff2())?;
+        } else {
+            check_table(font.loca(None))?;
+            check_table(font.glyf())?;
+        }
+        check_table(font.hmtx())?;
+        check_table(font.cmap())?;
+
+        let axes = font.axes();
+        if let Some((tag, _)) = self
+            .axes_location
+            .iter()
+            .find(|(tag, _)| !axes.iter().any(|axis| axis.tag() == *tag))
+        {
+            return Err(PlanError::UnknownAxis(*tag));
+        }
+        if !self.axes_location.is_empty() {
+            if let Some(tag) = uninstanceable_table(font, self.drop_hinting) {
+                return Err(PlanError::UnsupportedInstancing(tag));
+            }
+        }
+
+        let unmapped_unicodes = unmapped_unicodes(font, &self.unicodes);
+        if self.strict_unicodes && !unmapped_unicodes.is_empty() {
+            return Err(PlanError::UnmappedUnicodes(unmapped_unicodes));
+        }
+
+        let mut plan = Plan {
+            axes_location: self.axes_location.into_iter().collect(),
+            layout_features: self.layout_features,
+            retain_gids: self.retain_gids,
+            drop_hinting: self.drop_hinting,
+            unmapped_unicodes,
+            ..Default::default()
+        };
+        plan.populate(&self.gids, &self.unicodes, font)?;
+        Ok(plan)
+    }
+}
+
+/// Returns the requested codepoints that the font does not map to a glyph.
+///
+/// An inverted set (such as the one for `--unicodes '*'`) requests whatever
+/// the font maps, so nothing is reported as unmapped for it.
+fn unmapped_unicodes(font: &FontRef, requested: &IntSet<u32>) -> IntSet<u32> {
+    if requested.is_inverted() {
+        return IntSet::empty();
+    }
+    let mut unmapped = requested.clone();
+    unmapped.remove_range(MAX_CODEPOINT + 1..=u32::MAX);
+    unmapped.remove_all(font.charmap().mappings().map(|(cp, _)| cp));
+    unmapped
+}
+
+/// Returns the first table with variations that can't be instanced yet, if any.
+///
+/// Glyph outline and hint variations are only instanced for CFF2, and item
+/// variation stores only for the metrics tables.
+fn uninstanceable_table(font: &FontRef, drop_hinting: bool) -> Option<Tag> {
+    const GVAR: Tag = Tag::new(b"gvar");
+    const CVAR: Tag = Tag::new(b"cvar");
+    const VARC: Tag = Tag::new(b"VARC");
+    if let Some(tag) = [GVAR, CVAR, VARC]
+        .into_iter()
+        // cvar is a hinting table
+        .filter(|tag| !(drop_hinting && *tag == CVAR))
+        .find(|tag| font.data_for_tag(*tag).is_some())
+    {
+        return Some(tag);
+    }
+    // avar2 has its own variation store
+    if font.avar().is_ok_and(|avar| avar.version().major > 1) {
+        return Some(Avar::TAG);
+    }
+    if font
+        .gdef()
+        .is_ok_and(|gdef| gdef.item_var_store().is_some())
+    {
+        return Some(Gdef::TAG);
+    }
+    if font
+        .colr()
+        .is_ok_and(|colr| colr.item_variation_store().is_some())
+    {
+        return Some(Colr::TAG);
+    }
+    None
+}
+
+fn check_table<T: TopLevelTable>(table: Result<T, ReadError>) -> Result<(), PlanError> {
+    table
+        .map(|_| ())
+        .map_err(|e| PlanError::TableReadError(T::TAG, e))
+}
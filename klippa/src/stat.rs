@@ -0,0 +1,53 @@
+//! instance STAT table
+
+use crate::{Plan, SubsetError};
+use write_fonts::{
+    from_obj::FromTableRef,
+    read::{FontRef, TableProvider, TopLevelTable},
+    tables::stat::{AxisValue, Stat},
+    types::Fixed,
+};
+
+/// Instance the STAT table according to the plan.
+///
+/// Axis values that don't match the value of a pinned axis are removed. The
+/// design axes are all kept, since STAT may also describe axes that don't vary.
+pub fn subset_stat(font: &FontRef, plan: &Plan) -> Result<Vec<u8>, SubsetError> {
+    let stat = font
+        .stat()
+        .map_err(|e| SubsetError::table_subset_failed(Stat::TAG, e))?;
+    let mut stat = Stat::from_table_ref(&stat);
+    let pinned = plan.pinned_user_location(font);
+    let design_axes: Vec<_> = stat.design_axes.iter().map(|axis| axis.axis_tag).collect();
+    // the value of a pinned axis, given an index into the design axes
+    let pinned_value = |axis_index: u16| {
+        design_axes
+            .get(axis_index as usize)
+            .and_then(|tag| pinned.get(tag))
+            .copied()
+    };
+
+    if let Some(axis_values) = stat.offset_to_axis_values.as_mut() {
+        axis_values.retain(|axis_value| keep_axis_value(axis_value, &pinned_value));
+    }
+    write_fonts::dump_table(&stat).map_err(|e| SubsetError::table_subset_failed(Stat::TAG, e))
+}
+
+/// Whether an axis value still applies once axes are pinned.
+///
+/// As in hb-subset, the nominal value of a format 2 axis value is used,
+/// rather than its range.
+fn keep_axis_value(axis_value: &AxisValue, pinned_value: &impl Fn(u16) -> Option<Fixed>) -> bool {
+    let matches = |axis_index: u16, value: Fixed| {
+        pinned_value(axis_index).is_none_or(|pinned| pinned == value)
+    };
+    match axis_value {
+        AxisValue::Format1(table) => matches(table.axis_index, table.value),
+        AxisValue::Format2(table) => matches(table.axis_index, table.nominal_value),
+        AxisValue::Format3(table) => matches(table.axis_index, table.value),
+        AxisValue::Format4(table) => table
+            .axis_values
+            .iter()
+            .all(|record| matches(record.axis_index, record.value)),
+    }
+}
@@ -0,0 +1,275 @@
+//! shared helpers for subsetting tables that contain an ItemVariationStore
+use crate::Plan;
+use write_fonts::{
+    read::{
+        tables::variations::{
+            DeltaSetIndex, DeltaSetIndexMap, ItemVariationStore, RegionAxisCoordinates,
+            VariationRegion,
+        },
+        ReadError,
+    },
+    tables::variations::{
+        ivs_builder::{VariationIndexRemapping, VariationStoreBuilder},
+        DeltaSetIndexMap as WriteDeltaSetIndexMap, ItemVariationStore as WriteItemVariationStore,
+        RegionAxisCoordinates as WriteRegionAxisCoordinates,
+        VariationRegion as WriteVariationRegion,
+    },
+    types::F2Dot14,
+};
+
+/// Rebuilds an ItemVariationStore, keeping only the delta sets that are
+/// referenced after subsetting and instancing any pinned axes.
+///
+/// Regions are restricted to the axes that are not pinned. Deltas for regions
+/// that only depend on pinned axes become part of the default instance and are
+/// dropped; callers are responsible for baking them into the default values.
+pub(crate) struct VarStoreSubsetter<'a> {
+    store: ItemVariationStore<'a>,
+    location: &'a [Option<F2Dot14>],
+    builder: VariationStoreBuilder,
+}
+
+impl<'a> VarStoreSubsetter<'a> {
+    /// `location` holds the pinned normalized coordinate (if any) for each axis.
+    pub(crate) fn new(
+        store: ItemVariationStore<'a>,
+        location: &'a [Option<F2Dot14>],
+    ) -> Result<Self, ReadError> {
+        let axis_count = store.variation_region_list()?.axis_count() as usize;
+        let pinned_count = location
+            .iter()
+            .take(axis_count)
+            .filter(|coord| coord.is_some())
+            .count();
+        let builder = VariationStoreBuilder::new((axis_count - pinned_count) as u16);
+        Ok(VarStoreSubsetter {
+            store,
+            location,
+            builder,
+        })
+    }
+
+    /// Add the instanced delta set for `index` to the new store, returning its temporary id.
+    pub(crate) fn add_delta_set(&mut self, index: DeltaSetIndex) -> Result<u32, ReadError> {
+        let Some(data) = self
+            .store
+            .item_variation_data()
+            .get(index.outer as usize)
+            .transpose()?
+        else {
+            return Ok(self
+                .builder
+                .add_deltas(Vec::<(WriteVariationRegion, i32)>::new()));
+        };
+        let regions = self.store.variation_region_list()?.variation_regions();
+        let region_indices = data.region_indexes();
+
+        let mut deltas: Vec<(WriteVariationRegion, f32)> = Vec::new();
+        for (i, delta) in data.delta_set(index.inner).enumerate() {
+            let region_index = region_indices
+                .get(i)
+                .ok_or(ReadError::MalformedData(
+                    "invalid delta sets in ItemVariationStore",
+                ))?
+                .get();
+            let region = regions.get(region_index as usize)?;
+            let (scalar, Some(new_region)) = instance_region(&region, self.location) else {
+                continue;
+            };
+            if scalar == 0.0 {
+                continue;
+            }
+            let delta = delta as f32 * scalar;
+            // distinct source regions can collapse into the same region once pinned
+            // axes are removed, so merge their deltas.
+            match deltas.iter_mut().find(|(region, _)| *region == new_region) {
+                Some((_, value)) => *value += delta,
+                None => deltas.push((new_region, delta)),
+            }
+        }
+
+        let deltas = deltas
+            .into_iter()
+            .map(|(region, delta)| (region, (delta + 0.5).floor() as i32))
+            .collect();
+        Ok(self.builder.add_deltas(deltas))
+    }
+
+    fn add_empty_delta_set(&mut self) -> u32 {
+        self.builder
+            .add_deltas(Vec::<(WriteVariationRegion, i32)>::new())
+    }
+
+    /// Add the delta sets for all glyphs of the subset font, in new glyph id order.
+    ///
+    /// If `mapping` is `None` then glyph ids are used as implicit inner indices
+    /// into the first ItemVariationData subtable.
+    pub(crate) fn add_glyph_delta_sets(
+        &mut self,
+        mapping: Option<&DeltaSetIndexMap>,
+        plan: &Plan,
+    ) -> Result<Vec<u32>, ReadError> {
+        plan.output_glyphs()
+            .map(|gid| {
+                let Some(gid) = gid else {
+                    return Ok(self.add_empty_delta_set());
+                };
+                let index = match mapping {
+                    Some(mapping) => mapping.get(gid.to_u32())?,
+                    None => DeltaSetIndex {
+                        outer: 0,
+                        inner: gid.to_u32() as u16,
+                    },
+                };
+                self.add_delta_set(index)
+            })
+            .collect()
+    }
+
+    pub(crate) fn build(self) -> (WriteItemVariationStore, VariationIndexRemapping) {
+        self.builder.build()
+    }
+}
+
+/// Build a DeltaSetIndexMap from temporary delta set ids, after the store has been built.
+pub(crate) fn build_delta_set_index_map(
+    temp_ids: &[u32],
+    remapping: &VariationIndexRemapping,
+) -> WriteDeltaSetIndexMap {
+    temp_ids
+        .iter()
+        .map(|id| {
+            remapping
+                .get(*id)
+                .map(|idx| {
+                    ((idx.delta_set_outer_index as u32) << 16) | idx.delta_set_inner_index as u32
+                })
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Returns the normalized location used when baking deltas into default values:
+/// pinned axes are at their pinned position, all other axes at the default.
+pub(crate) fn default_coords(location: &[Option<F2Dot14>]) -> Vec<F2Dot14> {
+    location
+        .iter()
+        .map(|coord| coord.unwrap_or_default())
+        .collect()
+}
+
+/// Split a region into the scalar contributed by the pinned axes and a new
+/// region over the remaining axes.
+///
+/// The region is `None` if no remaining axis contributes to it, i.e. the deltas
+/// apply at the default location of the instanced font.
+pub(crate) fn instance_region(
+    region: &VariationRegion,
+    location: &[Option<F2Dot14>],
+) -> (f32, Option<WriteVariationRegion>) {
+    let mut scalar = 1.0;
+    let mut has_active_axis = false;
+    let mut region_axes = Vec::new();
+    for (i, axis) in region.region_axes().iter().enumerate() {
+        match location.get(i).copied().flatten() {
+            Some(coord) => scalar *= axis_scalar(axis, coord),
+            None => {
+                has_active_axis |= is_active(axis);
+                region_axes.push(WriteRegionAxisCoordinates::new(
+                    axis.start_coord(),
+                    axis.peak_coord(),
+                    axis.end_coord(),
+                ));
+            }
+        }
+    }
+    (
+        scalar,
+        has_active_axis.then(|| WriteVariationRegion::new(region_axes)),
+    )
+}
+
+/// Returns `false` for axes that have no influence on the region scalar.
+fn is_active(axis: &RegionAxisCoordinates) -> bool {
+    let start = axis.start_coord().to_f32();
+    let peak = axis.peak_coord().to_f32();
+    let end = axis.end_coord().to_f32();
+    !(start > peak || peak > end || peak == 0.0 || start < 0.0 && end > 0.0)
+}
+
+/// The scalar for a single axis of a region, following the same rules as
+/// `VariationRegion::compute_scalar_f32`.
+fn axis_scalar(axis: &RegionAxisCoordinates, coord: F2Dot14) -> f32 {
+    if !is_active(axis) {
+        return 1.0;
+    }
+    let coord = coord.to_f32();
+    let start = axis.start_coord().to_f32();
+    let peak = axis.peak_coord().to_f32();
+    let end = axis.end_coord().to_f32();
+    if coord < start || coord > end {
+        0.0
+    } else if coord == peak {
+        1.0
+    } else if coord < peak {
+        (coord - start) / (peak - start)
+    } else {
+        (end - coord) / (end - peak)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use write_fonts::{
+        dump_table,
+        read::{FontData, FontReadWithArgs},
+    };
+
+    fn region_bytes(axes: &[(f32, f32, f32)]) -> Vec<u8> {
+        let region = WriteVariationRegion::new(
+            axes.iter()
+                .map(|(start, peak, end)| {
+                    WriteRegionAxisCoordinates::new(
+                        F2Dot14::from_f32(*start),
+                        F2Dot14::from_f32(*peak),
+                        F2Dot14::from_f32(*end),
+                    )
+                })
+                .collect(),
+        );
+        dump_table(&region).unwrap()
+    }
+
+    #[test]
+    fn instance_region_without_pins() {
+        let bytes = region_bytes(&[(0.0, 1.0, 1.0), (0.0, 0.0, 0.0)]);
+        let region = VariationRegion::read_with_args(FontData::new(&bytes), &2).unwrap();
+        let (scalar, new_region) = instance_region(&region, &[None, None]);
+        assert_eq!(scalar, 1.0);
+        assert_eq!(new_region.unwrap().region_axes.len(), 2);
+    }
+
+    #[test]
+    fn instance_region_with_pins() {
+        let bytes = region_bytes(&[(0.0, 1.0, 1.0), (0.0, 1.0, 1.0)]);
+        let region = VariationRegion::read_with_args(FontData::new(&bytes), &2).unwrap();
+
+        // pinning one axis halfway scales the deltas and drops the axis
+        let (scalar, new_region) = instance_region(&region, &[Some(F2Dot14::from_f32(0.5)), None]);
+        assert_eq!(scalar, 0.5);
+        assert_eq!(new_region.unwrap().region_axes.len(), 1);
+
+        // pinning all axes leaves a region that applies at the default location
+        let (scalar, new_region) = instance_region(
+            &region,
+            &[Some(F2Dot14::from_f32(1.0)), Some(F2Dot14::from_f32(0.25))],
+        );
+        assert_eq!(scalar, 0.25);
+        assert!(new_region.is_none());
+
+        // pinned outside of the region
+        let (scalar, _) = instance_region(&region, &[Some(F2Dot14::from_f32(-0.5)), None]);
+        assert_eq!(scalar, 0.0);
+    }
+}
@@ -0,0 +1,10 @@
+//! impl subset() for vhea
+use crate::{Plan, Subset, SubsetError};
+use write_fonts::tables::vhea::Vhea;
+
+impl Subset for Vhea {
+    fn subset(&mut self, plan: &Plan) -> Result<bool, SubsetError> {
+        self.number_of_long_ver_metrics = plan.num_v_metrics;
+        Ok(true)
+    }
+}
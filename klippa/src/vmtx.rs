@@ -0,0 +1,53 @@
+//! impl subset() for vmtx
+
+use crate::hmtx::subset_long_metrics;
+use crate::{Plan, Subset, SubsetError};
+use write_fonts::read::TopLevelTable;
+use write_fonts::tables::vmtx::Vmtx;
+
+impl Subset for Vmtx {
+    fn subset(&mut self, plan: &Plan) -> Result<bool, SubsetError> {
+        let (metrics, side_bearings) = subset_long_metrics(
+            Vmtx::TAG,
+            &self.v_metrics,
+            &self.top_side_bearings,
+            plan.num_v_metrics,
+            plan,
+        )?;
+        self.v_metrics = metrics;
+        self.top_side_bearings = side_bearings;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::compute_num_long_metrics;
+    use write_fonts::{tables::vmtx::LongMetric, types::GlyphId};
+
+    #[test]
+    fn subset_vmtx() {
+        let metric = |advance, side_bearing| LongMetric {
+            advance,
+            side_bearing,
+        };
+        let mut vmtx = Vmtx::new(
+            vec![metric(1000, 10), metric(800, 20), metric(1000, 30)],
+            vec![40, 50],
+        );
+        let mut plan = Plan::default();
+        plan.glyphset
+            .extend([0, 1, 3].into_iter().map(GlyphId::new));
+        plan.num_output_glyphs = 3;
+        plan.num_v_metrics = compute_num_long_metrics(&vmtx.v_metrics, plan.output_glyphs());
+        assert_eq!(plan.num_v_metrics, 3);
+
+        vmtx.subset(&plan).unwrap();
+        assert_eq!(
+            vmtx.v_metrics,
+            [metric(1000, 10), metric(800, 20), metric(1000, 40)]
+        );
+        assert!(vmtx.top_side_bearings.is_empty());
+    }
+}
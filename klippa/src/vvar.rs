@@ -0,0 +1,83 @@
+//! subset VVAR table
+
+use crate::hmtx::expand_long_metrics;
+use crate::variations::{build_delta_set_index_map, default_coords, VarStoreSubsetter};
+use crate::{Plan, SubsetError};
+use write_fonts::{
+    read::{
+        tables::{variations::DeltaSetIndexMap, vvar::Vvar as ReadVvar},
+        FontRef, ReadError, TableProvider, TopLevelTable,
+    },
+    tables::{vmtx::Vmtx, vvar::Vvar},
+    types::{F2Dot14, GlyphId, MajorMinor},
+};
+
+/// Subset the VVAR table according to the plan.
+///
+/// This mirrors [`subset_hvar`](crate::subset_hvar): deltas at the pinned
+/// location are baked into the default advance heights by [`instance_advances`]
+/// when vmtx is subset.
+pub fn subset_vvar(font: &FontRef, plan: &Plan) -> Result<Vec<u8>, SubsetError> {
+    let vvar = font
+        .vvar()
+        .map_err(|e| SubsetError::table_subset_failed(Vvar::TAG, e))?;
+    let location = plan.pinned_location(font);
+    let vvar = subset_vvar_impl(&vvar, plan, &location)
+        .map_err(|e| SubsetError::table_subset_failed(Vvar::TAG, e))?;
+    write_fonts::dump_table(&vvar).map_err(|e| SubsetError::table_subset_failed(Vvar::TAG, e))
+}
+
+fn subset_vvar_impl(
+    vvar: &ReadVvar,
+    plan: &Plan,
+    location: &[Option<F2Dot14>],
+) -> Result<Vvar, ReadError> {
+    let mut store = VarStoreSubsetter::new(vvar.item_variation_store()?, location)?;
+
+    let advance_mapping = vvar.advance_height_mapping().transpose()?;
+    let advances = store.add_glyph_delta_sets(advance_mapping.as_ref(), plan)?;
+    let mut optional_mapping =
+        |mapping: Option<Result<DeltaSetIndexMap, ReadError>>| match mapping.transpose()? {
+            Some(mapping) => store.add_glyph_delta_sets(Some(&mapping), plan).map(Some),
+            None => Ok(None),
+        };
+    let tsbs = optional_mapping(vvar.tsb_mapping())?;
+    let bsbs = optional_mapping(vvar.bsb_mapping())?;
+    let v_orgs = optional_mapping(vvar.v_org_mapping())?;
+
+    let (store, remapping) = store.build();
+    Ok(Vvar::new(
+        MajorMinor::VERSION_1_0,
+        store,
+        Some(build_delta_set_index_map(&advances, &remapping)),
+        tsbs.map(|ids| build_delta_set_index_map(&ids, &remapping)),
+        bsbs.map(|ids| build_delta_set_index_map(&ids, &remapping)),
+        v_orgs.map(|ids| build_delta_set_index_map(&ids, &remapping)),
+    ))
+}
+
+/// Add the VVAR advance deltas at the pinned location to the default advance heights.
+///
+/// See [`hvar::instance_advances`](crate::hvar::instance_advances).
+pub(crate) fn instance_advances(
+    font: &FontRef,
+    location: &[Option<F2Dot14>],
+    vmtx: &mut Vmtx,
+) -> Result<(), ReadError> {
+    if location.iter().all(Option::is_none) {
+        return Ok(());
+    }
+    let Ok(vvar) = font.vvar() else {
+        return Ok(());
+    };
+    let coords = default_coords(location);
+
+    expand_long_metrics(&mut vmtx.v_metrics, &mut vmtx.top_side_bearings);
+    for (gid, metric) in vmtx.v_metrics.iter_mut().enumerate() {
+        let delta = vvar
+            .advance_height_delta(GlyphId::new(gid as u32), &coords)?
+            .to_i32();
+        metric.advance = (metric.advance as i32 + delta).clamp(0, u16::MAX as i32) as u16;
+    }
+    Ok(())
+}
@@ -6,7 +6,6 @@
 //! To generate the expected output files, pass GEN_EXPECTED_OUTPUTS=1 as an
 //! environment variable.
 
-use int_set::IntSet;
 use klippa::{parse_unicodes, subset_font, Plan};
 use std::fmt::Write;
 use std::fs;
@@ -312,11 +311,14 @@ fn gen_subset_font_file(font_file: &str, subset: &str, output_file: &PathBuf) {
     let org_font_bytes = std::fs::read(org_font_file).unwrap();
     let font = FontRef::new(&org_font_bytes).unwrap();
 
-    let gids = IntSet::empty();
     let unicodes = parse_unicodes(subset).unwrap();
-    let plan = Plan::new(&gids, &unicodes, &font);
+    let plan = Plan::builder(&font)
+        .with_unicodes(&unicodes)
+        .build()
+        .unwrap();
 
-    subset_font(font, &plan, output_file);
+    let output_bytes = subset_font(font, &plan).unwrap();
+    std::fs::write(output_file, output_bytes).unwrap();
     //TODO: re-enable OTS check
     //assert_has_ots_exec();
     //assert_check_ots(&output_file);
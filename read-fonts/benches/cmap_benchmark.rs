@@ -0,0 +1,143 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use read_fonts::{
+    tables::cmap::{Cmap12, Cmap4},
+    types::GlyphId,
+    FontData, FontRead,
+};
+
+/// Number of segments (format 4) or groups (format 12) in the test subtables.
+///
+/// This is similar to what is found in large CJK fonts.
+const NUM_RANGES: u32 = 256;
+/// Distance between the starts of consecutive ranges.
+const RANGE_STRIDE: u32 = 100;
+/// Number of codepoints covered by each range.
+const RANGE_LEN: u32 = 50;
+
+/// Builds a format 4 subtable with `NUM_RANGES` segments, plus the
+/// required final 0xFFFF segment.
+fn make_cmap4() -> Vec<u8> {
+    let seg_count = NUM_RANGES as u16 + 1;
+    let ranges = (0..NUM_RANGES)
+        .map(|i| (i * RANGE_STRIDE, i * RANGE_STRIDE + RANGE_LEN - 1))
+        .chain(std::iter::once((0xFFFF, 0xFFFF)))
+        .map(|(start, end)| (start as u16, end as u16))
+        .collect::<Vec<_>>();
+    let search_range = 2 * (1u16 << seg_count.ilog2());
+    let mut data = Vec::new();
+    let mut push = |val: u16| data.extend_from_slice(&val.to_be_bytes());
+    push(4);
+    push(16 + 8 * seg_count);
+    push(0);
+    push(seg_count * 2);
+    push(search_range);
+    push(seg_count.ilog2() as u16);
+    push(seg_count * 2 - search_range);
+    ranges.iter().for_each(|(_, end)| push(*end));
+    push(0);
+    ranges.iter().for_each(|(start, _)| push(*start));
+    ranges.iter().for_each(|_| push(1));
+    ranges.iter().for_each(|_| push(0));
+    data
+}
+
+/// Builds a format 12 subtable with `NUM_RANGES` groups.
+fn make_cmap12() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&12u16.to_be_bytes());
+    data.extend_from_slice(&0u16.to_be_bytes());
+    data.extend_from_slice(&(16 + 12 * NUM_RANGES).to_be_bytes());
+    data.extend_from_slice(&0u32.to_be_bytes());
+    data.extend_from_slice(&NUM_RANGES.to_be_bytes());
+    for i in 0..NUM_RANGES {
+        let start = i * RANGE_STRIDE;
+        for val in [start, start + RANGE_LEN - 1, i * RANGE_LEN + 1] {
+            data.extend_from_slice(&val.to_be_bytes());
+        }
+    }
+    data
+}
+
+/// Format 4 lookup using a linear scan over the segments, for comparison.
+///
+/// This only handles segments that use `idDelta`, which is all that the
+/// test subtable contains.
+fn cmap4_linear(cmap: &Cmap4, codepoint: u32) -> Option<GlyphId> {
+    let codepoint = u16::try_from(codepoint).ok()?;
+    let i = cmap
+        .end_code()
+        .iter()
+        .position(|end| codepoint <= end.get())?;
+    (codepoint >= cmap.start_code().get(i)?.get()).then(|| {
+        let delta = cmap.id_delta().get(i).unwrap().get();
+        GlyphId::new(codepoint.wrapping_add_signed(delta) as u32)
+    })
+}
+
+/// Format 12 lookup using a linear scan over the groups, for comparison.
+fn cmap12_linear(cmap: &Cmap12, codepoint: u32) -> Option<GlyphId> {
+    cmap.groups()
+        .iter()
+        .find(|group| (group.start_char_code()..=group.end_char_code()).contains(&codepoint))
+        .map(|group| GlyphId::new(group.start_glyph_id() + codepoint - group.start_char_code()))
+}
+
+/// A mix of mapped and unmapped codepoints spread over the whole range.
+fn codepoints() -> Vec<u32> {
+    (0..NUM_RANGES * RANGE_STRIDE).step_by(7).collect()
+}
+
+pub fn cmap4_benchmark(c: &mut Criterion) {
+    let data = make_cmap4();
+    let cmap = Cmap4::read(FontData::new(&data)).unwrap();
+    let codepoints = codepoints();
+    for cp in &codepoints {
+        assert_eq!(cmap.map_codepoint(*cp), cmap4_linear(&cmap, *cp));
+    }
+
+    let mut group = c.benchmark_group("BM_Cmap4MapCodepoint");
+    group.bench_function(BenchmarkId::new("binary", NUM_RANGES), |b| {
+        b.iter(|| {
+            for cp in &codepoints {
+                black_box(cmap.map_codepoint(*cp));
+            }
+        })
+    });
+    group.bench_function(BenchmarkId::new("linear", NUM_RANGES), |b| {
+        b.iter(|| {
+            for cp in &codepoints {
+                black_box(cmap4_linear(&cmap, *cp));
+            }
+        })
+    });
+    group.finish();
+}
+
+pub fn cmap12_benchmark(c: &mut Criterion) {
+    let data = make_cmap12();
+    let cmap = Cmap12::read(FontData::new(&data)).unwrap();
+    let codepoints = codepoints();
+    for cp in &codepoints {
+        assert_eq!(cmap.map_codepoint(*cp), cmap12_linear(&cmap, *cp));
+    }
+
+    let mut group = c.benchmark_group("BM_Cmap12MapCodepoint");
+    group.bench_function(BenchmarkId::new("binary", NUM_RANGES), |b| {
+        b.iter(|| {
+            for cp in &codepoints {
+                black_box(cmap.map_codepoint(*cp));
+            }
+        })
+    });
+    group.bench_function(BenchmarkId::new("linear", NUM_RANGES), |b| {
+        b.iter(|| {
+            for cp in &codepoints {
+                black_box(cmap12_linear(&cmap, *cp));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, cmap4_benchmark, cmap12_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,348 @@
+// THIS FILE IS AUTOGENERATED.
+// Any changes to this file will be overwritten.
+// For more information about how codegen works, see font-codegen/README.md
+
+#[allow(unused_imports)]
+use crate::codegen_prelude::*;
+
+/// The OpenType [kern](https://learn.microsoft.com/en-us/typography/opentype/spec/kern) table
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct KernMarker {
+    subtable_data_byte_len: usize,
+}
+
+impl KernMarker {
+    fn version_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + u16::RAW_BYTE_LEN
+    }
+    fn num_tables_byte_range(&self) -> Range<usize> {
+        let start = self.version_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+    fn subtable_data_byte_range(&self) -> Range<usize> {
+        let start = self.num_tables_byte_range().end;
+        start..start + self.subtable_data_byte_len
+    }
+}
+
+impl TopLevelTable for Kern<'_> {
+    /// `kern`
+    const TAG: Tag = Tag::new(b"kern");
+}
+
+impl<'a> FontRead<'a> for Kern<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<u16>();
+        cursor.advance::<u16>();
+        let subtable_data_byte_len = cursor.remaining_bytes() / u8::RAW_BYTE_LEN * u8::RAW_BYTE_LEN;
+        cursor.advance_by(subtable_data_byte_len);
+        cursor.finish(KernMarker {
+            subtable_data_byte_len,
+        })
+    }
+}
+
+/// The OpenType [kern](https://learn.microsoft.com/en-us/typography/opentype/spec/kern) table
+pub type Kern<'a> = TableRef<'a, KernMarker>;
+
+impl<'a> Kern<'a> {
+    /// Table version number—set to 0.
+    pub fn version(&self) -> u16 {
+        let range = self.shape.version_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Number of subtables in the kerning table.
+    pub fn num_tables(&self) -> u16 {
+        let range = self.shape.num_tables_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// The subtables, which are variable-length.
+    pub fn subtable_data(&self) -> &'a [u8] {
+        let range = self.shape.subtable_data_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "traversal")]
+impl<'a> SomeTable<'a> for Kern<'a> {
+    fn type_name(&self) -> &str {
+        "Kern"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new("version", self.version())),
+            1usize => Some(Field::new("num_tables", self.num_tables())),
+            2usize => Some(Field::new("subtable_data", self.subtable_data())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "traversal")]
+impl<'a> std::fmt::Debug for Kern<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// A kerning subtable.
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct KernSubtableMarker {
+    data_byte_len: usize,
+}
+
+impl KernSubtableMarker {
+    fn version_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + u16::RAW_BYTE_LEN
+    }
+    fn length_byte_range(&self) -> Range<usize> {
+        let start = self.version_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+    fn coverage_byte_range(&self) -> Range<usize> {
+        let start = self.length_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+    fn data_byte_range(&self) -> Range<usize> {
+        let start = self.coverage_byte_range().end;
+        start..start + self.data_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for KernSubtable<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        cursor.advance::<u16>();
+        cursor.advance::<u16>();
+        cursor.advance::<u16>();
+        let data_byte_len = cursor.remaining_bytes() / u8::RAW_BYTE_LEN * u8::RAW_BYTE_LEN;
+        cursor.advance_by(data_byte_len);
+        cursor.finish(KernSubtableMarker { data_byte_len })
+    }
+}
+
+/// A kerning subtable.
+pub type KernSubtable<'a> = TableRef<'a, KernSubtableMarker>;
+
+impl<'a> KernSubtable<'a> {
+    /// Kern subtable version number—set to 0.
+    pub fn version(&self) -> u16 {
+        let range = self.shape.version_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Length of the subtable, in bytes (including this header).
+    pub fn length(&self) -> u16 {
+        let range = self.shape.length_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// What type of information is contained in this table. The high
+    /// byte is the subtable format.
+    pub fn coverage(&self) -> u16 {
+        let range = self.shape.coverage_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// The format-specific subtable data.
+    pub fn data(&self) -> &'a [u8] {
+        let range = self.shape.data_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "traversal")]
+impl<'a> SomeTable<'a> for KernSubtable<'a> {
+    fn type_name(&self) -> &str {
+        "KernSubtable"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new("version", self.version())),
+            1usize => Some(Field::new("length", self.length())),
+            2usize => Some(Field::new("coverage", self.coverage())),
+            3usize => Some(Field::new("data", self.data())),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "traversal")]
+impl<'a> std::fmt::Debug for KernSubtable<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// Format 0 kerning data: an ordered list of kerning pairs.
+#[derive(Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct Kern0Marker {
+    pairs_byte_len: usize,
+}
+
+impl Kern0Marker {
+    fn num_pairs_byte_range(&self) -> Range<usize> {
+        let start = 0;
+        start..start + u16::RAW_BYTE_LEN
+    }
+    fn search_range_byte_range(&self) -> Range<usize> {
+        let start = self.num_pairs_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+    fn entry_selector_byte_range(&self) -> Range<usize> {
+        let start = self.search_range_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+    fn range_shift_byte_range(&self) -> Range<usize> {
+        let start = self.entry_selector_byte_range().end;
+        start..start + u16::RAW_BYTE_LEN
+    }
+    fn pairs_byte_range(&self) -> Range<usize> {
+        let start = self.range_shift_byte_range().end;
+        start..start + self.pairs_byte_len
+    }
+}
+
+impl<'a> FontRead<'a> for Kern0<'a> {
+    fn read(data: FontData<'a>) -> Result<Self, ReadError> {
+        let mut cursor = data.cursor();
+        let num_pairs: u16 = cursor.read()?;
+        cursor.advance::<u16>();
+        cursor.advance::<u16>();
+        cursor.advance::<u16>();
+        let pairs_byte_len = (num_pairs as usize)
+            .checked_mul(Kern0Pair::RAW_BYTE_LEN)
+            .ok_or(ReadError::OutOfBounds)?;
+        cursor.advance_by(pairs_byte_len);
+        cursor.finish(Kern0Marker { pairs_byte_len })
+    }
+}
+
+/// Format 0 kerning data: an ordered list of kerning pairs.
+pub type Kern0<'a> = TableRef<'a, Kern0Marker>;
+
+impl<'a> Kern0<'a> {
+    /// This gives the number of kerning pairs in the table.
+    pub fn num_pairs(&self) -> u16 {
+        let range = self.shape.num_pairs_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// The largest power of two less than or equal to the value of
+    /// num_pairs, multiplied by the size in bytes of an entry in the table.
+    pub fn search_range(&self) -> u16 {
+        let range = self.shape.search_range_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// This is calculated as log2 of the largest power of two less than or
+    /// equal to the value of num_pairs.
+    pub fn entry_selector(&self) -> u16 {
+        let range = self.shape.entry_selector_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// The value of num_pairs minus the largest power of two less than or
+    /// equal to num_pairs, and then multiplied by the size in bytes of an
+    /// entry in the table.
+    pub fn range_shift(&self) -> u16 {
+        let range = self.shape.range_shift_byte_range();
+        self.data.read_at(range.start).unwrap()
+    }
+
+    /// Kerning pairs, sorted by left and then right glyph.
+    pub fn pairs(&self) -> &'a [Kern0Pair] {
+        let range = self.shape.pairs_byte_range();
+        self.data.read_array(range).unwrap()
+    }
+}
+
+#[cfg(feature = "traversal")]
+impl<'a> SomeTable<'a> for Kern0<'a> {
+    fn type_name(&self) -> &str {
+        "Kern0"
+    }
+    fn get_field(&self, idx: usize) -> Option<Field<'a>> {
+        match idx {
+            0usize => Some(Field::new("num_pairs", self.num_pairs())),
+            1usize => Some(Field::new("search_range", self.search_range())),
+            2usize => Some(Field::new("entry_selector", self.entry_selector())),
+            3usize => Some(Field::new("range_shift", self.range_shift())),
+            4usize => Some(Field::new(
+                "pairs",
+                traversal::FieldType::array_of_records(
+                    stringify!(Kern0Pair),
+                    self.pairs(),
+                    self.offset_data(),
+                ),
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "traversal")]
+impl<'a> std::fmt::Debug for Kern0<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (self as &dyn SomeTable<'a>).fmt(f)
+    }
+}
+
+/// A kerning pair in a format 0 subtable.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, bytemuck :: AnyBitPattern)]
+#[repr(C)]
+#[repr(packed)]
+pub struct Kern0Pair {
+    /// The glyph index for the left-hand glyph in the kerning pair.
+    pub left: BigEndian<GlyphId16>,
+    /// The glyph index for the right-hand glyph in the kerning pair.
+    pub right: BigEndian<GlyphId16>,
+    /// The kerning value for the above pair, in design units.
+    pub value: BigEndian<FWord>,
+}
+
+impl Kern0Pair {
+    /// The glyph index for the left-hand glyph in the kerning pair.
+    pub fn left(&self) -> GlyphId16 {
+        self.left.get()
+    }
+
+    /// The glyph index for the right-hand glyph in the kerning pair.
+    pub fn right(&self) -> GlyphId16 {
+        self.right.get()
+    }
+
+    /// The kerning value for the above pair, in design units.
+    pub fn value(&self) -> FWord {
+        self.value.get()
+    }
+}
+
+impl FixedSize for Kern0Pair {
+    const RAW_BYTE_LEN: usize =
+        GlyphId16::RAW_BYTE_LEN + GlyphId16::RAW_BYTE_LEN + FWord::RAW_BYTE_LEN;
+}
+
+#[cfg(feature = "traversal")]
+impl<'a> SomeRecord<'a> for Kern0Pair {
+    fn traverse(self, data: FontData<'a>) -> RecordResolver<'a> {
+        RecordResolver {
+            name: "Kern0Pair",
+            get_field: Box::new(move |idx, _data| match idx {
+                0usize => Some(Field::new("left", self.left())),
+                1usize => Some(Field::new("right", self.right())),
+                2usize => Some(Field::new("value", self.value())),
+                _ => None,
+            }),
+            data,
+        }
+    }
+}
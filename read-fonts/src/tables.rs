@@ -25,6 +25,7 @@ pub mod head;
 pub mod hhea;
 pub mod hmtx;
 pub mod hvar;
+pub mod kern;
 pub mod layout;
 pub mod loca;
 pub mod maxp;
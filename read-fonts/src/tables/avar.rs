@@ -2,32 +2,43 @@
 
 include!("../../generated/generated_avar.rs");
 
+impl<'a> Avar<'a> {
+    /// Applies the segment map for the axis at `axis_index` to the
+    /// normalized coordinate `normalized_value`.
+    ///
+    /// Axes are in the same order as in the `fvar` table. If there is no
+    /// segment map for the axis, the value is returned unchanged.
+    pub fn remap(&self, axis_index: usize, normalized_value: f32) -> f32 {
+        match self.axis_segment_maps().get(axis_index) {
+            Some(Ok(segment_maps)) => segment_maps
+                .apply(Fixed::from_f64(normalized_value as f64))
+                .to_f64() as f32,
+            _ => normalized_value,
+        }
+    }
+}
+
 impl<'a> SegmentMaps<'a> {
     /// Applies the piecewise linear mapping to the specified coordinate.
     pub fn apply(&self, coord: Fixed) -> Fixed {
-        let mut prev = AxisValueMap {
-            from_coordinate: Default::default(),
-            to_coordinate: Default::default(),
+        let maps = self.axis_value_maps();
+        // maps are sorted by from_coordinate, so find the first one that
+        // is not below the coordinate
+        let ix = maps.partition_point(|map| map.from_coordinate().to_fixed() < coord);
+        let Some(next) = maps.get(ix) else {
+            return coord;
         };
-        for (i, axis_value_map) in self.axis_value_maps().iter().enumerate() {
-            use core::cmp::Ordering::*;
-            let from = axis_value_map.from_coordinate().to_fixed();
-            match from.cmp(&coord) {
-                Equal => return axis_value_map.to_coordinate().to_fixed(),
-                Greater => {
-                    if i == 0 {
-                        return coord;
-                    }
-                    let to = axis_value_map.to_coordinate().to_fixed();
-                    let prev_from = prev.from_coordinate().to_fixed();
-                    let prev_to = prev.to_coordinate().to_fixed();
-                    return prev_to + (to - prev_to).mul_div(coord - prev_from, from - prev_from);
-                }
-                _ => {}
-            }
-            prev = *axis_value_map;
+        let from = next.from_coordinate().to_fixed();
+        let to = next.to_coordinate().to_fixed();
+        if from == coord {
+            return to;
         }
-        coord
+        let Some(prev) = ix.checked_sub(1).and_then(|ix| maps.get(ix)) else {
+            return coord;
+        };
+        let prev_from = prev.from_coordinate().to_fixed();
+        let prev_to = prev.to_coordinate().to_fixed();
+        prev_to + (to - prev_to).mul_div(coord - prev_from, from - prev_from)
     }
 }
 
@@ -156,4 +167,15 @@ mod tests {
                 .collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn remap() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let avar = font.avar().unwrap();
+        assert_eq!(avar.remap(0, -0.5), -0.375);
+        assert_eq!(avar.remap(0, 0.5), 0.600_006_1);
+        assert_eq!(avar.remap(0, 1.0), 1.0);
+        // no segment map for this axis
+        assert_eq!(avar.remap(1, 0.5), 0.5);
+    }
 }
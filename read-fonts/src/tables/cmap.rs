@@ -31,6 +31,14 @@ impl<'a> Cmap<'a> {
             if let Ok(subtable) = record.subtable(self.offset_data()) {
                 if let Some(gid) = match subtable {
                     CmapSubtable::Format4(format4) => format4.map_codepoint(codepoint),
+                    // these formats are also used for legacy encodings, such
+                    // as Mac Roman, whose codes aren't Unicode codepoints
+                    CmapSubtable::Format6(format6) if record.is_unicode() => {
+                        format6.map_codepoint(codepoint)
+                    }
+                    CmapSubtable::Format10(format10) if record.is_unicode() => {
+                        format10.map_codepoint(codepoint)
+                    }
                     CmapSubtable::Format12(format12) => format12.map_codepoint(codepoint),
                     _ => None,
                 } {
@@ -58,6 +66,20 @@ impl<'a> Cmap<'a> {
     }
 }
 
+impl EncodingRecord {
+    /// Returns `true` if the record's subtable maps Unicode codepoints.
+    ///
+    /// This is the case for the Unicode platform, and for the Windows
+    /// Unicode BMP (3, 1) and full repertoire (3, 10) encodings.
+    pub fn is_unicode(&self) -> bool {
+        match self.platform_id() {
+            PlatformId::Unicode => true,
+            PlatformId::Windows => matches!(self.encoding_id(), 1 | 10),
+            _ => false,
+        }
+    }
+}
+
 impl<'a> Cmap4<'a> {
     /// Maps a codepoint to a nominal glyph identifier.
     pub fn map_codepoint(&self, codepoint: impl Into<u32>) -> Option<GlyphId> {
@@ -90,6 +112,35 @@ impl<'a> Cmap4<'a> {
         Cmap4Iter::new(self.clone())
     }
 
+    /// Returns an iterator over the `(start_code, end_code, id_delta)` of each
+    /// segment in the subtable.
+    ///
+    /// Both codes are inclusive. The delta is `Some` for segments that map
+    /// each codepoint to `codepoint + id_delta` (modulo 65536), and `None`
+    /// for segments whose glyphs are stored in the glyph id array, which may
+    /// leave some codepoints of the segment unmapped.
+    ///
+    /// The final `0xFFFF` segment required by the spec is not included.
+    pub fn segments(&self) -> impl Iterator<Item = (u32, u32, Option<u16>)> + 'a {
+        let seg_count = self.seg_count_x2() as usize / 2;
+        let start_codes = self.start_code();
+        let end_codes = self.end_code();
+        let deltas = self.id_delta();
+        let range_offsets = self.id_range_offsets();
+        (0..seg_count).map_while(move |i| {
+            let start = start_codes.get(i)?.get();
+            let end = end_codes.get(i)?.get();
+            if i == seg_count - 1 && start == 0xFFFF && end == 0xFFFF {
+                return None;
+            }
+            let delta = match range_offsets.get(i)?.get() {
+                0 => Some(deltas.get(i)?.get() as u16),
+                _ => None,
+            };
+            Some((start as u32, end as u32, delta))
+        })
+    }
+
     /// Does the final phase of glyph id lookup.
     ///
     /// Shared between Self::map and Cmap4Iter.
@@ -169,6 +220,24 @@ impl<'a> Iterator for Cmap4Iter<'a> {
     }
 }
 
+impl<'a> Cmap6<'a> {
+    /// Maps a codepoint to a nominal glyph identifier.
+    pub fn map_codepoint(&self, codepoint: impl Into<u32>) -> Option<GlyphId> {
+        let index = codepoint.into().checked_sub(self.first_code() as u32)?;
+        let gid = self.glyph_id_array().get(index as usize)?.get();
+        (gid != 0).then_some(GlyphId::from(gid))
+    }
+}
+
+impl<'a> Cmap10<'a> {
+    /// Maps a codepoint to a nominal glyph identifier.
+    pub fn map_codepoint(&self, codepoint: impl Into<u32>) -> Option<GlyphId> {
+        let index = codepoint.into().checked_sub(self.start_char_code())?;
+        let gid = self.glyph_id_array().get(index as usize)?.get();
+        (gid != 0).then_some(GlyphId::from(gid))
+    }
+}
+
 impl<'a> Cmap12<'a> {
     /// Maps a codepoint to a nominal glyph identifier.
     pub fn map_codepoint(&self, codepoint: impl Into<u32>) -> Option<GlyphId> {
@@ -624,6 +693,107 @@ mod tests {
         assert_eq!(mappings, &[(259, 236), (262, 326)]);
     }
 
+    #[test]
+    fn cmap4_segments() {
+        #[rustfmt::skip]
+        let cmap4_data: &[u16] = &[
+            // format, length, lang
+            4, 0, 0,
+            // segCountX2
+            6,
+            // bin search data
+            0, 0, 0,
+            // end code
+            0x43, 262, 0xFFFF,
+            // reserved pad
+            0,
+            // start code
+            0x41, 259, 0xFFFF,
+            // id delta
+            0xFFF0, 0, 1,
+            // id range offset
+            0, 4, 0,
+            // glyph ids
+            236, 0, 0, 326,
+        ];
+        let mut buf = BeBuffer::new();
+        for &word in cmap4_data {
+            buf = buf.push(word);
+        }
+        let cmap4 = Cmap4::read(FontData::new(&buf)).unwrap();
+        let segments = cmap4.segments().collect::<Vec<_>>();
+        assert_eq!(segments, &[(0x41, 0x43, Some(0xFFF0)), (259, 262, None)]);
+        // the segments cover every mapped codepoint
+        for (codepoint, _) in cmap4.iter() {
+            assert!(segments
+                .iter()
+                .any(|(start, end, _)| (*start..=*end).contains(&codepoint)));
+        }
+        assert_eq!(cmap4.map_codepoint(0x41u32), Some(GlyphId::new(0x31)));
+
+        let font = FontRef::new(font_test_data::SIMPLE_GLYF).unwrap();
+        let cmap4 = find_cmap4(&font.cmap().unwrap()).unwrap();
+        let mapped = cmap4
+            .segments()
+            .map(|(start, end, _)| end - start + 1)
+            .sum::<u32>();
+        assert!(mapped >= cmap4.iter().count() as u32);
+    }
+
+    #[test]
+    fn cmap6_map_codepoint() {
+        let buf = BeBuffer::new()
+            // format, length, language
+            .extend([6u16, 16, 0])
+            // first code, entry count
+            .extend([0x41u16, 3])
+            // glyph ids
+            .extend([5u16, 0, 7]);
+        let cmap6 = Cmap6::read(FontData::new(&buf)).unwrap();
+        assert_eq!(cmap6.map_codepoint('A'), Some(GlyphId::new(5)));
+        assert_eq!(cmap6.map_codepoint('B'), None);
+        assert_eq!(cmap6.map_codepoint('C'), Some(GlyphId::new(7)));
+        assert_eq!(cmap6.map_codepoint('@'), None);
+        assert_eq!(cmap6.map_codepoint('D'), None);
+
+        // the subtable is also used when mapping through the cmap table
+        let cmap = BeBuffer::new()
+            // version, numTables
+            .extend([0u16, 1])
+            // platform, encoding, offset
+            .extend([3u16, 1])
+            .push(12u32)
+            .extend(buf.iter().copied());
+        let cmap = Cmap::read(FontData::new(&cmap)).unwrap();
+        assert_eq!(cmap.map_codepoint('C'), Some(GlyphId::new(7)));
+        assert_eq!(cmap.map_codepoint('B'), None);
+
+        // but not for a Mac Roman subtable, whose codes aren't codepoints
+        let mac_roman = BeBuffer::new()
+            .extend([0u16, 1])
+            .extend([1u16, 0])
+            .push(12u32)
+            .extend(buf.iter().copied());
+        let mac_roman = Cmap::read(FontData::new(&mac_roman)).unwrap();
+        assert_eq!(mac_roman.map_codepoint('C'), None);
+    }
+
+    #[test]
+    fn cmap10_map_codepoint() {
+        let buf = BeBuffer::new()
+            // format, reserved
+            .extend([10u16, 0])
+            // length, language, start char code, num chars
+            .extend([26u32, 0, 0x1F600, 2])
+            // glyph ids
+            .extend([12u16, 13]);
+        let cmap10 = Cmap10::read(FontData::new(&buf)).unwrap();
+        assert_eq!(cmap10.map_codepoint(0x1F600_u32), Some(GlyphId::new(12)));
+        assert_eq!(cmap10.map_codepoint(0x1F601_u32), Some(GlyphId::new(13)));
+        assert_eq!(cmap10.map_codepoint(0x1F5FF_u32), None);
+        assert_eq!(cmap10.map_codepoint(0x1F602_u32), None);
+    }
+
     #[test]
     fn cmap12_iter() {
         let font = FontRef::new(font_test_data::CMAP12_FONT1).unwrap();
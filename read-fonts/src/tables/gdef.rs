@@ -14,3 +14,39 @@ use super::variations::ItemVariationStore;
 mod tests;
 
 include!("../../generated/generated_gdef.rs");
+
+impl<'a> Gdef<'a> {
+    /// Returns the contour point indices of the attachment points for
+    /// `glyph_id`, or `None` if the glyph has no entry in the attach list.
+    pub fn attachment_points(&self, glyph_id: GlyphId) -> Option<&'a [BigEndian<u16>]> {
+        self.attach_list()?.ok()?.attachment_points(glyph_id)
+    }
+
+    /// Returns an iterator over the mark glyph sets, as `(set_index, coverage)`
+    /// pairs, or `None` if the table has no mark glyph set definitions.
+    ///
+    /// The set index is the value used by lookups with the
+    /// `USE_MARK_FILTERING_SET` flag. Sets whose coverage table cannot be
+    /// read are skipped.
+    pub fn mark_glyph_sets(&self) -> Option<impl Iterator<Item = (u16, CoverageTable<'a>)> + 'a> {
+        let coverages = self.mark_glyph_sets_def()?.ok()?.coverages();
+        Some(
+            coverages
+                .iter()
+                .enumerate()
+                .filter_map(|(index, coverage)| Some((index as u16, coverage.ok()?))),
+        )
+    }
+}
+
+impl<'a> AttachList<'a> {
+    /// Returns the contour point indices of the attachment points for
+    /// `glyph_id`, or `None` if the glyph is not covered by this list.
+    pub fn attachment_points(&self, glyph_id: GlyphId) -> Option<&'a [BigEndian<u16>]> {
+        let index = self.coverage().ok()?.get(glyph_id)?;
+        self.attach_points()
+            .get(index as usize)
+            .ok()
+            .map(|points| points.point_indices())
+    }
+}
@@ -158,6 +158,16 @@ impl<'a> SimpleGlyph<'a> {
             .unwrap_or(0)
     }
 
+    /// Returns the number of contours in the glyph.
+    pub fn contour_count(&self) -> u16 {
+        self.end_pts_of_contours().len() as u16
+    }
+
+    /// Returns the total number of points, saturating at `u16::MAX`.
+    pub fn point_count(&self) -> u16 {
+        self.num_points().try_into().unwrap_or(u16::MAX)
+    }
+
     /// Returns true if the contours in the simple glyph may overlap.
     pub fn has_overlapping_contours(&self) -> bool {
         // Checks the first flag for the OVERLAP_SIMPLE bit.
@@ -249,6 +259,25 @@ impl<'a> SimpleGlyph<'a> {
             .unwrap_or_else(|| PointIter::new(&[], &[], &[]))
     }
 
+    /// Returns an iterator over the points in the glyph, annotated with
+    /// the contour structure.
+    ///
+    /// This is the same as [points](Self::points), except that each point
+    /// also records whether it ends a contour.
+    pub fn glyph_points(&self) -> impl Iterator<Item = GlyphPoint> + 'a + Clone {
+        let mut end_points = self
+            .end_pts_of_contours()
+            .iter()
+            .map(|end| end.get() as usize)
+            .peekable();
+        self.points().enumerate().map(move |(i, point)| GlyphPoint {
+            x: point.x,
+            y: point.y,
+            on_curve: point.on_curve,
+            is_last_in_contour: end_points.next_if_eq(&i).is_some(),
+        })
+    }
+
     fn points_impl(&self) -> Option<PointIter<'a>> {
         let end_points = self.end_pts_of_contours();
         let n_points = end_points.last()?.get().checked_add(1)?;
@@ -296,6 +325,19 @@ impl CurvePoint {
     }
 }
 
+/// Point in a simple glyph, along with its position in a contour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlyphPoint {
+    /// X coordinate.
+    pub x: i16,
+    /// Y coordinate.
+    pub y: i16,
+    /// True if this is an on-curve point.
+    pub on_curve: bool,
+    /// True if this is the final point of a contour.
+    pub is_last_in_contour: bool,
+}
+
 #[derive(Clone)]
 struct PointIter<'a> {
     flags: Cursor<'a>,
@@ -1227,6 +1269,27 @@ mod tests {
                 (10, 95, true),
             ]
         );
+        assert_eq!(simple_glyph.contour_count(), 2);
+        assert_eq!(simple_glyph.point_count(), 8);
+        let points = simple_glyph.glyph_points().collect::<Vec<_>>();
+        assert_eq!(points.len(), 8);
+        assert_eq!(
+            points[4],
+            GlyphPoint {
+                x: 10,
+                y: 5,
+                on_curve: true,
+                is_last_in_contour: false
+            }
+        );
+        assert_eq!(
+            points
+                .iter()
+                .enumerate()
+                .filter_map(|(i, pt)| pt.is_last_in_contour.then_some(i))
+                .collect::<Vec<_>>(),
+            [3, 7]
+        );
     }
 
     // Test helper to enumerate all TrueType glyphs in the given font
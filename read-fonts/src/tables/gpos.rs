@@ -30,6 +30,27 @@ pub type PositionSequenceContext<'a> = super::layout::SequenceContext<'a>;
 /// A GPOS [ChainedSequenceContext](super::layout::ChainedSequenceContext)
 pub type PositionChainContext<'a> = super::layout::ChainedSequenceContext<'a>;
 
+impl<'a> Gpos<'a> {
+    /// Returns the distinct tags of the features in this table.
+    #[cfg(feature = "std")]
+    pub fn feature_tags(&self) -> Result<std::collections::BTreeSet<Tag>, ReadError> {
+        Ok(self.feature_list()?.feature_tags())
+    }
+
+    /// Returns the `(script, language)` pairs of the language systems that
+    /// include the feature with the given tag.
+    ///
+    /// See [`ScriptList::scripts_with_feature`].
+    #[cfg(feature = "std")]
+    pub fn scripts_with_feature(
+        &self,
+        feature_tag: Tag,
+    ) -> Result<Vec<(Tag, Option<Tag>)>, ReadError> {
+        self.script_list()?
+            .scripts_with_feature(&self.feature_list()?, feature_tag)
+    }
+}
+
 impl<'a> AnchorTable<'a> {
     /// Attempt to resolve the `Device` or `VariationIndex` table for the
     /// x_coordinate, if present
@@ -50,6 +71,47 @@ impl<'a> AnchorTable<'a> {
     }
 }
 
+/// The [type](https://learn.microsoft.com/en-us/typography/opentype/spec/gpos#table-organization)
+/// of a GPOS lookup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u16)]
+pub enum GposLookupType {
+    Single = 1,
+    Pair = 2,
+    Cursive = 3,
+    MarkToBase = 4,
+    MarkToLigature = 5,
+    MarkToMark = 6,
+    Context = 7,
+    ChainedContext = 8,
+    Extension = 9,
+}
+
+impl GposLookupType {
+    /// Create from a raw lookup type, returning `None` if it is unknown.
+    pub fn new(raw: u16) -> Option<Self> {
+        match raw {
+            1 => Some(Self::Single),
+            2 => Some(Self::Pair),
+            3 => Some(Self::Cursive),
+            4 => Some(Self::MarkToBase),
+            5 => Some(Self::MarkToLigature),
+            6 => Some(Self::MarkToMark),
+            7 => Some(Self::Context),
+            8 => Some(Self::ChainedContext),
+            9 => Some(Self::Extension),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Lookup<'_, T> {
+    /// Returns the type of this lookup, if it is a known GPOS lookup type.
+    pub fn gpos_type(&self) -> Option<GposLookupType> {
+        GposLookupType::new(self.lookup_type())
+    }
+}
+
 impl<'a, T: FontRead<'a>> ExtensionLookup<'a, T> for ExtensionPosFormat1<'a, T> {
     fn extension(&self) -> Result<T, ReadError> {
         self.extension()
@@ -84,6 +146,29 @@ impl<'a> PositionLookup<'a> {
         self.of_unit_type().lookup_type()
     }
 
+    /// Returns the lookup type, resolving extension lookups to the type of
+    /// the lookup they wrap.
+    ///
+    /// For an extension lookup, this is the type declared by its first
+    /// subtable. If that subtable can't be read, the extension type
+    /// (9) is returned.
+    pub fn effective_type(&self) -> u16 {
+        let raw_lookup = self.of_unit_type();
+        let lookup_type = raw_lookup.lookup_type();
+        if lookup_type != GposLookupType::Extension as u16 {
+            return lookup_type;
+        }
+        raw_lookup
+            .subtable_offsets()
+            .first()
+            .and_then(|offset| {
+                let ext: ExtensionPosFormat1<()> =
+                    offset.get().resolve(raw_lookup.offset_data()).ok()?;
+                Some(ext.extension_lookup_type())
+            })
+            .unwrap_or(lookup_type)
+    }
+
     pub fn mark_filtering_set(&self) -> Option<u16> {
         self.of_unit_type().mark_filtering_set()
     }
@@ -138,3 +223,54 @@ impl<'a> PositionLookup<'a> {
         }
     }
 }
+
+impl<'a> SinglePos<'a> {
+    /// Returns the value record for `glyph_id`, or `None` if the glyph is
+    /// not covered by this subtable.
+    ///
+    /// In format 1 all covered glyphs share the same value record, and in
+    /// format 2 each covered glyph has its own.
+    pub fn value_record_for(&self, glyph_id: GlyphId) -> Option<ValueRecord> {
+        match self {
+            SinglePos::Format1(table) => table.value_record_for(glyph_id),
+            SinglePos::Format2(table) => table.value_record_for(glyph_id),
+        }
+    }
+}
+
+impl SinglePosFormat1<'_> {
+    /// Returns the value record for `glyph_id`, or `None` if the glyph is
+    /// not covered by this subtable.
+    pub fn value_record_for(&self, glyph_id: GlyphId) -> Option<ValueRecord> {
+        self.coverage().ok()?.get(glyph_id)?;
+        Some(self.value_record())
+    }
+}
+
+impl SinglePosFormat2<'_> {
+    /// Returns the value record for `glyph_id`, or `None` if the glyph is
+    /// not covered by this subtable.
+    pub fn value_record_for(&self, glyph_id: GlyphId) -> Option<ValueRecord> {
+        let index = self.coverage().ok()?.get(glyph_id)?;
+        self.value_records().get(index as usize).ok()
+    }
+}
+
+impl<'a> CursivePosFormat1<'a> {
+    /// Returns the `(entry, exit)` anchors for `glyph_id`, or `None` if the
+    /// glyph is not covered by this subtable.
+    ///
+    /// Either anchor may be `None` if the glyph does not connect on that side.
+    pub fn entry_exit_for(
+        &self,
+        glyph_id: GlyphId,
+    ) -> Option<(Option<AnchorTable<'a>>, Option<AnchorTable<'a>>)> {
+        let index = self.coverage().ok()?.get(glyph_id)?;
+        let record = self.entry_exit_record().get(index as usize)?;
+        let data = self.offset_data();
+        Some((
+            record.entry_anchor(data).and_then(Result::ok),
+            record.exit_anchor(data).and_then(Result::ok),
+        ))
+    }
+}
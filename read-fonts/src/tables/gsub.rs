@@ -3,10 +3,11 @@
 //! [GSUB]: https://docs.microsoft.com/en-us/typography/opentype/spec/gsub
 
 pub use super::layout::{
-    ChainedSequenceContext, ClassDef, CoverageTable, Device, FeatureList, FeatureVariations,
-    Lookup, LookupList, ScriptList, SequenceContext,
+    ChainedSequenceContext, ClassDef, CoverageTable, Device, Feature, FeatureList,
+    FeatureVariations, Lookup, LookupList, ScriptList, SequenceContext,
 };
 use super::layout::{ExtensionLookup, LookupFlag, Subtables};
+use int_set::IntSet;
 
 #[cfg(feature = "std")]
 mod closure;
@@ -25,6 +26,45 @@ pub type SubstitutionSequenceContext<'a> = super::layout::SequenceContext<'a>;
 /// A GSUB [ChainedSequenceContext]
 pub type SubstitutionChainContext<'a> = super::layout::ChainedSequenceContext<'a>;
 
+/// The [type](https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#table-organization)
+/// of a GSUB lookup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u16)]
+pub enum GsubLookupType {
+    Single = 1,
+    Multiple = 2,
+    Alternate = 3,
+    Ligature = 4,
+    Context = 5,
+    ChainedContext = 6,
+    Extension = 7,
+    ReverseChainSingle = 8,
+}
+
+impl GsubLookupType {
+    /// Create from a raw lookup type, returning `None` if it is unknown.
+    pub fn new(raw: u16) -> Option<Self> {
+        match raw {
+            1 => Some(Self::Single),
+            2 => Some(Self::Multiple),
+            3 => Some(Self::Alternate),
+            4 => Some(Self::Ligature),
+            5 => Some(Self::Context),
+            6 => Some(Self::ChainedContext),
+            7 => Some(Self::Extension),
+            8 => Some(Self::ReverseChainSingle),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Lookup<'_, T> {
+    /// Returns the type of this lookup, if it is a known GSUB lookup type.
+    pub fn gsub_type(&self) -> Option<GsubLookupType> {
+        GsubLookupType::new(self.lookup_type())
+    }
+}
+
 impl<'a, T: FontRead<'a>> ExtensionLookup<'a, T> for ExtensionSubstFormat1<'a, T> {
     fn extension(&self) -> Result<T, ReadError> {
         self.extension()
@@ -48,6 +88,44 @@ pub enum SubstitutionSubtables<'a> {
     Reverse(SubSubtables<'a, ReverseChainSingleSubstFormat1<'a>>),
 }
 
+impl<'a> Gsub<'a> {
+    /// Return the feature substitutions that apply at the given normalized
+    /// location, as `(feature_index, alternate_feature)` pairs.
+    ///
+    /// The alternate features replace the features at the corresponding index
+    /// of the feature list. This is empty if the table has no feature variations,
+    /// or none of them apply at this location.
+    #[cfg(feature = "std")]
+    pub fn apply_feature_variations(
+        &self,
+        normalized_coords: &[F2Dot14],
+    ) -> Result<Vec<(u16, Feature<'a>)>, ReadError> {
+        match self.feature_variations().transpose()? {
+            Some(variations) => variations.substitutions_at(normalized_coords),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Returns the distinct tags of the features in this table.
+    #[cfg(feature = "std")]
+    pub fn feature_tags(&self) -> Result<std::collections::BTreeSet<Tag>, ReadError> {
+        Ok(self.feature_list()?.feature_tags())
+    }
+
+    /// Returns the `(script, language)` pairs of the language systems that
+    /// include the feature with the given tag.
+    ///
+    /// See [`ScriptList::scripts_with_feature`].
+    #[cfg(feature = "std")]
+    pub fn scripts_with_feature(
+        &self,
+        feature_tag: Tag,
+    ) -> Result<Vec<(Tag, Option<Tag>)>, ReadError> {
+        self.script_list()?
+            .scripts_with_feature(&self.feature_list()?, feature_tag)
+    }
+}
+
 impl<'a> SubstitutionLookup<'a> {
     pub fn lookup_flag(&self) -> LookupFlag {
         self.of_unit_type().lookup_flag()
@@ -58,6 +136,29 @@ impl<'a> SubstitutionLookup<'a> {
         self.of_unit_type().lookup_type()
     }
 
+    /// Returns the lookup type, resolving extension lookups to the type of
+    /// the lookup they wrap.
+    ///
+    /// For an extension lookup, this is the type declared by its first
+    /// subtable. If that subtable can't be read, the extension type
+    /// (7) is returned.
+    pub fn effective_type(&self) -> u16 {
+        let raw_lookup = self.of_unit_type();
+        let lookup_type = raw_lookup.lookup_type();
+        if lookup_type != GsubLookupType::Extension as u16 {
+            return lookup_type;
+        }
+        raw_lookup
+            .subtable_offsets()
+            .first()
+            .and_then(|offset| {
+                let ext: ExtensionSubstFormat1<()> =
+                    offset.get().resolve(raw_lookup.offset_data()).ok()?;
+                Some(ext.extension_lookup_type())
+            })
+            .unwrap_or(lookup_type)
+    }
+
     pub fn mark_filtering_set(&self) -> Option<u16> {
         self.of_unit_type().mark_filtering_set()
     }
@@ -121,4 +222,190 @@ impl<'a> SubstitutionLookup<'a> {
             other => Err(ReadError::InvalidFormat(other as _)),
         }
     }
+
+    /// Returns the number of distinct glyphs covered by this lookup.
+    ///
+    /// This is the size of the union of the coverage tables of all subtables.
+    /// For (chained) contextual lookups only the coverage of the first input
+    /// glyph is counted, since no other glyph can start a match. A lookup
+    /// with a coverage size of zero can never apply.
+    pub fn coverage_size(&self) -> Result<usize, ReadError> {
+        let mut glyphs = IntSet::empty();
+        match self.subtables()? {
+            SubstitutionSubtables::Single(tables) => union_coverages(&tables, &mut glyphs, |t| {
+                t.coverage_offset().resolve(t.offset_data())
+            }),
+            SubstitutionSubtables::Multiple(tables) => {
+                union_coverages(&tables, &mut glyphs, |t| t.coverage())
+            }
+            SubstitutionSubtables::Alternate(tables) => {
+                union_coverages(&tables, &mut glyphs, |t| t.coverage())
+            }
+            SubstitutionSubtables::Ligature(tables) => {
+                union_coverages(&tables, &mut glyphs, |t| t.coverage())
+            }
+            SubstitutionSubtables::Contextual(tables) => {
+                union_coverages(&tables, &mut glyphs, |t| t.coverage())
+            }
+            SubstitutionSubtables::ChainContextual(tables) => {
+                union_coverages(&tables, &mut glyphs, |t| t.coverage())
+            }
+            SubstitutionSubtables::Reverse(tables) => {
+                union_coverages(&tables, &mut glyphs, |t| t.coverage())
+            }
+        }?;
+        Ok(glyphs.len())
+    }
+}
+
+/// Adds the glyphs covered by each of `subtables` to `glyphs`.
+fn union_coverages<'a, T, Ext>(
+    subtables: &Subtables<'a, T, Ext>,
+    glyphs: &mut IntSet<GlyphId16>,
+    coverage: impl Fn(&T) -> Result<CoverageTable<'a>, ReadError>,
+) -> Result<(), ReadError>
+where
+    T: FontRead<'a> + 'a,
+    Ext: ExtensionLookup<'a, T> + 'a,
+{
+    for subtable in subtables.iter() {
+        glyphs.extend(coverage(&subtable?)?.iter());
+    }
+    Ok(())
+}
+
+impl<'a> MultipleSubstFormat1<'a> {
+    /// Returns the sequence that replaces `glyph_id`, or `None` if the glyph
+    /// is not covered by this subtable.
+    pub fn sequence_for(&self, glyph_id: GlyphId) -> Option<Sequence<'a>> {
+        let index = self.coverage().ok()?.get(glyph_id)?;
+        self.sequences().get(index as usize).ok()
+    }
+}
+
+impl<'a> Sequence<'a> {
+    /// Returns an iterator over the glyphs in this sequence.
+    pub fn iter(&self) -> impl Iterator<Item = GlyphId> + 'a {
+        self.substitute_glyph_ids()
+            .iter()
+            .map(|gid| GlyphId::from(gid.get()))
+    }
+}
+
+impl<'a> AlternateSubstFormat1<'a> {
+    /// Returns the set of alternates for `glyph_id`, or `None` if the glyph
+    /// is not covered by this subtable.
+    pub fn alternates_for(&self, glyph_id: GlyphId) -> Option<AlternateSet<'a>> {
+        let index = self.coverage().ok()?.get(glyph_id)?;
+        self.alternate_sets().get(index as usize).ok()
+    }
+
+    /// Returns the number of alternates for `glyph_id`.
+    pub fn alternate_count(&self, glyph_id: GlyphId) -> usize {
+        self.alternates_for(glyph_id)
+            .map(|set| set.glyph_count() as usize)
+            .unwrap_or_default()
+    }
+
+    /// Returns the `n`th alternate for `glyph_id`, if it exists.
+    pub fn nth_alternate(&self, glyph_id: GlyphId, n: usize) -> Option<GlyphId> {
+        self.alternates_for(glyph_id)?
+            .alternate_glyph_ids()
+            .get(n)
+            .map(|gid| gid.get().into())
+    }
+}
+
+impl<'a> AlternateSet<'a> {
+    /// Returns an iterator over the alternate glyphs in this set.
+    pub fn iter(&self) -> impl Iterator<Item = GlyphId> + 'a {
+        self.alternate_glyph_ids()
+            .iter()
+            .map(|gid| GlyphId::from(gid.get()))
+    }
+}
+
+impl<'a> LigatureSubstFormat1<'a> {
+    /// Returns the ligature glyph for the first ligature that matches the
+    /// start of `glyphs`, or `None` if no ligature matches.
+    ///
+    /// Ligatures are tried in the order they appear in the font, which is
+    /// their order of preference. Trailing glyphs that are not part of the
+    /// matched ligature are ignored.
+    pub fn match_sequence(&self, glyphs: &[GlyphId]) -> Option<GlyphId> {
+        let (first, rest) = glyphs.split_first()?;
+        self.possible_sequences_starting_with(*first)?
+            .find(|(components, _)| {
+                components.len() <= rest.len()
+                    && components
+                        .iter()
+                        .zip(rest)
+                        .all(|(component, gid)| GlyphId::from(component.get()) == *gid)
+            })
+            .map(|(_, ligature)| ligature)
+    }
+
+    /// Returns an iterator over the `(components, ligature_glyph)` pairs for
+    /// all ligatures starting with `glyph_id`, or `None` if the glyph is not
+    /// covered by this subtable.
+    ///
+    /// The components do not include the first glyph.
+    pub fn possible_sequences_starting_with(
+        &self,
+        glyph_id: GlyphId,
+    ) -> Option<impl Iterator<Item = (&'a [BigEndian<GlyphId16>], GlyphId)> + 'a> {
+        let index = self.coverage().ok()?.get(glyph_id)?;
+        let ligature_set = self.ligature_sets().get(index as usize).ok()?;
+        Some(
+            ligature_set
+                .ligatures()
+                .iter()
+                .filter_map(Result::ok)
+                .map(|ligature| {
+                    (
+                        ligature.component_glyph_ids(),
+                        ligature.ligature_glyph().into(),
+                    )
+                }),
+        )
+    }
+}
+
+impl ReverseChainSingleSubstFormat1<'_> {
+    /// Returns the substitute for `glyph_id` in the given context, or `None`
+    /// if the subtable does not apply.
+    ///
+    /// `backtrack_glyphs` are in reverse logical order, starting with the glyph
+    /// immediately preceding `glyph_id`, and `lookahead_glyphs` are in logical
+    /// order, starting with the glyph immediately following it. These may be
+    /// longer than the context required by the subtable.
+    pub fn substitute(
+        &self,
+        glyph_id: GlyphId,
+        backtrack_glyphs: &[GlyphId],
+        lookahead_glyphs: &[GlyphId],
+    ) -> Option<GlyphId> {
+        let index = self.coverage().ok()?.get(glyph_id)?;
+        if !context_matches(self.backtrack_coverages(), backtrack_glyphs)
+            || !context_matches(self.lookahead_coverages(), lookahead_glyphs)
+        {
+            return None;
+        }
+        self.substitute_glyph_ids()
+            .get(index as usize)
+            .map(|gid| gid.get().into())
+    }
+}
+
+fn context_matches<'a>(
+    coverages: ArrayOfOffsets<'a, CoverageTable<'a>, Offset16>,
+    glyphs: &[GlyphId],
+) -> bool {
+    coverages.len() <= glyphs.len()
+        && coverages.iter().zip(glyphs).all(|(coverage, gid)| {
+            coverage
+                .ok()
+                .and_then(|coverage| coverage.get(*gid))
+                .is_some()
+        })
 }
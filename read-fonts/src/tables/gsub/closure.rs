@@ -6,6 +6,7 @@
 use std::collections::HashSet;
 
 use font_types::GlyphId16;
+use int_set::IntSet;
 
 use crate::{
     tables::layout::{
@@ -19,7 +20,7 @@ use crate::{
 use super::{
     AlternateSubstFormat1, ChainedSequenceContext, ClassDef, Gsub, LigatureSubstFormat1,
     MultipleSubstFormat1, ReverseChainSingleSubstFormat1, SequenceContext, SingleSubst,
-    SingleSubstFormat1, SingleSubstFormat2, SubstitutionSubtables,
+    SingleSubstFormat1, SingleSubstFormat2, SubstitutionLookup, SubstitutionSubtables,
 };
 
 /// A trait for tables which participate in closure
@@ -364,6 +365,49 @@ impl SequenceContextFormat3<'_> {
     }
 }
 
+impl<'a> SequenceContextFormat3<'a> {
+    /// Return the glyphs that may be produced by the nested lookups of this
+    /// subtable, given the set of glyphs that are currently retained.
+    ///
+    /// `all_lookups` are the lookups of the GSUB lookup list, indexed by the
+    /// lookup list indices of this subtable's sequence lookup records. If the
+    /// context cannot match the retained glyphs the result is empty.
+    pub fn potential_output_glyphs(
+        &self,
+        glyph_set: &IntSet<u32>,
+        all_lookups: &[SubstitutionLookup<'a>],
+    ) -> Result<IntSet<u32>, ReadError> {
+        let mut output = IntSet::empty();
+        let mut position_glyphs = Vec::with_capacity(self.glyph_count() as usize);
+        for position in 0..self.glyph_count() as usize {
+            let glyphs = self
+                .input_coverage_at(position)?
+                .iter()
+                .filter(|gid| glyph_set.contains(gid.to_u32()))
+                .collect::<HashSet<_>>();
+            if glyphs.is_empty() {
+                return Ok(output);
+            }
+            position_glyphs.push(glyphs);
+        }
+
+        for record in self.seq_lookup_records() {
+            let Some(glyphs) = position_glyphs.get_mut(record.sequence_index() as usize) else {
+                continue;
+            };
+            let Some(lookup) = all_lookups.get(record.lookup_list_index() as usize) else {
+                continue;
+            };
+            let mut reachable = glyphs.clone();
+            lookup.subtables()?.add_reachable_glyphs(&mut reachable)?;
+            output.extend_unsorted(reachable.difference(glyphs).map(|gid| gid.to_u32()));
+            // later records see the glyphs produced by earlier ones
+            *glyphs = reachable;
+        }
+        Ok(output)
+    }
+}
+
 impl ChainedSequenceContext<'_> {
     fn add_reachable_lookups(
         &self,
@@ -573,6 +617,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn multiple_sub_sequence() {
+        // test input is font-test-data/test_data/fea/simple_closure.fea
+        let gsub = get_gsub(test_data::SIMPLE);
+        let glyph_map = GlyphMap::new(test_data::SIMPLE_GLYPHS);
+        let a = glyph_map.get_gid("a").unwrap();
+
+        let lookup = gsub.lookup_list().unwrap().lookups().get(2).unwrap();
+        let SubstitutionSubtables::Multiple(subtables) = lookup.subtables().unwrap() else {
+            panic!("expected multiple substitution lookup");
+        };
+        let sequence = subtables
+            .get(0)
+            .unwrap()
+            .sequence_for(a.into())
+            .unwrap()
+            .iter()
+            .map(|gid| glyph_map.get_name(gid.try_into().unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(sequence, ["c", "d"]);
+
+        let result = compute_closure(&gsub, &glyph_map, &["a"]);
+        assert!(sequence
+            .iter()
+            .all(|name| result.contains(&glyph_map.get_gid(name).unwrap())));
+    }
+
     #[test]
     fn recursive() {
         // a scenario in which one substitution adds glyphs that trigger additional
@@ -612,6 +683,47 @@ mod tests {
         assert_closure_result!(glyph_map, gsub5f3, &["f", "g", "f.2"]);
     }
 
+    #[test]
+    fn context_format3_potential_output_glyphs() {
+        let gsub = get_gsub(test_data::CONTEXTUAL);
+        let glyph_map = GlyphMap::new(test_data::CONTEXTUAL_GLYPHS);
+        let lookups = gsub
+            .lookup_list()
+            .unwrap()
+            .lookups()
+            .iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        // 'sub f' lookup MY_RULES g';'
+        let context = lookups
+            .iter()
+            .find_map(|lookup| match lookup.subtables().unwrap() {
+                SubstitutionSubtables::Contextual(tables) => match tables.get(0).unwrap() {
+                    SequenceContext::Format3(table) => Some(table),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .unwrap();
+        let gid = |name| glyph_map.get_gid(name).unwrap().to_u32();
+
+        let f = context.input_coverage_at(0).unwrap();
+        assert_eq!(
+            f.iter().map(|gid| gid.to_u32()).collect::<Vec<_>>(),
+            [gid("f")]
+        );
+        assert!(context.input_coverage_at(2).is_err());
+
+        let glyphs = [gid("f"), gid("g")].into_iter().collect();
+        let output = context.potential_output_glyphs(&glyphs, &lookups).unwrap();
+        assert_eq!(output.iter().collect::<Vec<_>>(), [gid("f.2")]);
+
+        // the context can't match without 'g'
+        let glyphs = [gid("f")].into_iter().collect();
+        let output = context.potential_output_glyphs(&glyphs, &lookups).unwrap();
+        assert!(output.is_empty());
+    }
+
     #[test]
     fn recursive_context() {
         let gsub = get_gsub(test_data::RECURSIVE_CONTEXTUAL);
@@ -2,6 +2,90 @@
 
 include!("../../generated/generated_head.rs");
 
+use crate::{FontRef, TableProvider};
+
+/// The value that the checksum of a font, including its checksum
+/// adjustment, should equal.
+const CHECKSUM_MAGIC: u32 = 0xB1B0AFBA;
+
+/// Byte position of the `checksum_adjustment` field in the head table.
+const CHECKSUM_ADJUSTMENT_POS: usize = 8;
+
+/// An error returned by [`verify_checksum`].
+#[derive(Clone, Debug)]
+pub enum ChecksumError {
+    /// The font or its head table could not be read.
+    Read(ReadError),
+    /// The stored checksum adjustment does not match the computed value.
+    Mismatch {
+        /// The checksum adjustment computed from the font data.
+        expected: u32,
+        /// The checksum adjustment stored in the head table.
+        actual: u32,
+    },
+}
+
+/// Verifies the `checksum_adjustment` field of the head table against the
+/// data of the entire font.
+///
+/// The font is summed as a sequence of big-endian `u32`s, with the adjustment
+/// field treated as zero and the final word padded with zeros; the stored
+/// adjustment should equal `0xB1B0AFBA` minus this sum.
+///
+/// See <https://learn.microsoft.com/en-us/typography/opentype/spec/otff#calculating-checksums>
+pub fn verify_checksum(font_bytes: &[u8]) -> Result<(), ChecksumError> {
+    let font = FontRef::new(font_bytes)?;
+    let head_record = font
+        .table_directory
+        .table_records()
+        .iter()
+        .find(|record| record.tag() == Head::TAG)
+        .ok_or(ReadError::TableIsMissing(Head::TAG))?;
+    let actual = font.head()?.checksum_adjustment();
+    let adjustment_pos = head_record.offset() as usize + CHECKSUM_ADJUSTMENT_POS;
+    let adjustment_range = adjustment_pos..adjustment_pos + 4;
+
+    let sum = font_bytes
+        .chunks(4)
+        .enumerate()
+        .fold(0u32, |sum, (chunk_ix, chunk)| {
+            let mut word = [0u8; 4];
+            for (i, byte) in chunk.iter().enumerate() {
+                if !adjustment_range.contains(&(chunk_ix * 4 + i)) {
+                    word[i] = *byte;
+                }
+            }
+            sum.wrapping_add(u32::from_be_bytes(word))
+        });
+    let expected = CHECKSUM_MAGIC.wrapping_sub(sum);
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(ChecksumError::Mismatch { expected, actual })
+    }
+}
+
+impl From<ReadError> for ChecksumError {
+    fn from(value: ReadError) -> Self {
+        Self::Read(value)
+    }
+}
+
+impl std::fmt::Display for ChecksumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read(err) => write!(f, "failed to read font: {err}"),
+            Self::Mismatch { expected, actual } => write!(
+                f,
+                "checksum adjustment mismatch: expected 0x{expected:08X}, found 0x{actual:08X}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChecksumError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -26,4 +110,29 @@ mod tests {
         assert_eq!(head.created().as_secs(), -500);
         assert_eq!(head.y_min(), -50);
     }
+
+    #[test]
+    fn verify_font_checksum() {
+        for font_data in [
+            font_test_data::SIMPLE_GLYF,
+            font_test_data::VAZIRMATN_VAR,
+            font_test_data::CANTARELL_VF_TRIMMED,
+        ] {
+            verify_checksum(font_data).unwrap();
+        }
+    }
+
+    #[test]
+    fn verify_font_checksum_mismatch() {
+        let mut font_data = font_test_data::SIMPLE_GLYF.to_vec();
+        // modify the last byte of the font (which is never part of the
+        // table directory)
+        *font_data.last_mut().unwrap() ^= 0xFF;
+        let err = verify_checksum(&font_data).unwrap_err();
+        let ChecksumError::Mismatch { expected, actual } = err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!(actual, 0x7cbc7835);
+        assert_ne!(expected, actual);
+    }
 }
@@ -0,0 +1,177 @@
+//! The [kern](https://learn.microsoft.com/en-us/typography/opentype/spec/kern) table
+
+include!("../../generated/generated_kern.rs");
+
+/// The size of the header shared by all kern subtables.
+const SUBTABLE_HEADER_LEN: usize = 6;
+/// The size of the format 0 header that precedes the pairs.
+const KERN0_HEADER_LEN: usize = 8;
+
+impl<'a> Kern<'a> {
+    /// Returns an iterator over the subtables in this table.
+    pub fn subtables(&self) -> impl Iterator<Item = Result<KernSubtable<'a>, ReadError>> + 'a {
+        let mut data = FontData::new(self.subtable_data());
+        let mut remaining = self.num_tables();
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            remaining -= 1;
+            let subtable = match KernSubtable::read(data) {
+                Ok(subtable) => subtable,
+                Err(e) => {
+                    remaining = 0;
+                    return Some(Err(e));
+                }
+            };
+            // The length field of format 0 subtables with many pairs will
+            // overflow, so prefer the length implied by the pair count.
+            let len = match subtable.kern0() {
+                Some(Ok(kern0)) => {
+                    SUBTABLE_HEADER_LEN
+                        + KERN0_HEADER_LEN
+                        + kern0.pairs().len() * Kern0Pair::RAW_BYTE_LEN
+                }
+                _ => subtable.length() as usize,
+            };
+            data = data.split_off(len).unwrap_or_default();
+            Some(Ok(subtable))
+        })
+    }
+}
+
+impl<'a> KernSubtable<'a> {
+    /// The format of this subtable, stored in the high byte of `coverage`.
+    pub fn format(&self) -> u8 {
+        (self.coverage() >> 8) as u8
+    }
+
+    /// Returns the format 0 data for this subtable, if it is in that format.
+    pub fn kern0(&self) -> Option<Result<Kern0<'a>, ReadError>> {
+        (self.format() == 0).then(|| Kern0::read(FontData::new(self.data())))
+    }
+}
+
+impl<'a> Kern0<'a> {
+    /// Returns the kerning value for the given pair of glyphs, if present.
+    ///
+    /// The pairs are required to be sorted, so this performs a binary search.
+    pub fn kern_pair_value(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        let left = u16::try_from(left.to_u32()).ok()?;
+        let right = u16::try_from(right.to_u32()).ok()?;
+        let key = pair_key(left, right);
+        let pairs = self.pairs();
+        pairs
+            .binary_search_by_key(&key, |pair| {
+                pair_key(pair.left().to_u16(), pair.right().to_u16())
+            })
+            .ok()
+            .map(|ix| pairs[ix].value().to_i16())
+    }
+
+    /// Returns `true` if the `search_range`, `entry_selector` and
+    /// `range_shift` fields are consistent with the number of pairs.
+    ///
+    /// These fields are not used for lookups, but inconsistent values
+    /// may indicate that the table was generated incorrectly.
+    pub fn search_params_are_consistent(&self) -> bool {
+        let num_pairs = self.num_pairs() as u32;
+        if num_pairs == 0 {
+            return true;
+        }
+        let entry_selector = num_pairs.ilog2();
+        let search_range = (1 << entry_selector) * Kern0Pair::RAW_BYTE_LEN as u32;
+        let range_shift = num_pairs * Kern0Pair::RAW_BYTE_LEN as u32 - search_range;
+        self.entry_selector() as u32 == entry_selector
+            && self.search_range() as u32 == search_range
+            && self.range_shift() as u32 == range_shift
+    }
+}
+
+fn pair_key(left: u16, right: u16) -> u32 {
+    ((left as u32) << 16) | right as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    fn make_kern0(pairs: &[(u16, u16, i16)], search_params: [u16; 3]) -> BeBuffer {
+        let mut buf = BeBuffer::new()
+            .push(0u16) // subtable version
+            .push((14 + pairs.len() * 6) as u16) // length
+            .push(0x0001u16) // coverage: horizontal, format 0
+            .push(pairs.len() as u16)
+            .extend(search_params);
+        for (left, right, value) in pairs {
+            buf = buf.push(*left).push(*right).push(*value);
+        }
+        buf
+    }
+
+    #[test]
+    fn format0_lookup() {
+        let pairs = [(1, 2, -40), (1, 5, 20), (3, 1, -15), (7, 7, 100)];
+        let subtable = make_kern0(&pairs, [24, 2, 0]);
+        let buf = BeBuffer::new()
+            .push(0u16) // version
+            .push(1u16) // num tables
+            .extend(subtable.iter().copied());
+        let kern = Kern::read(buf.font_data()).unwrap();
+        let subtables = kern.subtables().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(subtables.len(), 1);
+        assert_eq!(subtables[0].format(), 0);
+        let kern0 = subtables[0].kern0().unwrap().unwrap();
+        assert!(kern0.search_params_are_consistent());
+        for (left, right, value) in pairs {
+            assert_eq!(
+                kern0.kern_pair_value(GlyphId::new(left as u32), GlyphId::new(right as u32)),
+                Some(value)
+            );
+        }
+        assert_eq!(
+            kern0.kern_pair_value(GlyphId::new(2), GlyphId::new(1)),
+            None
+        );
+        assert_eq!(
+            kern0.kern_pair_value(GlyphId::new(1), GlyphId::new(0x10002)),
+            None
+        );
+    }
+
+    #[test]
+    fn inconsistent_search_params() {
+        let pairs = [(1, 2, -40), (1, 5, 20), (3, 1, -15)];
+        let subtable = make_kern0(&pairs, [24, 2, 0]);
+        let kern0 = Kern0::read(FontData::new(&subtable[SUBTABLE_HEADER_LEN..])).unwrap();
+        assert!(!kern0.search_params_are_consistent());
+        let subtable = make_kern0(&pairs, [12, 1, 6]);
+        let kern0 = Kern0::read(FontData::new(&subtable[SUBTABLE_HEADER_LEN..])).unwrap();
+        assert!(kern0.search_params_are_consistent());
+    }
+
+    #[test]
+    fn multiple_subtables() {
+        let first = make_kern0(&[(1, 2, -40)], [6, 0, 0]);
+        let second = make_kern0(&[(1, 2, 10), (4, 4, 4)], [12, 1, 0]);
+        let buf = BeBuffer::new()
+            .push(0u16)
+            .push(2u16)
+            .extend(first.iter().copied())
+            .extend(second.iter().copied());
+        let kern = Kern::read(buf.font_data()).unwrap();
+        let values = kern
+            .subtables()
+            .map(|subtable| {
+                subtable
+                    .unwrap()
+                    .kern0()
+                    .unwrap()
+                    .unwrap()
+                    .kern_pair_value(GlyphId::new(1), GlyphId::new(2))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, [Some(-40), Some(10)]);
+    }
+}
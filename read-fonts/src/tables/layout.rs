@@ -137,6 +137,109 @@ impl<'a> SomeTable<'a> for FeatureParams<'a> {
     }
 }
 
+impl<'a> ScriptList<'a> {
+    /// Returns an iterator over the tags of the scripts in this list.
+    pub fn script_tags(&self) -> impl Iterator<Item = Tag> + 'a {
+        self.script_records().iter().map(|rec| rec.script_tag())
+    }
+
+    /// Returns the script with the given tag, if present.
+    pub fn script(&self, tag: Tag) -> Option<Result<Script<'a>, ReadError>> {
+        self.script_records()
+            .iter()
+            .find(|rec| rec.script_tag() == tag)
+            .map(|rec| rec.script(self.offset_data()))
+    }
+
+    /// Returns `true` if this list contains a script with the given tag.
+    pub fn has_script(&self, tag: Tag) -> bool {
+        self.script_tags().any(|script_tag| script_tag == tag)
+    }
+
+    /// Returns the `(script, language)` pairs of the language systems that
+    /// include a feature with the given tag in `features`.
+    ///
+    /// The language is `None` for the default language system of a script.
+    /// A feature counts as included if it is the required feature of the
+    /// language system.
+    #[cfg(feature = "std")]
+    pub fn scripts_with_feature(
+        &self,
+        features: &FeatureList,
+        feature_tag: Tag,
+    ) -> Result<Vec<(Tag, Option<Tag>)>, ReadError> {
+        let feature_records = features.feature_records();
+        let has_feature = |lang_sys: &LangSys| {
+            core::iter::once(lang_sys.required_feature_index())
+                .chain(lang_sys.feature_indices().iter().map(|ix| ix.get()))
+                .filter_map(|ix| feature_records.get(ix as usize))
+                .any(|rec| rec.feature_tag() == feature_tag)
+        };
+        let mut result = Vec::new();
+        for record in self.script_records() {
+            let script = record.script(self.offset_data())?;
+            if let Some(lang_sys) = script.default_lang_sys().transpose()? {
+                if has_feature(&lang_sys) {
+                    result.push((record.script_tag(), None));
+                }
+            }
+            for lang_record in script.lang_sys_records() {
+                if has_feature(&lang_record.lang_sys(script.offset_data())?) {
+                    result.push((record.script_tag(), Some(lang_record.lang_sys_tag())));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<'a> FeatureList<'a> {
+    /// Returns an iterator over the indices of the features with the given tag.
+    pub fn feature_indices_for_tag(&self, tag: Tag) -> impl Iterator<Item = u16> + 'a {
+        self.feature_records()
+            .iter()
+            .enumerate()
+            .filter(move |(_, rec)| rec.feature_tag() == tag)
+            .map(|(i, _)| i as u16)
+    }
+
+    /// Returns an iterator over the features with the given tag, as
+    /// `(feature_index, feature)` pairs.
+    pub fn features_with_tag(
+        &self,
+        tag: Tag,
+    ) -> impl Iterator<Item = Result<(u16, Feature<'a>), ReadError>> + 'a {
+        let data = self.offset_data();
+        let records = self.feature_records();
+        self.feature_indices_for_tag(tag)
+            .map(move |ix| Ok((ix, records[ix as usize].feature(data)?)))
+    }
+
+    /// Returns the distinct tags of the features in this list.
+    #[cfg(feature = "std")]
+    pub fn feature_tags(&self) -> std::collections::BTreeSet<Tag> {
+        self.feature_records()
+            .iter()
+            .map(|rec| rec.feature_tag())
+            .collect()
+    }
+
+    /// Returns the indices of all lookups referenced by any feature in this
+    /// list, without duplicates.
+    ///
+    /// Features from [`FeatureVariations`] are not included.
+    #[cfg(feature = "std")]
+    pub fn lookup_indices_deduped(&self) -> Result<std::collections::BTreeSet<u16>, ReadError> {
+        let data = self.offset_data();
+        let mut lookup_indices = std::collections::BTreeSet::new();
+        for record in self.feature_records() {
+            let feature = record.feature(data)?;
+            lookup_indices.extend(feature.lookup_list_indices().iter().map(|ix| ix.get()));
+        }
+        Ok(lookup_indices)
+    }
+}
+
 impl FeatureTableSubstitutionRecord {
     pub fn alternate_feature<'a>(&self, data: FontData<'a>) -> Result<Feature<'a>, ReadError> {
         self.alternate_feature_offset()
@@ -144,6 +247,92 @@ impl FeatureTableSubstitutionRecord {
     }
 }
 
+impl<'a> FeatureVariations<'a> {
+    /// Return the feature substitutions that apply at the given normalized
+    /// location, as `(feature_index, alternate_feature)` pairs.
+    ///
+    /// Records are evaluated in order, and only the first record whose
+    /// condition set is satisfied is applied.
+    #[cfg(feature = "std")]
+    pub fn substitutions_at(
+        &self,
+        coords: &[F2Dot14],
+    ) -> Result<Vec<(u16, Feature<'a>)>, ReadError> {
+        let data = self.offset_data();
+        for record in self.feature_variation_records() {
+            let matches = match record.condition_set(data).transpose()? {
+                Some(condition_set) => condition_set.is_satisfied(coords)?,
+                // an empty condition set matches every location
+                None => true,
+            };
+            if !matches {
+                continue;
+            }
+            let Some(substitution) = record.feature_table_substitution(data).transpose()? else {
+                return Ok(Vec::new());
+            };
+            let subst_data = substitution.offset_data();
+            return substitution
+                .substitutions()
+                .iter()
+                .map(|rec| Ok((rec.feature_index(), rec.alternate_feature(subst_data)?)))
+                .collect();
+        }
+        Ok(Vec::new())
+    }
+}
+
+impl ConditionSet<'_> {
+    /// Returns `true` if all conditions are satisfied at the given normalized location.
+    pub fn is_satisfied(&self, coords: &[F2Dot14]) -> Result<bool, ReadError> {
+        for condition in self.conditions().iter() {
+            if !condition?.is_satisfied(coords)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl Condition<'_> {
+    /// Returns `true` if the condition is satisfied at the given normalized location.
+    ///
+    /// Variable value conditions (format 2) are never satisfied, since they
+    /// require access to an item variation store.
+    pub fn is_satisfied(&self, coords: &[F2Dot14]) -> Result<bool, ReadError> {
+        match self {
+            Condition::Format1AxisRange(condition) => {
+                let coord = coords
+                    .get(condition.axis_index() as usize)
+                    .copied()
+                    .unwrap_or_default();
+                Ok(condition.filter_range_min_value() <= coord
+                    && coord <= condition.filter_range_max_value())
+            }
+            Condition::Format2VariableValue(_) => Ok(false),
+            Condition::Format3And(condition) => {
+                for condition in condition.conditions().iter() {
+                    if !condition?.is_satisfied(coords)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Condition::Format4Or(condition) => {
+                for condition in condition.conditions().iter() {
+                    if condition?.is_satisfied(coords)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Condition::Format5Negate(condition) => {
+                Ok(!condition.condition()?.is_satisfied(coords)?)
+            }
+        }
+    }
+}
+
 impl<'a> CoverageTable<'a> {
     pub fn iter(&self) -> impl Iterator<Item = GlyphId16> + 'a {
         // all one expression so that we have a single return type
@@ -307,6 +496,74 @@ impl ClassDef<'_> {
     }
 }
 
+impl<'a> SequenceContextFormat1<'a> {
+    /// Returns the rules for input sequences starting with `glyph_id`, or
+    /// `None` if the glyph is not covered by this subtable.
+    pub fn rule_set_for(&self, glyph_id: GlyphId) -> Option<SequenceRuleSet<'a>> {
+        let index = self.coverage().ok()?.get(glyph_id)?;
+        self.seq_rule_sets().get(index as usize)?.ok()
+    }
+}
+
+impl<'a> SequenceContextFormat2<'a> {
+    /// Returns the rules for input sequences starting with `glyph_id`, or
+    /// `None` if the glyph is not covered by this subtable.
+    ///
+    /// These are the rules for the class of the glyph in the input class
+    /// definition.
+    pub fn rule_set_for(&self, glyph_id: GlyphId) -> Option<ClassSequenceRuleSet<'a>> {
+        self.coverage().ok()?.get(glyph_id)?;
+        let class = self.class_def().ok()?.get(glyph_id.try_into().ok()?);
+        self.rule_set_for_class(class)
+    }
+
+    /// Returns the rules for input sequences starting with a glyph of
+    /// the given class, if there are any.
+    pub fn rule_set_for_class(&self, class: u16) -> Option<ClassSequenceRuleSet<'a>> {
+        self.class_seq_rule_sets().get(class as usize)?.ok()
+    }
+}
+
+impl<'a> SequenceContextFormat3<'a> {
+    /// Returns the coverage of the glyph at `position` in the input sequence.
+    pub fn input_coverage_at(&self, position: usize) -> Result<CoverageTable<'a>, ReadError> {
+        self.coverages().get(position)
+    }
+}
+
+impl<'a> SequenceContext<'a> {
+    /// Returns the coverage of the first glyph in the input sequence.
+    ///
+    /// A glyph that is not covered can never start a match of this subtable.
+    pub fn coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        match self {
+            SequenceContext::Format1(table) => table.coverage(),
+            SequenceContext::Format2(table) => table.coverage(),
+            SequenceContext::Format3(table) => table.input_coverage_at(0),
+        }
+    }
+}
+
+impl<'a> ChainedSequenceContextFormat3<'a> {
+    /// Returns the coverage of the glyph at `position` in the input sequence.
+    pub fn input_coverage_at(&self, position: usize) -> Result<CoverageTable<'a>, ReadError> {
+        self.input_coverages().get(position)
+    }
+}
+
+impl<'a> ChainedSequenceContext<'a> {
+    /// Returns the coverage of the first glyph in the input sequence.
+    ///
+    /// A glyph that is not covered can never start a match of this subtable.
+    pub fn coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        match self {
+            ChainedSequenceContext::Format1(table) => table.coverage(),
+            ChainedSequenceContext::Format2(table) => table.coverage(),
+            ChainedSequenceContext::Format3(table) => table.input_coverage_at(0),
+        }
+    }
+}
+
 impl<'a> Device<'a> {
     /// Iterate over the decoded values for this device
     pub fn iter(&self) -> impl Iterator<Item = i8> + 'a {
@@ -354,6 +611,39 @@ fn iter_packed_values(raw: u16, format: DeltaFormat, n: usize) -> impl Iterator<
     decoded.into_iter().flatten()
 }
 
+/// The position of the lookup list offset in the GSUB and GPOS headers.
+const LOOKUP_LIST_OFFSET_POS: usize = 8;
+
+/// Returns the number of lookups in a GSUB or GPOS table.
+///
+/// Only the table header and the lookup list header are read, so this is
+/// cheaper than parsing the table when only the count is needed. Returns `0`
+/// if the data is malformed.
+pub fn lookup_count(layout_table_bytes: &[u8]) -> u16 {
+    read_lookup_list(layout_table_bytes)
+        .map(|list| list.lookup_count())
+        .unwrap_or_default()
+}
+
+/// Returns the type of the lookup at `index` in a GSUB or GPOS table.
+///
+/// Only the headers on the path to the lookup are read. Extension lookups
+/// are not resolved, so their type is reported as the extension type.
+///
+/// Returns `None` if the index is out of bounds or the data is malformed.
+pub fn lookup_type(layout_table_bytes: &[u8], index: u16) -> Option<u8> {
+    let list = read_lookup_list(layout_table_bytes).ok()?;
+    let offset = list.lookup_offsets().get(index as usize)?.get();
+    let lookup: Lookup<()> = offset.resolve(list.offset_data()).ok()?;
+    lookup.lookup_type().try_into().ok()
+}
+
+fn read_lookup_list(layout_table_bytes: &[u8]) -> Result<LookupList<'_, ()>, ReadError> {
+    let data = FontData::new(layout_table_bytes);
+    let offset: Offset16 = data.read_at(LOOKUP_LIST_OFFSET_POS)?;
+    offset.resolve(data)
+}
+
 impl From<VariationIndex<'_>> for DeltaSetIndex {
     fn from(src: VariationIndex) -> DeltaSetIndex {
         DeltaSetIndex {
@@ -1,3 +1,89 @@
 //! The [maxp](https://docs.microsoft.com/en-us/typography/opentype/spec/maxp) table
 
 include!("../../generated/generated_maxp.rs");
+
+/// The fields of a version 1.0 `maxp` table, used by TrueType fonts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TrueTypeLimits {
+    /// Maximum points in a non-composite glyph.
+    pub max_points: u16,
+    /// Maximum contours in a non-composite glyph.
+    pub max_contours: u16,
+    /// Maximum points in a composite glyph.
+    pub max_composite_points: u16,
+    /// Maximum contours in a composite glyph.
+    pub max_composite_contours: u16,
+    /// 1 if instructions do not use the twilight zone (Z0), or 2 if
+    /// instructions do use Z0.
+    pub max_zones: u16,
+    /// Maximum points used in Z0.
+    pub max_twilight_points: u16,
+    /// Number of Storage Area locations.
+    pub max_storage: u16,
+    /// Number of FDEFs, equal to the highest function number + 1.
+    pub max_function_defs: u16,
+    /// Number of IDEFs.
+    pub max_instruction_defs: u16,
+    /// Maximum stack depth across the font program, CVT program and all
+    /// glyph instructions.
+    pub max_stack_elements: u16,
+    /// Maximum byte count for glyph instructions.
+    pub max_size_of_instructions: u16,
+    /// Maximum number of components referenced at “top level” for any
+    /// composite glyph.
+    pub max_component_elements: u16,
+    /// Maximum levels of recursion; 1 for simple components.
+    pub max_component_depth: u16,
+}
+
+impl Maxp<'_> {
+    /// Returns the TrueType specific limits, or `None` if this is a
+    /// version 0.5 table, as used by CFF fonts.
+    pub fn truetype_limits(&self) -> Option<TrueTypeLimits> {
+        Some(TrueTypeLimits {
+            max_points: self.max_points()?,
+            max_contours: self.max_contours()?,
+            max_composite_points: self.max_composite_points()?,
+            max_composite_contours: self.max_composite_contours()?,
+            max_zones: self.max_zones()?,
+            max_twilight_points: self.max_twilight_points()?,
+            max_storage: self.max_storage()?,
+            max_function_defs: self.max_function_defs()?,
+            max_instruction_defs: self.max_instruction_defs()?,
+            max_stack_elements: self.max_stack_elements()?,
+            max_size_of_instructions: self.max_size_of_instructions()?,
+            max_component_elements: self.max_component_elements()?,
+            max_component_depth: self.max_component_depth()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::BeBuffer;
+
+    #[test]
+    fn version_0_5() {
+        let buf = BeBuffer::new()
+            .push(Version16Dot16::VERSION_0_5)
+            .push(42u16);
+        let maxp = Maxp::read(buf.font_data()).unwrap();
+        assert_eq!(maxp.num_glyphs(), 42);
+        assert_eq!(maxp.truetype_limits(), None);
+    }
+
+    #[test]
+    fn version_1_0() {
+        let buf = BeBuffer::new()
+            .push(Version16Dot16::VERSION_1_0)
+            .push(42u16)
+            .extend(1u16..=13);
+        let maxp = Maxp::read(buf.font_data()).unwrap();
+        assert_eq!(maxp.num_glyphs(), 42);
+        let limits = maxp.truetype_limits().unwrap();
+        assert_eq!(limits.max_points, 1);
+        assert_eq!(limits.max_zones, 5);
+        assert_eq!(limits.max_component_depth, 13);
+    }
+}
@@ -2,8 +2,432 @@
 
 include!("../../generated/generated_os2.rs");
 
+/// A Unicode range, as encoded in the `ulUnicodeRange` fields of the OS/2 table.
+///
+/// The discriminant is the bit number of the range. Some ranges cover several
+/// Unicode blocks; see the [spec] for the exact code point ranges.
+///
+/// [spec]: https://learn.microsoft.com/en-us/typography/opentype/spec/os2#ur
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum UnicodeRangeBlock {
+    /// Basic Latin
+    BasicLatin = 0,
+    /// Latin-1 Supplement
+    Latin1Supplement = 1,
+    /// Latin Extended-A
+    LatinExtendedA = 2,
+    /// Latin Extended-B
+    LatinExtendedB = 3,
+    /// IPA Extensions, Phonetic Extensions and Phonetic Extensions Supplement
+    IpaExtensions = 4,
+    /// Spacing Modifier Letters and Modifier Tone Letters
+    SpacingModifierLetters = 5,
+    /// Combining Diacritical Marks and Combining Diacritical Marks Supplement
+    CombiningDiacriticalMarks = 6,
+    /// Greek and Coptic
+    GreekAndCoptic = 7,
+    /// Coptic
+    Coptic = 8,
+    /// Cyrillic, Cyrillic Supplement, Cyrillic Extended-A and Cyrillic Extended-B
+    Cyrillic = 9,
+    /// Armenian
+    Armenian = 10,
+    /// Hebrew
+    Hebrew = 11,
+    /// Vai
+    Vai = 12,
+    /// Arabic and Arabic Supplement
+    Arabic = 13,
+    /// NKo
+    Nko = 14,
+    /// Devanagari
+    Devanagari = 15,
+    /// Bengali
+    Bengali = 16,
+    /// Gurmukhi
+    Gurmukhi = 17,
+    /// Gujarati
+    Gujarati = 18,
+    /// Oriya
+    Oriya = 19,
+    /// Tamil
+    Tamil = 20,
+    /// Telugu
+    Telugu = 21,
+    /// Kannada
+    Kannada = 22,
+    /// Malayalam
+    Malayalam = 23,
+    /// Thai
+    Thai = 24,
+    /// Lao
+    Lao = 25,
+    /// Georgian and Georgian Supplement
+    Georgian = 26,
+    /// Balinese
+    Balinese = 27,
+    /// Hangul Jamo
+    HangulJamo = 28,
+    /// Latin Extended Additional, Latin Extended-C and Latin Extended-D
+    LatinExtendedAdditional = 29,
+    /// Greek Extended
+    GreekExtended = 30,
+    /// General Punctuation and Supplemental Punctuation
+    GeneralPunctuation = 31,
+    /// Superscripts And Subscripts
+    SuperscriptsAndSubscripts = 32,
+    /// Currency Symbols
+    CurrencySymbols = 33,
+    /// Combining Diacritical Marks For Symbols
+    CombiningDiacriticalMarksForSymbols = 34,
+    /// Letterlike Symbols
+    LetterlikeSymbols = 35,
+    /// Number Forms
+    NumberForms = 36,
+    /// Arrows, Supplemental Arrows-A, Supplemental Arrows-B and Miscellaneous Symbols and Arrows
+    Arrows = 37,
+    /// Mathematical Operators, Supplemental Mathematical Operators, Miscellaneous Mathematical Symbols-A and Miscellaneous Mathematical Symbols-B
+    MathematicalOperators = 38,
+    /// Miscellaneous Technical
+    MiscellaneousTechnical = 39,
+    /// Control Pictures
+    ControlPictures = 40,
+    /// Optical Character Recognition
+    OpticalCharacterRecognition = 41,
+    /// Enclosed Alphanumerics
+    EnclosedAlphanumerics = 42,
+    /// Box Drawing
+    BoxDrawing = 43,
+    /// Block Elements
+    BlockElements = 44,
+    /// Geometric Shapes
+    GeometricShapes = 45,
+    /// Miscellaneous Symbols
+    MiscellaneousSymbols = 46,
+    /// Dingbats
+    Dingbats = 47,
+    /// CJK Symbols And Punctuation
+    CjkSymbolsAndPunctuation = 48,
+    /// Hiragana
+    Hiragana = 49,
+    /// Katakana and Katakana Phonetic Extensions
+    Katakana = 50,
+    /// Bopomofo and Bopomofo Extended
+    Bopomofo = 51,
+    /// Hangul Compatibility Jamo
+    HangulCompatibilityJamo = 52,
+    /// Phags-pa
+    PhagsPa = 53,
+    /// Enclosed CJK Letters And Months
+    EnclosedCjkLettersAndMonths = 54,
+    /// CJK Compatibility
+    CjkCompatibility = 55,
+    /// Hangul Syllables
+    HangulSyllables = 56,
+    /// Non-Plane 0, indicating supplementary characters
+    NonPlane0 = 57,
+    /// Phoenician
+    Phoenician = 58,
+    /// CJK Unified Ideographs, CJK Radicals Supplement, Kangxi Radicals, Ideographic Description Characters, CJK Unified Ideographs Extension A and B, and Kanbun
+    CjkUnifiedIdeographs = 59,
+    /// Private Use Area (plane 0)
+    PrivateUseAreaPlane0 = 60,
+    /// CJK Strokes, CJK Compatibility Ideographs and CJK Compatibility Ideographs Supplement
+    CjkStrokes = 61,
+    /// Alphabetic Presentation Forms
+    AlphabeticPresentationForms = 62,
+    /// Arabic Presentation Forms-A
+    ArabicPresentationFormsA = 63,
+    /// Combining Half Marks
+    CombiningHalfMarks = 64,
+    /// Vertical Forms and CJK Compatibility Forms
+    VerticalForms = 65,
+    /// Small Form Variants
+    SmallFormVariants = 66,
+    /// Arabic Presentation Forms-B
+    ArabicPresentationFormsB = 67,
+    /// Halfwidth And Fullwidth Forms
+    HalfwidthAndFullwidthForms = 68,
+    /// Specials
+    Specials = 69,
+    /// Tibetan
+    Tibetan = 70,
+    /// Syriac
+    Syriac = 71,
+    /// Thaana
+    Thaana = 72,
+    /// Sinhala
+    Sinhala = 73,
+    /// Myanmar
+    Myanmar = 74,
+    /// Ethiopic, Ethiopic Supplement and Ethiopic Extended
+    Ethiopic = 75,
+    /// Cherokee
+    Cherokee = 76,
+    /// Unified Canadian Aboriginal Syllabics
+    UnifiedCanadianAboriginalSyllabics = 77,
+    /// Ogham
+    Ogham = 78,
+    /// Runic
+    Runic = 79,
+    /// Khmer and Khmer Symbols
+    Khmer = 80,
+    /// Mongolian
+    Mongolian = 81,
+    /// Braille Patterns
+    BraillePatterns = 82,
+    /// Yi Syllables and Yi Radicals
+    YiSyllables = 83,
+    /// Tagalog, Hanunoo, Buhid and Tagbanwa
+    Tagalog = 84,
+    /// Old Italic
+    OldItalic = 85,
+    /// Gothic
+    Gothic = 86,
+    /// Deseret
+    Deseret = 87,
+    /// Byzantine Musical Symbols, Musical Symbols and Ancient Greek Musical Notation
+    ByzantineMusicalSymbols = 88,
+    /// Mathematical Alphanumeric Symbols
+    MathematicalAlphanumericSymbols = 89,
+    /// Private Use (plane 15) and Private Use (plane 16)
+    PrivateUsePlanes15And16 = 90,
+    /// Variation Selectors and Variation Selectors Supplement
+    VariationSelectors = 91,
+    /// Tags
+    Tags = 92,
+    /// Limbu
+    Limbu = 93,
+    /// Tai Le
+    TaiLe = 94,
+    /// New Tai Lue
+    NewTaiLue = 95,
+    /// Buginese
+    Buginese = 96,
+    /// Glagolitic
+    Glagolitic = 97,
+    /// Tifinagh
+    Tifinagh = 98,
+    /// Yijing Hexagram Symbols
+    YijingHexagramSymbols = 99,
+    /// Syloti Nagri
+    SylotiNagri = 100,
+    /// Linear B Syllabary, Linear B Ideograms and Aegean Numbers
+    LinearB = 101,
+    /// Ancient Greek Numbers
+    AncientGreekNumbers = 102,
+    /// Ugaritic
+    Ugaritic = 103,
+    /// Old Persian
+    OldPersian = 104,
+    /// Shavian
+    Shavian = 105,
+    /// Osmanya
+    Osmanya = 106,
+    /// Cypriot Syllabary
+    CypriotSyllabary = 107,
+    /// Kharoshthi
+    Kharoshthi = 108,
+    /// Tai Xuan Jing Symbols
+    TaiXuanJingSymbols = 109,
+    /// Cuneiform and Cuneiform Numbers and Punctuation
+    Cuneiform = 110,
+    /// Counting Rod Numerals
+    CountingRodNumerals = 111,
+    /// Sundanese
+    Sundanese = 112,
+    /// Lepcha
+    Lepcha = 113,
+    /// Ol Chiki
+    OlChiki = 114,
+    /// Saurashtra
+    Saurashtra = 115,
+    /// Kayah Li
+    KayahLi = 116,
+    /// Rejang
+    Rejang = 117,
+    /// Cham
+    Cham = 118,
+    /// Ancient Symbols
+    AncientSymbols = 119,
+    /// Phaistos Disc
+    PhaistosDisc = 120,
+    /// Carian, Lycian and Lydian
+    Carian = 121,
+    /// Domino Tiles and Mahjong Tiles
+    DominoTiles = 122,
+}
+
+impl UnicodeRangeBlock {
+    /// All ranges, in bit order.
+    ///
+    /// Bits 123 to 127 are reserved and have no corresponding range.
+    pub const ALL: [UnicodeRangeBlock; 123] = [
+        UnicodeRangeBlock::BasicLatin,
+        UnicodeRangeBlock::Latin1Supplement,
+        UnicodeRangeBlock::LatinExtendedA,
+        UnicodeRangeBlock::LatinExtendedB,
+        UnicodeRangeBlock::IpaExtensions,
+        UnicodeRangeBlock::SpacingModifierLetters,
+        UnicodeRangeBlock::CombiningDiacriticalMarks,
+        UnicodeRangeBlock::GreekAndCoptic,
+        UnicodeRangeBlock::Coptic,
+        UnicodeRangeBlock::Cyrillic,
+        UnicodeRangeBlock::Armenian,
+        UnicodeRangeBlock::Hebrew,
+        UnicodeRangeBlock::Vai,
+        UnicodeRangeBlock::Arabic,
+        UnicodeRangeBlock::Nko,
+        UnicodeRangeBlock::Devanagari,
+        UnicodeRangeBlock::Bengali,
+        UnicodeRangeBlock::Gurmukhi,
+        UnicodeRangeBlock::Gujarati,
+        UnicodeRangeBlock::Oriya,
+        UnicodeRangeBlock::Tamil,
+        UnicodeRangeBlock::Telugu,
+        UnicodeRangeBlock::Kannada,
+        UnicodeRangeBlock::Malayalam,
+        UnicodeRangeBlock::Thai,
+        UnicodeRangeBlock::Lao,
+        UnicodeRangeBlock::Georgian,
+        UnicodeRangeBlock::Balinese,
+        UnicodeRangeBlock::HangulJamo,
+        UnicodeRangeBlock::LatinExtendedAdditional,
+        UnicodeRangeBlock::GreekExtended,
+        UnicodeRangeBlock::GeneralPunctuation,
+        UnicodeRangeBlock::SuperscriptsAndSubscripts,
+        UnicodeRangeBlock::CurrencySymbols,
+        UnicodeRangeBlock::CombiningDiacriticalMarksForSymbols,
+        UnicodeRangeBlock::LetterlikeSymbols,
+        UnicodeRangeBlock::NumberForms,
+        UnicodeRangeBlock::Arrows,
+        UnicodeRangeBlock::MathematicalOperators,
+        UnicodeRangeBlock::MiscellaneousTechnical,
+        UnicodeRangeBlock::ControlPictures,
+        UnicodeRangeBlock::OpticalCharacterRecognition,
+        UnicodeRangeBlock::EnclosedAlphanumerics,
+        UnicodeRangeBlock::BoxDrawing,
+        UnicodeRangeBlock::BlockElements,
+        UnicodeRangeBlock::GeometricShapes,
+        UnicodeRangeBlock::MiscellaneousSymbols,
+        UnicodeRangeBlock::Dingbats,
+        UnicodeRangeBlock::CjkSymbolsAndPunctuation,
+        UnicodeRangeBlock::Hiragana,
+        UnicodeRangeBlock::Katakana,
+        UnicodeRangeBlock::Bopomofo,
+        UnicodeRangeBlock::HangulCompatibilityJamo,
+        UnicodeRangeBlock::PhagsPa,
+        UnicodeRangeBlock::EnclosedCjkLettersAndMonths,
+        UnicodeRangeBlock::CjkCompatibility,
+        UnicodeRangeBlock::HangulSyllables,
+        UnicodeRangeBlock::NonPlane0,
+        UnicodeRangeBlock::Phoenician,
+        UnicodeRangeBlock::CjkUnifiedIdeographs,
+        UnicodeRangeBlock::PrivateUseAreaPlane0,
+        UnicodeRangeBlock::CjkStrokes,
+        UnicodeRangeBlock::AlphabeticPresentationForms,
+        UnicodeRangeBlock::ArabicPresentationFormsA,
+        UnicodeRangeBlock::CombiningHalfMarks,
+        UnicodeRangeBlock::VerticalForms,
+        UnicodeRangeBlock::SmallFormVariants,
+        UnicodeRangeBlock::ArabicPresentationFormsB,
+        UnicodeRangeBlock::HalfwidthAndFullwidthForms,
+        UnicodeRangeBlock::Specials,
+        UnicodeRangeBlock::Tibetan,
+        UnicodeRangeBlock::Syriac,
+        UnicodeRangeBlock::Thaana,
+        UnicodeRangeBlock::Sinhala,
+        UnicodeRangeBlock::Myanmar,
+        UnicodeRangeBlock::Ethiopic,
+        UnicodeRangeBlock::Cherokee,
+        UnicodeRangeBlock::UnifiedCanadianAboriginalSyllabics,
+        UnicodeRangeBlock::Ogham,
+        UnicodeRangeBlock::Runic,
+        UnicodeRangeBlock::Khmer,
+        UnicodeRangeBlock::Mongolian,
+        UnicodeRangeBlock::BraillePatterns,
+        UnicodeRangeBlock::YiSyllables,
+        UnicodeRangeBlock::Tagalog,
+        UnicodeRangeBlock::OldItalic,
+        UnicodeRangeBlock::Gothic,
+        UnicodeRangeBlock::Deseret,
+        UnicodeRangeBlock::ByzantineMusicalSymbols,
+        UnicodeRangeBlock::MathematicalAlphanumericSymbols,
+        UnicodeRangeBlock::PrivateUsePlanes15And16,
+        UnicodeRangeBlock::VariationSelectors,
+        UnicodeRangeBlock::Tags,
+        UnicodeRangeBlock::Limbu,
+        UnicodeRangeBlock::TaiLe,
+        UnicodeRangeBlock::NewTaiLue,
+        UnicodeRangeBlock::Buginese,
+        UnicodeRangeBlock::Glagolitic,
+        UnicodeRangeBlock::Tifinagh,
+        UnicodeRangeBlock::YijingHexagramSymbols,
+        UnicodeRangeBlock::SylotiNagri,
+        UnicodeRangeBlock::LinearB,
+        UnicodeRangeBlock::AncientGreekNumbers,
+        UnicodeRangeBlock::Ugaritic,
+        UnicodeRangeBlock::OldPersian,
+        UnicodeRangeBlock::Shavian,
+        UnicodeRangeBlock::Osmanya,
+        UnicodeRangeBlock::CypriotSyllabary,
+        UnicodeRangeBlock::Kharoshthi,
+        UnicodeRangeBlock::TaiXuanJingSymbols,
+        UnicodeRangeBlock::Cuneiform,
+        UnicodeRangeBlock::CountingRodNumerals,
+        UnicodeRangeBlock::Sundanese,
+        UnicodeRangeBlock::Lepcha,
+        UnicodeRangeBlock::OlChiki,
+        UnicodeRangeBlock::Saurashtra,
+        UnicodeRangeBlock::KayahLi,
+        UnicodeRangeBlock::Rejang,
+        UnicodeRangeBlock::Cham,
+        UnicodeRangeBlock::AncientSymbols,
+        UnicodeRangeBlock::PhaistosDisc,
+        UnicodeRangeBlock::Carian,
+        UnicodeRangeBlock::DominoTiles,
+    ];
+
+    /// The bit for this range in the combined 128-bit `ulUnicodeRange` field.
+    pub fn bit(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the range for this bit, or `None` if the bit is reserved.
+    pub fn from_bit(bit: u8) -> Option<Self> {
+        Self::ALL.get(bit as usize).copied()
+    }
+}
+
+impl Os2<'_> {
+    /// Returns the four `ulUnicodeRange` fields as a single 128-bit mask.
+    pub fn unicode_range_bits(&self) -> u128 {
+        self.ul_unicode_range_1() as u128
+            | (self.ul_unicode_range_2() as u128) << 32
+            | (self.ul_unicode_range_3() as u128) << 64
+            | (self.ul_unicode_range_4() as u128) << 96
+    }
+
+    /// Returns an iterator over the Unicode ranges this font claims to support.
+    ///
+    /// Reserved bits are ignored.
+    pub fn unicode_ranges(&self) -> impl Iterator<Item = UnicodeRangeBlock> {
+        let bits = self.unicode_range_bits();
+        UnicodeRangeBlock::ALL
+            .into_iter()
+            .filter(move |block| bits & (1 << block.bit()) != 0)
+    }
+
+    /// Returns `true` if the bit for `block` is set.
+    pub fn has_unicode_range(&self, block: UnicodeRangeBlock) -> bool {
+        self.unicode_range_bits() & (1 << block.bit()) != 0
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::UnicodeRangeBlock;
     use crate::{table_provider::TableProvider, FontRef};
 
     #[test]
@@ -12,4 +436,29 @@ mod tests {
         let table = font.os2().unwrap();
         assert_eq!(table.version(), 4);
     }
+
+    #[test]
+    fn unicode_ranges() {
+        let font = FontRef::new(font_test_data::NOTOSERIFHEBREW_AUTOHINT_METRICS).unwrap();
+        let table = font.os2().unwrap();
+        assert_eq!(
+            table.unicode_ranges().collect::<Vec<_>>(),
+            [UnicodeRangeBlock::BasicLatin, UnicodeRangeBlock::Hebrew]
+        );
+        assert!(table.has_unicode_range(UnicodeRangeBlock::Hebrew));
+        assert!(!table.has_unicode_range(UnicodeRangeBlock::Arabic));
+    }
+
+    #[test]
+    fn unicode_range_bits() {
+        for (i, block) in UnicodeRangeBlock::ALL.iter().enumerate() {
+            assert_eq!(block.bit() as usize, i);
+            assert_eq!(UnicodeRangeBlock::from_bit(i as u8), Some(*block));
+        }
+        assert_eq!(
+            UnicodeRangeBlock::from_bit(122),
+            Some(UnicodeRangeBlock::DominoTiles)
+        );
+        assert_eq!(UnicodeRangeBlock::from_bit(123), None);
+    }
 }
@@ -1,9 +1,9 @@
 //! Parsing for PostScript charstrings.
 
-use super::{BlendState, Error, Index, Stack};
+use super::{BlendState, Error, Index, Number, Stack};
 use crate::{
     types::{Fixed, Pen, Point},
-    Cursor,
+    Cursor, FontData, ReadError,
 };
 
 /// Maximum nesting depth for subroutine calls.
@@ -102,11 +102,139 @@ pub fn evaluate(
     Ok(())
 }
 
+/// An operand or operator in a charstring.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Token {
+    /// A number that is pushed onto the stack.
+    Operand(Number),
+    /// An operator, as its opcode. Two byte operators are `0x0C00 | b1`,
+    /// where `b1` is the byte following the escape byte.
+    Operator(u16),
+}
+
+/// Reads the operand or operator at the start of `charstring_data`,
+/// returning it together with its length in bytes.
+///
+/// Operators that are unknown to the evaluator are returned as well. The
+/// hintmask and cntrmask operators are followed by mask bytes, whose length
+/// depends on the number of stems; these are not part of the token, and
+/// must be skipped by the caller.
+pub fn read_token(charstring_data: &[u8]) -> Result<(Token, usize), Error> {
+    let mut cursor = FontData::new(charstring_data).cursor();
+    let b0 = cursor.read::<u8>()?;
+    let token = match b0 {
+        28 | 32..=255 => Token::Operand(read_operand(&mut cursor, b0)?),
+        ESCAPE => Token::Operator(0x0C00 | cursor.read::<u8>()? as u16),
+        _ => Token::Operator(b0 as u16),
+    };
+    Ok((token, cursor.position()?))
+}
+
+/// Reads an operand, given its first byte.
+///
+/// See "3.2 Charstring Number Encoding" <https://adobe-type-tools.github.io/font-tech-notes/pdfs/5177.Type2.pdf#page=12>
+fn read_operand(cursor: &mut Cursor, b0: u8) -> Result<Number, Error> {
+    Ok(match b0 {
+        // a fixed point value
+        255 => Fixed::from_bits(cursor.read::<i32>()?).into(),
+        // an integer
+        _ => super::dict::parse_int(cursor, b0)?.into(),
+    })
+}
+
+/// Escape opcode for accessing two byte operators.
+const ESCAPE: u8 = 12;
+
+/// A path command resulting from charstring evaluation.
+///
+/// All coordinates are absolute.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PathOp {
+    MoveTo(Fixed, Fixed),
+    LineTo(Fixed, Fixed),
+    CurveTo(Fixed, Fixed, Fixed, Fixed, Fixed, Fixed),
+    ClosePath,
+}
+
+/// Evaluates the given CFF charstring and returns the resulting path
+/// commands.
+///
+/// Subroutines are provided as slices of charstring data, indexed in the
+/// same order as the corresponding INDEX. If the charstring contains a
+/// callsubr operator and `local_subrs` is `None`, then
+/// `Error::MissingSubroutines` is returned.
+///
+/// This is a convenience for working with subroutines that have already
+/// been extracted from their INDEX; use [`evaluate`] to process a charstring
+/// directly from font data.
+#[cfg(feature = "std")]
+pub fn decode_charstring(
+    charstring: &[u8],
+    local_subrs: Option<&[&[u8]]>,
+    global_subrs: &[&[u8]],
+) -> Result<Vec<PathOp>, Error> {
+    let mut sink = PathOpSink::default();
+    let mut evaluator = Evaluator::new(global_subrs, local_subrs, None, &mut sink);
+    evaluator.evaluate(charstring, 0)?;
+    Ok(sink.0)
+}
+
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct PathOpSink(Vec<PathOp>);
+
+#[cfg(feature = "std")]
+impl CommandSink for PathOpSink {
+    fn move_to(&mut self, x: Fixed, y: Fixed) {
+        self.0.push(PathOp::MoveTo(x, y));
+    }
+
+    fn line_to(&mut self, x: Fixed, y: Fixed) {
+        self.0.push(PathOp::LineTo(x, y));
+    }
+
+    fn curve_to(&mut self, cx0: Fixed, cy0: Fixed, cx1: Fixed, cy1: Fixed, x: Fixed, y: Fixed) {
+        self.0.push(PathOp::CurveTo(cx0, cy0, cx1, cy1, x, y));
+    }
+
+    fn close(&mut self) {
+        self.0.push(PathOp::ClosePath);
+    }
+}
+
+/// A collection of subroutines that can be called from a charstring.
+trait Subroutines<'a> {
+    fn count(&self) -> u32;
+    fn get(&self, index: usize) -> Result<&'a [u8], Error>;
+}
+
+impl<'a> Subroutines<'a> for Index<'a> {
+    fn count(&self) -> u32 {
+        Index::count(self)
+    }
+
+    fn get(&self, index: usize) -> Result<&'a [u8], Error> {
+        Index::get(self, index)
+    }
+}
+
+impl<'a> Subroutines<'a> for &'a [&'a [u8]] {
+    fn count(&self) -> u32 {
+        self.len() as u32
+    }
+
+    fn get(&self, index: usize) -> Result<&'a [u8], Error> {
+        <[_]>::get(self, index)
+            .copied()
+            .ok_or(ReadError::OutOfBounds.into())
+    }
+}
+
 /// Transient state for evaluating a charstring and handling recursive
 /// subroutine calls.
-struct Evaluator<'a, S> {
-    global_subrs: Index<'a>,
-    subrs: Option<Index<'a>>,
+struct Evaluator<'a, S, R> {
+    global_subrs: R,
+    subrs: Option<R>,
     blend_state: Option<BlendState<'a>>,
     sink: &'a mut S,
     is_open: bool,
@@ -118,13 +246,14 @@ struct Evaluator<'a, S> {
     stack_ix: usize,
 }
 
-impl<'a, S> Evaluator<'a, S>
+impl<'a, S, R> Evaluator<'a, S, R>
 where
     S: CommandSink,
+    R: Subroutines<'a>,
 {
     fn new(
-        global_subrs: Index<'a>,
-        subrs: Option<Index<'a>>,
+        global_subrs: R,
+        subrs: Option<R>,
         blend_state: Option<BlendState<'a>>,
         sink: &'a mut S,
     ) -> Self {
@@ -147,20 +276,13 @@ where
         if nesting_depth > NESTING_DEPTH_LIMIT {
             return Err(Error::CharstringNestingDepthLimitExceeded);
         }
-        let mut cursor = crate::FontData::new(charstring_data).cursor();
+        let mut cursor = FontData::new(charstring_data).cursor();
         while cursor.remaining_bytes() != 0 {
             let b0 = cursor.read::<u8>()?;
             match b0 {
-                // See "3.2 Charstring Number Encoding" <https://adobe-type-tools.github.io/font-tech-notes/pdfs/5177.Type2.pdf#page=12>
-                //
-                // Push an integer to the stack
-                28 | 32..=254 => {
-                    self.stack.push(super::dict::parse_int(&mut cursor, b0)?)?;
-                }
-                // Push a fixed point value to the stack
-                255 => {
-                    let num = Fixed::from_bits(cursor.read::<i32>()?);
-                    self.stack.push(num)?;
+                // Push a number to the stack
+                28 | 32..=255 => {
+                    self.stack.push(read_operand(&mut cursor, b0)?)?;
                 }
                 _ => {
                     let operator = Operator::read(&mut cursor, b0)?;
@@ -476,7 +598,8 @@ where
                 } else {
                     &self.global_subrs
                 };
-                let biased_index = (self.stack.pop_i32()? + subrs_index.subr_bias()) as usize;
+                let bias = super::index::subr_bias(subrs_index.count());
+                let biased_index = (self.stack.pop_i32()? + bias) as usize;
                 let subr_charstring_data = subrs_index.get(biased_index)?;
                 self.evaluate(subr_charstring_data, nesting_depth + 1)?;
             }
@@ -626,8 +749,6 @@ enum Operator {
 
 impl Operator {
     fn read(cursor: &mut Cursor, b0: u8) -> Result<Self, Error> {
-        // Escape opcode for accessing two byte operators
-        const ESCAPE: u8 = 12;
         let (opcode, operator) = if b0 == ESCAPE {
             let b1 = cursor.read::<u8>()?;
             (b1, Self::from_two_byte_opcode(b1))
@@ -1048,4 +1169,58 @@ mod tests {
         ];
         assert_eq!(&commands.0, expected);
     }
+
+    #[test]
+    fn decode_charstring_with_subrs() {
+        // local subr 0: 10 20 rmoveto 30 hlineto return
+        let local_subr: &[u8] = &[149, 159, 21, 169, 6, 11];
+        // global subr 0: 40 vlineto return
+        let global_subr: &[u8] = &[179, 7, 11];
+        // -107 callsubr -107 callgsubr endchar
+        let charstring = &[32, 10, 32, 29, 14];
+        let path = decode_charstring(charstring, Some(&[local_subr]), &[global_subr]).unwrap();
+        assert_eq!(
+            path,
+            [
+                PathOp::MoveTo(Fixed::from_i32(10), Fixed::from_i32(20)),
+                PathOp::LineTo(Fixed::from_i32(40), Fixed::from_i32(20)),
+                PathOp::LineTo(Fixed::from_i32(40), Fixed::from_i32(60)),
+                PathOp::ClosePath,
+            ]
+        );
+        assert!(matches!(
+            decode_charstring(charstring, None, &[global_subr]),
+            Err(Error::MissingSubroutines)
+        ));
+    }
+
+    #[test]
+    fn read_tokens() {
+        // 100 -1000 1.5 hintmask <mask> 12 34 (hflex) 9 (reserved)
+        let charstring: &[u8] = &[239, 254, 124, 255, 0, 1, 128, 0, 19, 0xF0, 12, 34, 9];
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+        while pos < charstring.len() {
+            let (token, len) = read_token(&charstring[pos..]).unwrap();
+            tokens.push(token);
+            pos += len;
+            if token == Token::Operator(19) {
+                // skip the mask for a single stem
+                pos += 1;
+            }
+        }
+        assert_eq!(
+            tokens,
+            [
+                Token::Operand(Number::I32(100)),
+                Token::Operand(Number::I32(-1000)),
+                Token::Operand(Number::Fixed(Fixed::from_f64(1.5))),
+                Token::Operator(19),
+                Token::Operator(0x0C00 | 34),
+                Token::Operator(9),
+            ]
+        );
+        // a truncated operand
+        assert!(read_token(&[28, 1]).is_err());
+    }
 }
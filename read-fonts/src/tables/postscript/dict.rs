@@ -172,12 +172,28 @@ where
 /// This does not perform any additional processing such as type conversion,
 /// delta decoding or blending.
 pub fn tokens(dict_data: &[u8]) -> impl Iterator<Item = Result<Token, Error>> + '_ + Clone {
+    tokens_with_ranges(dict_data).map(|token| token.map(|(token, _)| token))
+}
+
+/// Given a byte slice containing DICT data, returns an iterator yielding
+/// raw operands and operators, along with the range of bytes encoding each
+/// of them.
+///
+/// This is useful when rewriting a DICT, to copy the tokens that don't
+/// change without re-encoding them.
+pub fn tokens_with_ranges(
+    dict_data: &[u8],
+) -> impl Iterator<Item = Result<(Token, Range<usize>), Error>> + '_ + Clone {
     let mut cursor = crate::FontData::new(dict_data).cursor();
     std::iter::from_fn(move || {
-        if cursor.remaining_bytes() == 0 {
+        let start = dict_data.len() - cursor.remaining_bytes();
+        if start == dict_data.len() {
             None
         } else {
-            Some(parse_token(&mut cursor))
+            Some(parse_token(&mut cursor).map(|token| {
+                let end = dict_data.len() - cursor.remaining_bytes();
+                (token, start..end)
+            }))
         }
     })
 }
@@ -614,6 +630,24 @@ mod tests {
         assert_eq!(&tokens, expected);
     }
 
+    #[test]
+    fn example_top_dict_token_ranges() {
+        use Operator::*;
+        let top_dict_data = &font_test_data::cff2::EXAMPLE[5..12];
+        let tokens: Vec<_> = tokens_with_ranges(top_dict_data)
+            .map(|entry| entry.unwrap())
+            .collect();
+        let expected: &[(Token, Range<usize>)] = &[
+            (68.into(), 0..1),
+            (FdArrayOffset.into(), 1..3),
+            (56.into(), 3..4),
+            (CharstringsOffset.into(), 4..5),
+            (16.into(), 5..6),
+            (VariationStoreOffset.into(), 6..7),
+        ];
+        assert_eq!(&tokens, expected);
+    }
+
     #[test]
     fn example_top_dict_entries() {
         use Entry::*;
@@ -38,14 +38,7 @@ impl<'a> Index<'a> {
     ///
     /// See <https://learn.microsoft.com/en-us/typography/opentype/spec/cff2#9-local-and-global-subr-indexes>
     pub fn subr_bias(&self) -> i32 {
-        let count = self.count();
-        if count < 1240 {
-            107
-        } else if count < 33900 {
-            1131
-        } else {
-            32768
-        }
+        subr_bias(self.count())
     }
 
     /// Returns the total size in bytes of the index table.
@@ -73,6 +66,20 @@ impl<'a> Index<'a> {
     }
 }
 
+/// Computes the subroutine bias for an index containing `count` objects.
+///
+/// This is the same as [`Index::subr_bias`], for use when the index has not
+/// been written yet.
+pub fn subr_bias(count: u32) -> i32 {
+    if count < 1240 {
+        107
+    } else if count < 33900 {
+        1131
+    } else {
+        32768
+    }
+}
+
 impl<'a> From<Index1<'a>> for Index<'a> {
     fn from(value: Index1<'a>) -> Self {
         Self::Format1(value)
@@ -1,10 +1,16 @@
 //! A GPOS ValueRecord
 
 use font_types::Nullable;
-use types::{BigEndian, FixedSize, Offset16};
+use types::{BigEndian, F2Dot14, FWord, FixedSize, Offset16};
 
 use super::ValueFormat;
-use crate::{tables::layout::DeviceOrVariationIndex, ResolveNullableOffset};
+use crate::{
+    tables::{
+        layout::DeviceOrVariationIndex,
+        variations::{FloatItemDeltaTarget, ItemVariationStore},
+    },
+    ResolveNullableOffset,
+};
 
 #[cfg(feature = "traversal")]
 use crate::traversal::{Field, FieldType, RecordResolver, SomeRecord};
@@ -135,6 +141,113 @@ impl ValueRecord {
     ) -> Option<Result<DeviceOrVariationIndex<'a>, ReadError>> {
         self.y_advance_device.get().resolve(data)
     }
+
+    /// Returns the x-placement adjustment, including any variation delta at
+    /// the given normalized coordinates.
+    ///
+    /// `data` is the data of the table containing this record, used to
+    /// resolve the device offsets. See [`x_advance_at_coords`] for details.
+    ///
+    /// [`x_advance_at_coords`]: Self::x_advance_at_coords
+    pub fn x_placement_at_coords(
+        &self,
+        data: FontData,
+        variation_store: Option<&ItemVariationStore>,
+        coords: &[F2Dot14],
+    ) -> f32 {
+        value_at_coords(
+            self.x_placement(),
+            self.x_placement_device(data),
+            variation_store,
+            coords,
+        )
+    }
+
+    /// Returns the y-placement adjustment, including any variation delta at
+    /// the given normalized coordinates.
+    ///
+    /// See [`x_advance_at_coords`] for details.
+    ///
+    /// [`x_advance_at_coords`]: Self::x_advance_at_coords
+    pub fn y_placement_at_coords(
+        &self,
+        data: FontData,
+        variation_store: Option<&ItemVariationStore>,
+        coords: &[F2Dot14],
+    ) -> f32 {
+        value_at_coords(
+            self.y_placement(),
+            self.y_placement_device(data),
+            variation_store,
+            coords,
+        )
+    }
+
+    /// Returns the x-advance adjustment, including any variation delta at
+    /// the given normalized coordinates.
+    ///
+    /// `data` is the data of the table containing this record, used to
+    /// resolve the device offsets, and `variation_store` is the item
+    /// variation store from the GDEF table.
+    ///
+    /// A missing value is treated as zero. The delta is only applied if the
+    /// record has a variation index table and a variation store is provided;
+    /// device tables for hinting adjustments are ignored, as are errors
+    /// when resolving the delta.
+    pub fn x_advance_at_coords(
+        &self,
+        data: FontData,
+        variation_store: Option<&ItemVariationStore>,
+        coords: &[F2Dot14],
+    ) -> f32 {
+        value_at_coords(
+            self.x_advance(),
+            self.x_advance_device(data),
+            variation_store,
+            coords,
+        )
+    }
+
+    /// Returns the y-advance adjustment, including any variation delta at
+    /// the given normalized coordinates.
+    ///
+    /// See [`x_advance_at_coords`] for details.
+    ///
+    /// [`x_advance_at_coords`]: Self::x_advance_at_coords
+    pub fn y_advance_at_coords(
+        &self,
+        data: FontData,
+        variation_store: Option<&ItemVariationStore>,
+        coords: &[F2Dot14],
+    ) -> f32 {
+        value_at_coords(
+            self.y_advance(),
+            self.y_advance_device(data),
+            variation_store,
+            coords,
+        )
+    }
+}
+
+fn value_at_coords(
+    value: Option<i16>,
+    device: Option<Result<DeviceOrVariationIndex, ReadError>>,
+    variation_store: Option<&ItemVariationStore>,
+    coords: &[F2Dot14],
+) -> f32 {
+    let value = FWord::new(value.unwrap_or_default());
+    let delta = match (device, variation_store) {
+        (Some(Ok(DeviceOrVariationIndex::VariationIndex(index))), Some(store))
+            if !coords.is_empty() =>
+        {
+            store.compute_float_delta(index.into(), coords).ok()
+        }
+        _ => None,
+    };
+    match delta {
+        Some(delta) => value.apply_float_delta(delta),
+        None => value.to_i16() as f32,
+    }
 }
 
 impl ReadArgs for ValueRecord {
@@ -244,4 +357,49 @@ mod tests {
         assert_eq!(format, ValueFormat::ANY_DEVICE_OR_VARIDX);
         assert_eq!(format.record_byte_len(), 4 * 2);
     }
+
+    #[test]
+    fn values_at_coords() {
+        use crate::{test_helpers::BeBuffer, FontRead};
+
+        // a store with a single region peaking at 1.0 on one axis and a
+        // single delta of 50 for that region
+        let store_data = BeBuffer::new()
+            .push(1u16) // format
+            .push(12u32) // region list offset
+            .push(1u16) // item variation data count
+            .push(22u32) // item variation data offset
+            // region list
+            .extend([1u16, 1u16]) // axis count, region count
+            .extend([0u16, 0x4000, 0x4000]) // start, peak, end
+            // item variation data
+            .extend([1u16, 0, 1]) // item count, word delta count, region index count
+            .push(0u16) // region index
+            .push(50i8);
+        let store = ItemVariationStore::read(store_data.font_data()).unwrap();
+
+        let format = ValueFormat::X_PLACEMENT
+            | ValueFormat::X_ADVANCE
+            | ValueFormat::X_PLACEMENT_DEVICE
+            | ValueFormat::X_ADVANCE_DEVICE;
+        let data = BeBuffer::new()
+            .push(-20i16) // x placement
+            .push(100i16) // x advance
+            .push(14u16) // x placement device: a hinting device table
+            .push(8u16) // x advance device: a variation index
+            .extend([0u16, 0, 0x8000]) // variation index (outer, inner, format)
+            .extend([12u16, 12, 1, 0x4000]); // device table
+        let record = ValueRecord::read(data.font_data(), format).unwrap();
+        let data = data.font_data();
+
+        let half = [F2Dot14::from_f32(0.5)];
+        assert_eq!(record.x_advance_at_coords(data, Some(&store), &half), 125.0);
+        assert_eq!(record.x_advance_at_coords(data, Some(&store), &[]), 100.0);
+        assert_eq!(record.x_advance_at_coords(data, None, &half), 100.0);
+        assert_eq!(
+            record.x_placement_at_coords(data, Some(&store), &half),
+            -20.0
+        );
+        assert_eq!(record.y_advance_at_coords(data, Some(&store), &half), 0.0);
+    }
 }
@@ -1,4 +1,5 @@
 use super::*;
+use crate::test_helpers::BeBuffer;
 use font_test_data::layout as test_data;
 
 #[test]
@@ -12,6 +13,33 @@ fn example_1_scripts() {
     assert_eq!(table.script_records()[2].script_tag(), Tag::new(b"latn"));
 }
 
+#[test]
+fn script_list_lookup_by_tag() {
+    let table = ScriptList::read(test_data::SCRIPTS.into()).unwrap();
+    assert_eq!(
+        table.script_tags().collect::<Vec<_>>(),
+        [Tag::new(b"hani"), Tag::new(b"kana"), Tag::new(b"latn")]
+    );
+    assert!(table.has_script(Tag::new(b"kana")));
+    assert!(!table.has_script(Tag::new(b"arab")));
+    assert!(table.script(Tag::new(b"arab")).is_none());
+
+    let buf = BeBuffer::new()
+        .push(2u16) // script count
+        .push(Tag::new(b"arab"))
+        .push(14u16)
+        .push(Tag::new(b"latn"))
+        .push(18u16)
+        // arab: no default lang sys, no lang sys records
+        .extend([0u16, 0u16])
+        // latn: no default lang sys, one (truncated) lang sys record
+        .extend([0u16, 1u16]);
+    let table = ScriptList::read(buf.font_data()).unwrap();
+    let arab = table.script(Tag::new(b"arab")).unwrap().unwrap();
+    assert_eq!(arab.lang_sys_count(), 0);
+    assert!(table.script(Tag::new(b"latn")).unwrap().is_err());
+}
+
 #[test]
 fn example_2_scripts_and_langs() {
     // https://docs.microsoft.com/en-us/typography/opentype/spec/chapter2#example-2-script-table-langsysrecord-and-langsys-table
@@ -39,3 +67,125 @@ fn example_3_featurelist_and_feature() {
     assert!(feature.feature_params_offset().is_null());
     assert_eq!(feature.lookup_list_indices().len(), 1);
 }
+
+#[test]
+fn features_by_tag() {
+    let table = FeatureList::read(test_data::FEATURELIST_AND_FEATURE.into()).unwrap();
+    let liga = Tag::new(b"liga");
+    assert_eq!(
+        table.feature_indices_for_tag(liga).collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+    let lookup_counts = table
+        .features_with_tag(liga)
+        .map(|result| {
+            let (ix, feature) = result.unwrap();
+            (ix, feature.lookup_index_count())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(lookup_counts, [(0, 1), (1, 2), (2, 3)]);
+
+    let kern = Tag::new(b"kern");
+    assert_eq!(table.feature_indices_for_tag(kern).count(), 0);
+    assert_eq!(table.features_with_tag(kern).count(), 0);
+}
+
+#[test]
+fn lookup_indices_deduped() {
+    let table = FeatureList::read(test_data::FEATURELIST_AND_FEATURE.into()).unwrap();
+    // lookup 0 is referenced by two of the features
+    assert_eq!(
+        table
+            .lookup_indices_deduped()
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        [0, 1, 2]
+    );
+}
+
+#[test]
+fn feature_tags() {
+    let table = FeatureList::read(test_data::FEATURELIST_AND_FEATURE.into()).unwrap();
+    assert_eq!(
+        table.feature_tags().into_iter().collect::<Vec<_>>(),
+        [Tag::new(b"liga")]
+    );
+}
+
+#[test]
+fn scripts_with_feature() {
+    let kern = Tag::new(b"kern");
+    let liga = Tag::new(b"liga");
+    let features = BeBuffer::new()
+        .push(3u16)
+        .push(kern)
+        .push(20u16)
+        .push(liga)
+        .push(24u16)
+        .push(kern)
+        .push(28u16)
+        // three empty features
+        .extend([0u16; 6]);
+    let features = FeatureList::read(features.font_data()).unwrap();
+    assert_eq!(
+        features.feature_tags().into_iter().collect::<Vec<_>>(),
+        [kern, liga]
+    );
+
+    let dflt = Tag::new(b"DFLT");
+    let latn = Tag::new(b"latn");
+    let trk = Tag::new(b"TRK ");
+    let scripts = BeBuffer::new()
+        .push(2u16)
+        .push(dflt)
+        .push(14u16)
+        .push(latn)
+        .push(26u16)
+        // DFLT: default lang sys with feature 0
+        .extend([4u16, 0])
+        .extend([0u16, 0xFFFF, 1, 0])
+        // latn: default lang sys with feature 1, TRK with required
+        // feature 1 and feature 2
+        .extend([10u16, 1])
+        .push(trk)
+        .push(18u16)
+        .extend([0u16, 0xFFFF, 1, 1])
+        .extend([0u16, 1, 1, 2]);
+    let scripts = ScriptList::read(scripts.font_data()).unwrap();
+    assert_eq!(
+        scripts.scripts_with_feature(&features, kern).unwrap(),
+        [(dflt, None), (latn, Some(trk))]
+    );
+    assert_eq!(
+        scripts.scripts_with_feature(&features, liga).unwrap(),
+        [(latn, None), (latn, Some(trk))]
+    );
+    assert!(scripts
+        .scripts_with_feature(&features, Tag::new(b"mark"))
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn lookup_count_and_type_from_bytes() {
+    let buf = BeBuffer::new()
+        .extend([1u16, 0]) // version
+        .extend([0u16, 0]) // script and feature list offsets
+        .push(10u16) // lookup list offset
+        // lookup list
+        .push(2u16)
+        .extend([6u16, 14])
+        // lookup 0: type 4, no subtables
+        .extend([4u16, 0, 0])
+        .push(0u16) // padding
+        // lookup 1: type 7, no subtables
+        .extend([7u16, 0, 0]);
+    assert_eq!(lookup_count(&buf), 2);
+    assert_eq!(lookup_type(&buf, 0), Some(4));
+    assert_eq!(lookup_type(&buf, 1), Some(7));
+    assert_eq!(lookup_type(&buf, 2), None);
+
+    assert_eq!(lookup_count(&buf[..8]), 0);
+    assert_eq!(lookup_type(&buf[..20], 1), None);
+}
@@ -1,4 +1,4 @@
-use types::{GlyphId16, MajorMinor};
+use types::{GlyphId, GlyphId16, MajorMinor};
 
 use super::*;
 use crate::tables::layout::{ClassDefFormat2, DeltaFormat, DeviceOrVariationIndex};
@@ -30,6 +30,15 @@ fn attach_list_table() {
     assert_eq!(attach_point.point_indices()[1].get(), 23);
 }
 
+#[test]
+fn attach_list_attachment_points() {
+    let table = AttachList::read(test_data::ATTACHLIST_TABLE.into()).unwrap();
+    let points = table.attachment_points(GlyphId::new(32)).unwrap();
+    assert_eq!(points.iter().map(|p| p.get()).collect::<Vec<_>>(), [14, 23]);
+    assert_eq!(table.attachment_points(GlyphId::new(28)).unwrap().len(), 1);
+    assert!(table.attachment_points(GlyphId::new(29)).is_none());
+}
+
 #[test]
 fn lig_caret_list() {
     let table = LigCaretList::read(test_data::LIGCARETLIST_TABLE.into()).unwrap();
@@ -69,3 +78,32 @@ fn caretvalueformat3() {
             .collect::<Vec<_>>()
     );
 }
+
+#[test]
+fn mark_glyph_sets() {
+    let buf = crate::test_helpers::BeBuffer::new()
+        // version 1.2
+        .extend([1u16, 2])
+        // glyphClassDef, attachList, ligCaretList, markAttachClassDef
+        .extend([0u16, 0, 0, 0])
+        // markGlyphSetsDef
+        .push(14u16)
+        // format, markGlyphSetCount
+        .extend([1u16, 2])
+        // coverage offsets
+        .extend([12u32, 20])
+        // coverage format 1 with glyphs 10, 11
+        .extend([1u16, 2, 10, 11])
+        // coverage format 1 with glyph 12
+        .extend([1u16, 1, 12]);
+    let table = Gdef::read(FontData::new(&buf)).unwrap();
+    let sets = table
+        .mark_glyph_sets()
+        .unwrap()
+        .map(|(index, coverage)| (index, coverage.iter().map(|gid| gid.to_u16()).collect()))
+        .collect::<Vec<(u16, Vec<u16>)>>();
+    assert_eq!(sets, [(0, vec![10, 11]), (1, vec![12])]);
+
+    let table = Gdef::read(test_data::GDEF_HEADER.into()).unwrap();
+    assert!(table.mark_glyph_sets().is_none());
+}
@@ -1,6 +1,7 @@
 use crate::tables::layout::DeltaFormat;
 
 use super::*;
+use crate::test_helpers::BeBuffer;
 use font_test_data::gpos as test_data;
 
 #[test]
@@ -36,6 +37,26 @@ fn singleposformat2() {
     assert!(table.value_records().get(3).is_err());
 }
 
+#[test]
+fn singlepos_value_record_for() {
+    let table = SinglePos::read(test_data::SINGLEPOSFORMAT1.into()).unwrap();
+    for gid in 0x1b3..=0x1bc {
+        let record = table.value_record_for(GlyphId::new(gid)).unwrap();
+        assert_eq!(record.y_placement(), Some(-80));
+    }
+    assert!(table.value_record_for(GlyphId::new(0x1bd)).is_none());
+
+    let table = SinglePos::read(test_data::SINGLEPOSFORMAT2.into()).unwrap();
+    let x_placements = [0x4f, 0x125, 0x129].map(|gid| {
+        table
+            .value_record_for(GlyphId::new(gid))
+            .unwrap()
+            .x_placement()
+    });
+    assert_eq!(x_placements, [Some(50), Some(25), Some(10)]);
+    assert!(table.value_record_for(GlyphId::new(0x50)).is_none());
+}
+
 #[test]
 fn pairposformat1() {
     // https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#example-4-pairposformat1-subtable
@@ -103,6 +124,21 @@ fn cursiveposformat1() {
     assert_eq!(entry2.y_coordinate(), 44);
 }
 
+#[test]
+fn cursiveposformat1_entry_exit_for() {
+    let table = CursivePosFormat1::read(test_data::CURSIVEPOSFORMAT1.into()).unwrap();
+    let (entry, exit) = table.entry_exit_for(GlyphId::new(0x203)).unwrap();
+    let AnchorTable::Format1(entry) = entry.unwrap() else {
+        panic!("wrong anchor format");
+    };
+    assert_eq!((entry.x_coordinate(), entry.y_coordinate()), (1500, 44));
+    let AnchorTable::Format1(exit) = exit.unwrap() else {
+        panic!("wrong anchor format");
+    };
+    assert_eq!((exit.x_coordinate(), exit.y_coordinate()), (0, -20));
+    assert!(table.entry_exit_for(GlyphId::new(0x204)).is_none());
+}
+
 #[test]
 fn markbaseposformat1() {
     // https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#example-7-markbaseposformat1-subtable
@@ -180,9 +216,68 @@ fn contextualposformat2() {
 fn contextualposformat3() {
     // https://docs.microsoft.com/en-us/typography/opentype/spec/gpos#example-12-contextual-positioning-format-3
 
-    let _table =
+    let table =
         crate::tables::layout::SequenceContextFormat3::read(test_data::CONTEXTUALPOSFORMAT3.into())
             .unwrap();
+    let second = table.input_coverage_at(1).unwrap();
+    assert_eq!(
+        second.iter().map(|gid| gid.to_u16()).collect::<Vec<_>>(),
+        [0x11E, 0x12D]
+    );
+    assert!(table.input_coverage_at(3).is_err());
+    let records = table.seq_lookup_records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].sequence_index(), 1);
+    assert_eq!(records[0].lookup_list_index(), 1);
+
+    let context = PositionSequenceContext::read(test_data::CONTEXTUALPOSFORMAT3.into()).unwrap();
+    let coverage = context.coverage().unwrap();
+    assert_eq!(coverage.iter().count(), 20);
+    assert_eq!(coverage.get(GlyphId::new(0x33)), Some(0));
+}
+
+#[test]
+fn contextualposformat1_coverage() {
+    let context = PositionSequenceContext::read(test_data::CONTEXTUALPOSFORMAT1.into()).unwrap();
+    let coverage = context.coverage().unwrap();
+    assert_eq!(
+        coverage.iter().map(|gid| gid.to_u16()).collect::<Vec<_>>(),
+        [0x2A6]
+    );
+}
+
+#[test]
+fn chained_context_format3_coverage() {
+    let buf = BeBuffer::new()
+        .push(3u16) // format
+        .push(1u16) // backtrack count
+        .push(20u16)
+        .push(2u16) // input count
+        .extend([26u16, 32u16])
+        .push(0u16) // lookahead count
+        .push(1u16) // seq lookup count
+        .extend([1u16, 4u16]) // sequence index, lookup list index
+        // backtrack coverage
+        .extend([1u16, 1u16, 5u16])
+        // input coverages
+        .extend([1u16, 1u16, 10u16])
+        .extend([1u16, 2u16, 11u16, 12u16]);
+    let context = PositionChainContext::read(buf.font_data()).unwrap();
+    let coverage = context.coverage().unwrap();
+    assert_eq!(
+        coverage.iter().map(|gid| gid.to_u16()).collect::<Vec<_>>(),
+        [10]
+    );
+    let PositionChainContext::Format3(table) = context else {
+        panic!("expected format 3");
+    };
+    let second = table.input_coverage_at(1).unwrap();
+    assert_eq!(
+        second.iter().map(|gid| gid.to_u16()).collect::<Vec<_>>(),
+        [11, 12]
+    );
+    assert!(table.input_coverage_at(2).is_err());
+    assert_eq!(table.seq_lookup_records()[0].lookup_list_index(), 4);
 }
 
 //FIXME: we don't have a way to instantiate individual records right now?
@@ -265,6 +360,34 @@ fn anchorformat3() {
     assert_eq!(y_dev.delta_value(), [0x1111, 0x2200]);
 }
 
+#[test]
+fn effective_lookup_type() {
+    let buf = BeBuffer::new()
+        // lookup type, flag, subtable count, subtable offset
+        .extend([9u16, 0, 1, 8])
+        // extension format, extension lookup type, extension offset
+        .extend([1u16, 2])
+        .push(8u32);
+    let lookup = PositionLookup::read(FontData::new(&buf)).unwrap();
+    assert_eq!(lookup.lookup_type(), 9);
+    assert_eq!(lookup.effective_type(), 2);
+}
+
+#[test]
+fn gpos_lookup_type() {
+    for (raw, expected) in [
+        (2, Some(GposLookupType::Pair)),
+        (5, Some(GposLookupType::MarkToLigature)),
+        (9, Some(GposLookupType::Extension)),
+        (0, None),
+        (10, None),
+    ] {
+        let buf = BeBuffer::new().extend([raw, 0u16, 0]);
+        let lookup = Lookup::<()>::read(FontData::new(&buf)).unwrap();
+        assert_eq!(lookup.gpos_type(), expected);
+    }
+}
+
 //NOTE: I think the sample bytes are missing the actual anchor tables??
 // and so we can't really round-trip this...
 //#[test]
@@ -35,6 +35,20 @@ fn multiplesubstformat1() {
     );
 }
 
+#[test]
+fn multiplesubstformat1_sequence_for() {
+    let table = MultipleSubstFormat1::read(test_data::MULTIPLESUBSTFORMAT1_TABLE.into()).unwrap();
+    assert_eq!(
+        table
+            .sequence_for(GlyphId::new(0xf1))
+            .unwrap()
+            .iter()
+            .collect::<Vec<_>>(),
+        [GlyphId::new(0x1a), GlyphId::new(0x1a), GlyphId::new(0x1d)]
+    );
+    assert!(table.sequence_for(GlyphId::new(0xf2)).is_none());
+}
+
 #[test]
 fn alternatesubstformat1() {
     // https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#example-5-alternatesubstformat-1-subtable
@@ -47,6 +61,28 @@ fn alternatesubstformat1() {
     );
 }
 
+#[test]
+fn alternatesubstformat1_alternates_for() {
+    let table = AlternateSubstFormat1::read(test_data::ALTERNATESUBSTFORMAT1_TABLE.into()).unwrap();
+    let glyph = GlyphId::new(0x3a);
+    assert_eq!(
+        table
+            .alternates_for(glyph)
+            .unwrap()
+            .iter()
+            .collect::<Vec<_>>(),
+        [GlyphId::new(0xc9), GlyphId::new(0xca)]
+    );
+    assert_eq!(table.alternate_count(glyph), 2);
+    assert_eq!(table.nth_alternate(glyph, 1), Some(GlyphId::new(0xca)));
+    assert_eq!(table.nth_alternate(glyph, 2), None);
+
+    let uncovered = GlyphId::new(0x3b);
+    assert!(table.alternates_for(uncovered).is_none());
+    assert_eq!(table.alternate_count(uncovered), 0);
+    assert_eq!(table.nth_alternate(uncovered, 0), None);
+}
+
 #[test]
 fn ligaturesubstformat1() {
     // https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#example-6-ligaturesubstformat1-subtable
@@ -71,8 +107,216 @@ fn ligaturesubstformat1() {
     );
 }
 
+#[test]
+fn ligaturesubstformat1_match_sequence() {
+    let table = LigatureSubstFormat1::read(test_data::LIGATURESUBSTFORMAT1_TABLE.into()).unwrap();
+    let gids = |ids: &[u16]| ids.iter().copied().map(GlyphId::from).collect::<Vec<_>>();
+    assert_eq!(
+        table.match_sequence(&gids(&[0x19, 0x28, 0x17])),
+        Some(GlyphId::new(347))
+    );
+    // the first matching ligature is preferred
+    assert_eq!(
+        table.match_sequence(&gids(&[0x1a, 0x1a, 0x1d, 0x5])),
+        Some(GlyphId::new(0xf1))
+    );
+    assert_eq!(
+        table.match_sequence(&gids(&[0x1a, 0x1d])),
+        Some(GlyphId::new(0xf0))
+    );
+    assert_eq!(table.match_sequence(&gids(&[0x19, 0x28])), None);
+    assert_eq!(table.match_sequence(&gids(&[0x28, 0x17])), None);
+    assert_eq!(table.match_sequence(&[]), None);
+
+    let sequences = table
+        .possible_sequences_starting_with(GlyphId::new(0x1a))
+        .unwrap()
+        .map(|(components, ligature)| {
+            (
+                components
+                    .iter()
+                    .map(|gid| gid.get().to_u16())
+                    .collect::<Vec<_>>(),
+                ligature,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sequences,
+        [
+            (vec![0x1a, 0x1d], GlyphId::new(0xf1)),
+            (vec![0x1d], GlyphId::new(0xf0))
+        ]
+    );
+    assert!(table
+        .possible_sequences_starting_with(GlyphId::new(0x28))
+        .is_none());
+}
+
+#[test]
+fn reversechainsinglesubstformat1_substitute() {
+    use crate::test_helpers::BeBuffer;
+
+    let buf = BeBuffer::new()
+        .push(1u16) // format
+        .push(18u16) // coverage offset
+        .push(1u16) // backtrack count
+        .push(26u16) // backtrack coverage offset
+        .push(1u16) // lookahead count
+        .push(32u16) // lookahead coverage offset
+        .push(2u16) // glyph count
+        .extend([20u16, 21])
+        // coverage
+        .extend([1u16, 2, 10, 11])
+        // backtrack coverage
+        .extend([1u16, 1, 5])
+        // lookahead coverage
+        .extend([1u16, 1, 6]);
+    let table = ReverseChainSingleSubstFormat1::read(buf.font_data()).unwrap();
+    let gid = GlyphId::new;
+    assert_eq!(
+        table.substitute(gid(10), &[gid(5)], &[gid(6)]),
+        Some(gid(20))
+    );
+    // extra context is ignored
+    assert_eq!(
+        table.substitute(gid(11), &[gid(5), gid(1)], &[gid(6), gid(2)]),
+        Some(gid(21))
+    );
+    // context glyphs not covered
+    assert_eq!(table.substitute(gid(10), &[gid(6)], &[gid(6)]), None);
+    assert_eq!(table.substitute(gid(10), &[gid(5)], &[gid(5)]), None);
+    // not enough context
+    assert_eq!(table.substitute(gid(10), &[gid(5)], &[]), None);
+    // input glyph not covered
+    assert_eq!(table.substitute(gid(12), &[gid(5)], &[gid(6)]), None);
+}
+
 //TODO:
 // - https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#example-7-contextual-substitution-format-1
 // - https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#example-8-contextual-substitution-format-2
 // - https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#example-9-contextual-substitution-format-3
 // - https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#example-10-reversechainsinglesubstformat1-subtable
+
+#[test]
+fn contextual_substitution_rule_sets() {
+    use crate::tables::layout::{SequenceContextFormat1, SequenceContextFormat2};
+
+    // https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#example-10-contextual-substitution-format-1
+    let table =
+        SequenceContextFormat1::read(test_data::CONTEXTUAL_SUBSTITUTION_FORMAT1.into()).unwrap();
+    let rule_set = table.rule_set_for(GlyphId::new(0x28)).unwrap();
+    let rule = rule_set.seq_rules().get(0).unwrap();
+    assert_eq!(rule.input_sequence()[0].get(), GlyphId16::new(0x5d));
+    let record = &rule.seq_lookup_records()[0];
+    assert_eq!(
+        (record.sequence_index(), record.lookup_list_index()),
+        (0, 1)
+    );
+    let rule_set = table.rule_set_for(GlyphId::new(0x5d)).unwrap();
+    let rule = rule_set.seq_rules().get(0).unwrap();
+    assert_eq!(rule.input_sequence()[0].get(), GlyphId16::new(0x28));
+    assert!(table.rule_set_for(GlyphId::new(0x29)).is_none());
+
+    // https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#example-11-contextual-substitution-format-2
+    let table =
+        SequenceContextFormat2::read(test_data::CONTEXTUAL_SUBSTITUTION_FORMAT2.into()).unwrap();
+    let rule_set = table.rule_set_for(GlyphId::new(0x30)).unwrap();
+    let rule = rule_set.class_seq_rules().get(0).unwrap();
+    assert_eq!(
+        rule.input_sequence()
+            .iter()
+            .map(|class| class.get())
+            .collect::<Vec<_>>(),
+        [1]
+    );
+    assert!(table.rule_set_for_class(3).is_some());
+    // class 0 has no rule set
+    assert!(table.rule_set_for_class(0).is_none());
+    // glyphs in class 1 are not covered
+    assert!(table.rule_set_for(GlyphId::new(0xd2)).is_none());
+}
+
+#[test]
+fn apply_feature_variations() {
+    use crate::{FontRef, TableProvider};
+
+    let font = FontRef::new(font_test_data::closure::VARIATIONS_CLOSURE).unwrap();
+    let gsub = font.gsub().unwrap();
+    let feature_list = gsub.feature_list().unwrap();
+    let default_feature = feature_list.feature_records()[0]
+        .feature(feature_list.offset_data())
+        .unwrap();
+
+    // the 'heavy' condition set covers 0.625..=0.875
+    let heavy = gsub
+        .apply_feature_variations(&[F2Dot14::from_f32(0.75)])
+        .unwrap();
+    assert_eq!(heavy.len(), 1);
+    let (feature_index, feature) = &heavy[0];
+    assert_eq!(*feature_index, 0);
+    assert_ne!(
+        feature.lookup_list_indices(),
+        default_feature.lookup_list_indices()
+    );
+
+    for coord in [F2Dot14::ZERO, F2Dot14::ONE] {
+        assert!(gsub.apply_feature_variations(&[coord]).unwrap().is_empty());
+    }
+}
+
+#[test]
+fn effective_lookup_type() {
+    let buf = crate::test_helpers::BeBuffer::new()
+        // lookup type, flag, subtable count, subtable offset
+        .extend([7u16, 0, 1, 8])
+        // extension format, extension lookup type, extension offset
+        .extend([1u16, 4])
+        .push(8u32);
+    let lookup = SubstitutionLookup::read(FontData::new(&buf)).unwrap();
+    assert_eq!(lookup.lookup_type(), 7);
+    assert_eq!(lookup.effective_type(), 4);
+
+    // an extension lookup with no subtables keeps the extension type
+    let buf = crate::test_helpers::BeBuffer::new().extend([7u16, 0, 0]);
+    let lookup = SubstitutionLookup::read(FontData::new(&buf)).unwrap();
+    assert_eq!(lookup.effective_type(), 7);
+
+    // other lookups report their own type
+    let buf = crate::test_helpers::BeBuffer::new().extend([4u16, 0, 0]);
+    let lookup = SubstitutionLookup::read(FontData::new(&buf)).unwrap();
+    assert_eq!(lookup.effective_type(), 4);
+}
+
+#[test]
+fn gsub_lookup_type() {
+    for (raw, expected) in [
+        (1, Some(GsubLookupType::Single)),
+        (6, Some(GsubLookupType::ChainedContext)),
+        (8, Some(GsubLookupType::ReverseChainSingle)),
+        (0, None),
+        (9, None),
+    ] {
+        let buf = crate::test_helpers::BeBuffer::new().extend([raw, 0u16, 0]);
+        let lookup = Lookup::<()>::read(FontData::new(&buf)).unwrap();
+        assert_eq!(lookup.gsub_type(), expected);
+        assert_eq!(expected.map(|ty| ty as u16), expected.and(Some(raw)));
+    }
+}
+
+#[test]
+fn lookup_coverage_size() {
+    let buf = crate::test_helpers::BeBuffer::new()
+        // lookup type, flag, subtable count, subtable offsets
+        .extend([1u16, 0, 2, 10, 26])
+        // format, coverage offset, delta; coverage format, glyph count, glyphs
+        .extend([1u16, 6, 5, 1, 3, 10, 11, 12])
+        .extend([1u16, 6, 5, 1, 2, 11, 20]);
+    let lookup = SubstitutionLookup::read(FontData::new(&buf)).unwrap();
+    // glyph 11 is covered by both subtables but is only counted once
+    assert_eq!(lookup.coverage_size().unwrap(), 4);
+
+    let buf = crate::test_helpers::BeBuffer::new().extend([1u16, 0, 0]);
+    let lookup = SubstitutionLookup::read(FontData::new(&buf)).unwrap();
+    assert_eq!(lookup.coverage_size().unwrap(), 0);
+}
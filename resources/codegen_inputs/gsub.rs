@@ -64,6 +64,7 @@ table SingleSubstFormat2 {
     glyph_count: u16,
     /// Array of substitute glyph IDs — ordered by Coverage index
     #[count($glyph_count)]
+    #[validate(check_substitute_count)]
     substitute_glyph_ids: [GlyphId16],
 }
 
@@ -0,0 +1,56 @@
+#![parse_module(read_fonts::tables::kern)]
+
+/// The OpenType [kern](https://learn.microsoft.com/en-us/typography/opentype/spec/kern) table
+#[tag = "kern"]
+table Kern {
+    /// Table version number—set to 0.
+    version: u16,
+    /// Number of subtables in the kerning table.
+    num_tables: u16,
+    /// The subtables, which are variable-length.
+    #[count(..)]
+    subtable_data: [u8],
+}
+
+/// A kerning subtable.
+table KernSubtable {
+    /// Kern subtable version number—set to 0.
+    version: u16,
+    /// Length of the subtable, in bytes (including this header).
+    length: u16,
+    /// What type of information is contained in this table. The high
+    /// byte is the subtable format.
+    coverage: u16,
+    /// The format-specific subtable data.
+    #[count(..)]
+    data: [u8],
+}
+
+/// Format 0 kerning data: an ordered list of kerning pairs.
+table Kern0 {
+    /// This gives the number of kerning pairs in the table.
+    num_pairs: u16,
+    /// The largest power of two less than or equal to the value of
+    /// num_pairs, multiplied by the size in bytes of an entry in the table.
+    search_range: u16,
+    /// This is calculated as log2 of the largest power of two less than or
+    /// equal to the value of num_pairs.
+    entry_selector: u16,
+    /// The value of num_pairs minus the largest power of two less than or
+    /// equal to num_pairs, and then multiplied by the size in bytes of an
+    /// entry in the table.
+    range_shift: u16,
+    /// Kerning pairs, sorted by left and then right glyph.
+    #[count($num_pairs)]
+    pairs: [Kern0Pair],
+}
+
+/// A kerning pair in a format 0 subtable.
+record Kern0Pair {
+    /// The glyph index for the left-hand glyph in the kerning pair.
+    left: GlyphId16,
+    /// The glyph index for the right-hand glyph in the kerning pair.
+    right: GlyphId16,
+    /// The kerning value for the above pair, in design units.
+    value: FWord,
+}
@@ -68,6 +68,23 @@ pub struct Decoration {
 ///
 /// For variable fonts, deltas are computed using the  [MVAR](https://learn.microsoft.com/en-us/typography/opentype/spec/MVAR)
 /// table.
+///
+/// # Example
+///
+/// Metrics in font units for the default instance are retrieved by passing an
+/// unscaled size and the default location:
+///
+/// ```
+/// use skrifa::{
+///     instance::{LocationRef, Size},
+///     FontRef, MetadataProvider,
+/// };
+///
+/// fn line_height(font: &FontRef) -> f32 {
+///     let metrics = font.metrics(Size::unscaled(), LocationRef::default());
+///     metrics.ascent - metrics.descent + metrics.leading
+/// }
+/// ```
 #[derive(Copy, Clone, PartialEq, Default, Debug)]
 pub struct Metrics {
     /// Number of font design units per em unit.
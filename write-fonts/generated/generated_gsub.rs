@@ -378,6 +378,7 @@ impl Validate for SingleSubstFormat2 {
                 if self.substitute_glyph_ids.len() > (u16::MAX as usize) {
                     ctx.report("array exceeds max length");
                 }
+                self.check_substitute_count(ctx);
             });
         })
     }
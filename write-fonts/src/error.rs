@@ -2,6 +2,8 @@
 
 use std::sync::Arc;
 
+use font_types::Tag;
+
 use crate::{graph::Graph, validate::ValidationReport};
 
 /// A packing could not be found that satisfied all offsets
@@ -18,7 +20,11 @@ pub struct PackingError {
 }
 
 /// An error occurred while writing this table
+///
+/// New variants may be added in the future, so matches on this type must
+/// include a wildcard arm.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum Error {
     /// The table failed a validation check
     ///
@@ -32,6 +38,17 @@ pub enum Error {
     /// tables to be reachable from their parents. See [`PackingError`] for
     /// more details.
     PackingFailed(PackingError),
+    /// Offsets in the table could not be represented
+    ///
+    /// This is returned by [`validate_offsets`](crate::validate_offsets), and
+    /// identifies every offset that still overflowed after attempting to
+    /// repack the table.
+    OffsetOverflow {
+        /// The tag of the table being checked
+        table: Tag,
+        /// The parent and child tables of each overflowing offset
+        offset_sites: Vec<String>,
+    },
 }
 
 impl PackingError {
@@ -49,6 +66,17 @@ impl std::fmt::Display for Error {
         match self {
             Error::ValidationFailed(report) => report.fmt(f),
             Error::PackingFailed(error) => error.fmt(f),
+            Error::OffsetOverflow {
+                table,
+                offset_sites,
+            } => {
+                write!(
+                    f,
+                    "{} offset overflows in '{table}': {}",
+                    offset_sites.len(),
+                    offset_sites.join(", ")
+                )
+            }
         }
     }
 }
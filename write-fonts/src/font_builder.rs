@@ -6,10 +6,19 @@ use std::{borrow::Cow, fmt::Display};
 use read_fonts::{FontRef, TableProvider};
 use types::{Tag, TT_SFNT_VERSION};
 
+use crate::tables::head::Head;
+
 include!("../generated/generated_font.rs");
 
 const TABLE_RECORD_LEN: usize = 16;
 
+/// The value that the checksum of a font, including its checksum
+/// adjustment, should equal.
+const CHECKSUM_MAGIC: u32 = 0xB1B0AFBA;
+
+/// The position of the `checksum_adjustment` field in the head table.
+const CHECKSUM_ADJUSTMENT_RANGE: std::ops::Range<usize> = 8..12;
+
 /// Build a font from some set of tables.
 #[derive(Debug, Clone, Default)]
 pub struct FontBuilder<'a> {
@@ -99,17 +108,32 @@ impl<'a> FontBuilder<'a> {
     /// Assemble all the tables into a binary font file with a [Table Directory].
     ///
     /// [Table Directory]: https://learn.microsoft.com/en-us/typography/opentype/spec/otff#table-directory
+    /// If the font has a `head` table, its `checksum_adjustment` is computed
+    /// from the assembled font and written into the output.
     pub fn build(&mut self) -> Vec<u8> {
+        // the adjustment must be zero while the checksums are computed
+        if let Some(head) = self.tables.get_mut(&Head::TAG) {
+            if let Some(adjustment) = head.get(CHECKSUM_ADJUSTMENT_RANGE) {
+                if adjustment != [0u8; 4] {
+                    head.to_mut()[CHECKSUM_ADJUSTMENT_RANGE].fill(0);
+                }
+            }
+        }
+
         let header_len = std::mem::size_of::<u32>() // sfnt
             + std::mem::size_of::<u16>() * 4 // num_tables to range_shift
             + self.tables.len() * TABLE_RECORD_LEN;
 
         let mut position = header_len as u32;
+        let mut head_offset = None;
         let table_records: Vec<_> = self
             .tables
             .iter_mut()
             .map(|(tag, data)| {
                 let offset = position;
+                if *tag == Head::TAG && data.len() >= CHECKSUM_ADJUSTMENT_RANGE.end {
+                    head_offset = Some(offset as usize);
+                }
                 let length = data.len() as u32;
                 position += length;
                 let (checksum, padding) = checksum_and_padding(data);
@@ -129,6 +153,11 @@ impl<'a> FontBuilder<'a> {
             let padding = [0u8; 4];
             data.extend_from_slice(&padding[..rem]);
         }
+        if let Some(head_offset) = head_offset {
+            let adjustment = CHECKSUM_MAGIC.wrapping_sub(checksum_and_padding(&data).0);
+            let start = head_offset + CHECKSUM_ADJUSTMENT_RANGE.start;
+            data[start..start + 4].copy_from_slice(&adjustment.to_be_bytes());
+        }
         data
     }
 }
@@ -205,6 +234,21 @@ mod tests {
         FontBuilder::default().build();
     }
 
+    #[test]
+    fn computes_checksum_adjustment() {
+        let head = crate::tables::head::Head {
+            checksum_adjustment: 0xdead_beef,
+            units_per_em: 1000,
+            ..Default::default()
+        };
+        let bytes = FontBuilder::default()
+            .add_table(&head)
+            .unwrap()
+            .add_raw(Tag::new(b"abcd"), b"odd".as_slice())
+            .build();
+        read_fonts::tables::head::verify_checksum(&bytes).unwrap();
+    }
+
     #[test]
     fn pad4() {
         for i in 0..10 {
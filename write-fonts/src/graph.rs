@@ -394,6 +394,17 @@ impl Graph {
         result
     }
 
+    /// A description of the tables on either side of an overflowing offset.
+    pub(crate) fn describe_overflow(&self, overflow: &Overflow) -> String {
+        format!(
+            "{} -> {} ({} at distance {})",
+            self.objects[&overflow.parent].type_,
+            self.objects[&overflow.child].type_,
+            overflow.offset_type,
+            overflow.distance
+        )
+    }
+
     fn debug_overflows(&self, overflows: &[Overflow]) {
         let (parents, children): (HashSet<_>, HashSet<_>) =
             overflows.iter().map(|x| (x.parent, x.child)).unzip();
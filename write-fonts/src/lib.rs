@@ -142,7 +142,7 @@ mod hex_diff;
 pub use font_builder::{BuilderError, FontBuilder};
 pub use offsets::{NullableOffsetMarker, OffsetMarker};
 pub use round::OtRound;
-pub use write::{dump_table, FontWrite, TableWriter};
+pub use write::{dump_table, estimated_size, validate_offsets, FontWrite, TableWriter};
 
 /// Rexport of the common font types
 pub extern crate font_types as types;
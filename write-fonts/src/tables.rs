@@ -28,6 +28,7 @@ pub mod stat;
 pub mod variations;
 pub mod vhea;
 pub mod vmtx;
+pub mod vvar;
 
 // ensure that all of our types implement the serde traits
 #[cfg(feature = "serde")]
@@ -57,6 +58,7 @@ fn do_we_even_serde() {
         stat: stat::Stat,
         vhea: vhea::Vhea,
         vmtx: vmtx::Vmtx,
+        vvar: vvar::Vvar,
     }
     let tables = AllTables::default();
     let dumped = bincode::serialize(&tables).unwrap();
@@ -2,10 +2,12 @@
 //!
 //! [GDEF]: https://docs.microsoft.com/en-us/typography/opentype/spec/gdef
 
-use types::MajorMinor;
+use std::collections::BTreeMap;
+
+use types::{GlyphId16, MajorMinor};
 
 use super::{
-    layout::{ClassDef, CoverageTable, DeviceOrVariationIndex},
+    layout::{ClassDef, CoverageTable, CoverageTableBuilder, DeviceOrVariationIndex},
     variations::ItemVariationStore,
 };
 
@@ -23,6 +25,142 @@ impl Gdef {
     }
 }
 
+/// A builder for the [`Gdef`] table.
+///
+/// The class definitions, attachment point list, ligature caret list and
+/// mark glyph sets are only included in the table if they are not empty.
+#[derive(Clone, Debug, Default)]
+pub struct GdefBuilder {
+    glyph_classes: BTreeMap<GlyphId16, GlyphClassDef>,
+    mark_attach_classes: BTreeMap<GlyphId16, u16>,
+    attach_points: BTreeMap<GlyphId16, Vec<u16>>,
+    ligature_carets: BTreeMap<GlyphId16, Vec<CaretValue>>,
+    mark_sets: MarkGlyphSetsDefBuilder,
+}
+
+impl GdefBuilder {
+    /// Set the glyph class of a glyph, replacing any existing class.
+    pub fn add_glyph_class(&mut self, glyph: GlyphId16, class: GlyphClassDef) {
+        self.glyph_classes.insert(glyph, class);
+    }
+
+    /// Set the mark attachment class of a mark glyph, replacing any existing class.
+    pub fn add_mark_attach_class(&mut self, glyph: GlyphId16, class: u16) {
+        self.mark_attach_classes.insert(glyph, class);
+    }
+
+    /// Add attachment points, as contour point indices, for a glyph.
+    pub fn add_attachment_points(
+        &mut self,
+        glyph: GlyphId16,
+        points: impl IntoIterator<Item = u16>,
+    ) {
+        let glyph_points = self.attach_points.entry(glyph).or_default();
+        glyph_points.extend(points);
+        // the spec requires the points to be in increasing order
+        glyph_points.sort_unstable();
+        glyph_points.dedup();
+    }
+
+    /// Set the caret positions of a ligature glyph, replacing any existing carets.
+    ///
+    /// The carets should be ordered by position, in the writing direction of the
+    /// glyph.
+    pub fn add_ligature_carets(&mut self, glyph: GlyphId16, carets: Vec<CaretValue>) {
+        self.ligature_carets.insert(glyph, carets);
+    }
+
+    /// Add a set of mark glyphs, returning the index of the set.
+    ///
+    /// See [`MarkGlyphSetsDefBuilder::add_mark_set`].
+    pub fn add_mark_set(&mut self, glyphs: impl IntoIterator<Item = GlyphId16>) -> u16 {
+        self.mark_sets.add_mark_set(glyphs)
+    }
+
+    /// Construct the [`Gdef`] table.
+    pub fn build(self) -> Gdef {
+        let glyph_class_def = (!self.glyph_classes.is_empty()).then(|| {
+            self.glyph_classes
+                .iter()
+                .map(|(glyph, class)| (*glyph, *class as u16))
+                .collect::<ClassDef>()
+        });
+        let mark_attach_class_def = (!self.mark_attach_classes.is_empty())
+            .then(|| self.mark_attach_classes.into_iter().collect::<ClassDef>());
+        let attach_list = (!self.attach_points.is_empty()).then(|| {
+            AttachList::new(
+                self.attach_points.keys().copied().collect(),
+                self.attach_points
+                    .into_values()
+                    .map(AttachPoint::new)
+                    .collect(),
+            )
+        });
+        let lig_caret_list = (!self.ligature_carets.is_empty()).then(|| {
+            LigCaretList::new(
+                self.ligature_carets.keys().copied().collect(),
+                self.ligature_carets
+                    .into_values()
+                    .map(LigGlyph::new)
+                    .collect(),
+            )
+        });
+        let mark_glyph_sets_def = (!self.mark_sets.is_empty()).then(|| self.mark_sets.build());
+        Gdef {
+            glyph_class_def: glyph_class_def.into(),
+            attach_list: attach_list.into(),
+            lig_caret_list: lig_caret_list.into(),
+            mark_attach_class_def: mark_attach_class_def.into(),
+            mark_glyph_sets_def: mark_glyph_sets_def.into(),
+            item_var_store: Default::default(),
+        }
+    }
+}
+
+/// A builder for the [`MarkGlyphSets`] table.
+///
+/// Each set is assigned the index that lookups with the
+/// `USE_MARK_FILTERING_SET` flag use to refer to it. Adding a set with the
+/// same glyphs as an existing set returns the existing index.
+#[derive(Clone, Debug, Default)]
+pub struct MarkGlyphSetsDefBuilder {
+    // invariant: each set is sorted and deduplicated
+    sets: Vec<Vec<GlyphId16>>,
+}
+
+impl MarkGlyphSetsDefBuilder {
+    /// Add a set of mark glyphs, returning the index of the set.
+    pub fn add_mark_set(&mut self, glyphs: impl IntoIterator<Item = GlyphId16>) -> u16 {
+        let mut glyphs = glyphs.into_iter().collect::<Vec<_>>();
+        glyphs.sort_unstable();
+        glyphs.dedup();
+        let index = match self.sets.iter().position(|set| *set == glyphs) {
+            Some(index) => index,
+            None => {
+                self.sets.push(glyphs);
+                self.sets.len() - 1
+            }
+        };
+        // if we're over u16::MAX sets, crash
+        index.try_into().unwrap()
+    }
+
+    /// Returns `true` if no sets have been added.
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    /// Construct the [`MarkGlyphSets`] table, with a coverage table for each set.
+    pub fn build(self) -> MarkGlyphSets {
+        MarkGlyphSets::new(
+            self.sets
+                .into_iter()
+                .map(|glyphs| CoverageTableBuilder::from_glyphs(glyphs).build())
+                .collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -43,4 +181,94 @@ mod tests {
         assert_eq!(loaded.version(), MajorMinor::VERSION_1_3);
         assert!(!loaded.item_var_store_offset().unwrap().is_null());
     }
+
+    #[test]
+    fn gdef_builder() {
+        let mut builder = GdefBuilder::default();
+        builder.add_glyph_class(GlyphId16::new(5), GlyphClassDef::Base);
+        builder.add_glyph_class(GlyphId16::new(6), GlyphClassDef::Ligature);
+        builder.add_glyph_class(GlyphId16::new(7), GlyphClassDef::Mark);
+        builder.add_glyph_class(GlyphId16::new(8), GlyphClassDef::Mark);
+        builder.add_mark_attach_class(GlyphId16::new(8), 1);
+        builder.add_attachment_points(GlyphId16::new(5), [4, 2]);
+        builder.add_attachment_points(GlyphId16::new(5), [2, 9]);
+        builder.add_ligature_carets(
+            GlyphId16::new(6),
+            vec![CaretValue::format_1(300), CaretValue::format_2(7)],
+        );
+        assert_eq!(builder.add_mark_set([GlyphId16::new(7)]), 0);
+
+        let gdef = builder.build();
+        assert_eq!(gdef.compute_version(), MajorMinor::VERSION_1_2);
+        let dumped = crate::write::dump_table(&gdef).unwrap();
+        let loaded = read_fonts::tables::gdef::Gdef::read(FontData::new(&dumped)).unwrap();
+
+        let glyph_classes = loaded.glyph_class_def().unwrap().unwrap();
+        assert_eq!(glyph_classes.get(GlyphId16::new(5)), 1);
+        assert_eq!(glyph_classes.get(GlyphId16::new(6)), 2);
+        assert_eq!(glyph_classes.get(GlyphId16::new(8)), 3);
+        assert_eq!(glyph_classes.get(GlyphId16::new(9)), 0);
+        let mark_classes = loaded.mark_attach_class_def().unwrap().unwrap();
+        assert_eq!(mark_classes.get(GlyphId16::new(8)), 1);
+        assert_eq!(mark_classes.get(GlyphId16::new(7)), 0);
+
+        let attach_list = loaded.attach_list().unwrap().unwrap();
+        assert_eq!(
+            attach_list.coverage().unwrap().get(GlyphId16::new(5)),
+            Some(0)
+        );
+        let points = attach_list.attach_points().get(0).unwrap();
+        assert_eq!(
+            points
+                .point_indices()
+                .iter()
+                .map(|p| p.get())
+                .collect::<Vec<_>>(),
+            [2, 4, 9]
+        );
+
+        let lig_carets = loaded.lig_caret_list().unwrap().unwrap();
+        assert_eq!(
+            lig_carets.coverage().unwrap().get(GlyphId16::new(6)),
+            Some(0)
+        );
+        let lig_glyph = lig_carets.lig_glyphs().get(0).unwrap();
+        assert_eq!(lig_glyph.caret_values().len(), 2);
+
+        let sets = loaded.mark_glyph_sets().unwrap().collect::<Vec<_>>();
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].1.get(GlyphId16::new(7)), Some(0));
+    }
+
+    #[test]
+    fn empty_gdef_builder() {
+        let gdef = GdefBuilder::default().build();
+        assert_eq!(gdef, Gdef::default());
+        assert_eq!(gdef.compute_version(), MajorMinor::VERSION_1_0);
+    }
+
+    #[test]
+    fn mark_glyph_sets_builder() {
+        let mut builder = MarkGlyphSetsDefBuilder::default();
+        assert!(builder.is_empty());
+        let gids = |ids: &[u16]| ids.iter().copied().map(GlyphId16::new).collect::<Vec<_>>();
+        assert_eq!(builder.add_mark_set(gids(&[12, 10, 11])), 0);
+        assert_eq!(builder.add_mark_set(gids(&[20])), 1);
+        // identical sets share an index
+        assert_eq!(builder.add_mark_set(gids(&[10, 11, 12, 10])), 0);
+
+        let gdef = Gdef {
+            mark_glyph_sets_def: builder.build().into(),
+            ..Default::default()
+        };
+        assert_eq!(gdef.compute_version(), MajorMinor::VERSION_1_2);
+        let dumped = crate::write::dump_table(&gdef).unwrap();
+        let loaded = read_fonts::tables::gdef::Gdef::read(FontData::new(&dumped)).unwrap();
+        let sets = loaded
+            .mark_glyph_sets()
+            .unwrap()
+            .map(|(index, coverage)| (index, coverage.iter().map(|gid| gid.to_u16()).collect()))
+            .collect::<Vec<(u16, Vec<u16>)>>();
+        assert_eq!(sets, [(0, vec![10, 11, 12]), (1, vec![20])]);
+    }
 }
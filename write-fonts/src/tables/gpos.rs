@@ -24,6 +24,12 @@ mod spec_tests;
 mod value_record;
 pub use value_record::ValueRecord;
 
+mod builders;
+pub use builders::{
+    MarkToBaseBuilder, MarkToLigatureBuilder, MarkToMarkBuilder, SingleAdjustBuilder,
+    AUTO_MARK_CLASS,
+};
+
 /// A GPOS lookup list table.
 pub type PositionLookupList = LookupList<PositionLookup>;
 
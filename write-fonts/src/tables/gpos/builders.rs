@@ -0,0 +1,629 @@
+//! Builders for GPOS subtables
+
+use std::collections::BTreeMap;
+
+use crate::tables::layout::{CoverageTable, CoverageTableBuilder};
+use types::GlyphId16;
+
+use super::{
+    AnchorTable, BaseArray, BaseRecord, ComponentRecord, LigatureArray, LigatureAttach, Mark2Array,
+    Mark2Record, MarkArray, MarkBasePosFormat1, MarkLigPosFormat1, MarkMarkPosFormat1, MarkRecord,
+    SinglePos, ValueFormat, ValueRecord,
+};
+
+/// Passing this as the mark class requests a newly allocated class.
+pub const AUTO_MARK_CLASS: u16 = u16::MAX;
+
+/// A builder for [SinglePos] (single adjustment) subtables.
+///
+/// Value record fields that are zero in every record are omitted, and the
+/// remaining fields are written for all glyphs. If all glyphs end up with
+/// the same adjustment, a format 1 subtable is built, and otherwise a
+/// format 2 subtable.
+#[derive(Clone, Debug, Default)]
+pub struct SingleAdjustBuilder {
+    items: BTreeMap<GlyphId16, ValueRecord>,
+}
+
+impl SingleAdjustBuilder {
+    /// Create a builder that applies the same adjustment to every glyph in
+    /// `coverage`.
+    pub fn from_coverage(coverage: &CoverageTable, record: ValueRecord) -> Self {
+        let mut builder = Self::default();
+        builder.insert_all(coverage.iter(), record);
+        builder
+    }
+
+    /// Add an adjustment for a glyph, replacing any existing adjustment.
+    pub fn insert(&mut self, glyph: GlyphId16, record: ValueRecord) {
+        self.items.insert(glyph, record);
+    }
+
+    /// Add the same adjustment for each of `glyphs`.
+    pub fn insert_all(&mut self, glyphs: impl IntoIterator<Item = GlyphId16>, record: ValueRecord) {
+        for glyph in glyphs {
+            self.insert(glyph, record.clone());
+        }
+    }
+
+    /// Returns `true` if no adjustments have been added.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Construct the [SinglePos] subtable.
+    pub fn build(&self) -> SinglePos {
+        let records = self
+            .items
+            .values()
+            .cloned()
+            .map(without_zero_values)
+            .collect::<Vec<_>>();
+        let format = records
+            .iter()
+            .fold(ValueFormat::empty(), |format, rec| format | rec.format());
+        let mut records = records
+            .into_iter()
+            .map(|rec| rec.with_explicit_value_format(format))
+            .collect::<Vec<_>>();
+        let coverage = self.items.keys().copied().collect::<CoverageTableBuilder>();
+        if records.windows(2).all(|pair| pair[0] == pair[1]) {
+            let record = records
+                .pop()
+                .unwrap_or_else(|| ValueRecord::new().with_explicit_value_format(format));
+            SinglePos::format_1(coverage.build(), record)
+        } else {
+            SinglePos::format_2(coverage.build(), records)
+        }
+    }
+}
+
+impl FromIterator<(GlyphId16, ValueRecord)> for SingleAdjustBuilder {
+    fn from_iter<T: IntoIterator<Item = (GlyphId16, ValueRecord)>>(iter: T) -> Self {
+        SingleAdjustBuilder {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Remove the values of a record that are zero, so they don't contribute
+/// to its format.
+fn without_zero_values(mut record: ValueRecord) -> ValueRecord {
+    for value in [
+        &mut record.x_placement,
+        &mut record.y_placement,
+        &mut record.x_advance,
+        &mut record.y_advance,
+    ] {
+        if *value == Some(0) {
+            *value = None;
+        }
+    }
+    record
+}
+
+/// The marks of a mark attachment subtable, along with their classes.
+#[derive(Clone, Debug, Default)]
+struct MarkList {
+    marks: BTreeMap<GlyphId16, (u16, AnchorTable)>,
+    // one past the largest class seen so far
+    next_class: u16,
+}
+
+impl MarkList {
+    fn insert(&mut self, glyph: GlyphId16, class: u16, anchor: AnchorTable) -> u16 {
+        let class = if class == AUTO_MARK_CLASS {
+            self.next_class
+        } else {
+            class
+        };
+        self.next_class = self.next_class.max(class + 1);
+        self.marks.insert(glyph, (class, anchor));
+        class
+    }
+
+    /// The classes in use, in ascending order.
+    ///
+    /// The position of a class in this list is its class id in the compiled
+    /// subtable, which must have no gaps.
+    fn used_classes(&self) -> Vec<u16> {
+        let mut classes = self
+            .marks
+            .values()
+            .map(|(class, _)| *class)
+            .collect::<Vec<_>>();
+        classes.sort_unstable();
+        classes.dedup();
+        classes
+    }
+
+    fn build(&self, used_classes: &[u16]) -> (CoverageTableBuilder, MarkArray) {
+        let coverage = self.marks.keys().copied().collect();
+        let records = self
+            .marks
+            .values()
+            .map(|(class, anchor)| {
+                let class = used_classes.binary_search(class).unwrap() as u16;
+                MarkRecord::new(class, anchor.clone())
+            })
+            .collect();
+        (coverage, MarkArray::new(records))
+    }
+}
+
+/// Select the anchors for the used classes, in compiled class order.
+fn anchors_for_classes(
+    anchors_by_class: &[Option<AnchorTable>],
+    used_classes: &[u16],
+) -> Vec<Option<AnchorTable>> {
+    used_classes
+        .iter()
+        .map(|class| anchors_by_class.get(*class as usize).cloned().flatten())
+        .collect()
+}
+
+/// A builder for [MarkBasePosFormat1] subtables.
+///
+/// Mark classes are compacted when building, so classes that are not used
+/// by any mark are removed, along with the corresponding base anchors.
+#[derive(Clone, Debug, Default)]
+pub struct MarkToBaseBuilder {
+    marks: MarkList,
+    bases: BTreeMap<GlyphId16, Vec<Option<AnchorTable>>>,
+}
+
+impl MarkToBaseBuilder {
+    /// Add a mark glyph with the given class and anchor.
+    ///
+    /// If `mark_class` is [`AUTO_MARK_CLASS`], the mark is given a new class.
+    ///
+    /// Returns the class of the mark, which is the index of its anchor in
+    /// the `anchors_by_class` of the bases.
+    pub fn add_mark(&mut self, mark_glyph: GlyphId16, mark_class: u16, anchor: AnchorTable) -> u16 {
+        self.marks.insert(mark_glyph, mark_class, anchor)
+    }
+
+    /// Add a base glyph, with its anchors indexed by mark class.
+    ///
+    /// Classes without an anchor can be `None`, or omitted at the end.
+    pub fn add_base(&mut self, base_glyph: GlyphId16, anchors_by_class: Vec<Option<AnchorTable>>) {
+        self.bases.insert(base_glyph, anchors_by_class);
+    }
+
+    /// Returns `true` if no marks or bases have been added.
+    pub fn is_empty(&self) -> bool {
+        self.marks.marks.is_empty() && self.bases.is_empty()
+    }
+
+    /// Construct the [MarkBasePosFormat1] subtable.
+    pub fn build(&self) -> MarkBasePosFormat1 {
+        let used_classes = self.marks.used_classes();
+        let (mark_coverage, mark_array) = self.marks.build(&used_classes);
+        let base_coverage = self.bases.keys().copied().collect::<CoverageTableBuilder>();
+        let base_records = self
+            .bases
+            .values()
+            .map(|anchors| BaseRecord::new(anchors_for_classes(anchors, &used_classes)))
+            .collect();
+        MarkBasePosFormat1::new(
+            mark_coverage.build(),
+            base_coverage.build(),
+            mark_array,
+            BaseArray::new(base_records),
+        )
+    }
+}
+
+/// A builder for [MarkMarkPosFormat1] subtables.
+///
+/// This attaches marks (mark1) to preceding marks (mark2), for instance to
+/// stack diacritics. As with [`MarkToBaseBuilder`], mark classes are compacted
+/// when building.
+#[derive(Clone, Debug, Default)]
+pub struct MarkToMarkBuilder {
+    marks: MarkList,
+    base_marks: BTreeMap<GlyphId16, Vec<Option<AnchorTable>>>,
+}
+
+impl MarkToMarkBuilder {
+    /// Add an attaching mark glyph with the given class and anchor.
+    ///
+    /// If `mark_class` is [`AUTO_MARK_CLASS`], the mark is given a new class.
+    ///
+    /// Returns the class of the mark, which is the index of its anchor in
+    /// the `anchors_by_class` of the base marks.
+    pub fn add_mark(&mut self, mark_glyph: GlyphId16, mark_class: u16, anchor: AnchorTable) -> u16 {
+        self.marks.insert(mark_glyph, mark_class, anchor)
+    }
+
+    /// Add a mark glyph that other marks attach to, with its anchors indexed
+    /// by the class of the attaching mark.
+    pub fn add_base_mark(
+        &mut self,
+        base_mark_glyph: GlyphId16,
+        anchors_by_class: Vec<Option<AnchorTable>>,
+    ) {
+        self.base_marks.insert(base_mark_glyph, anchors_by_class);
+    }
+
+    /// Returns `true` if no marks have been added.
+    pub fn is_empty(&self) -> bool {
+        self.marks.marks.is_empty() && self.base_marks.is_empty()
+    }
+
+    /// Construct the [MarkMarkPosFormat1] subtable.
+    pub fn build(&self) -> MarkMarkPosFormat1 {
+        let used_classes = self.marks.used_classes();
+        let (mark1_coverage, mark1_array) = self.marks.build(&used_classes);
+        let mark2_coverage = self
+            .base_marks
+            .keys()
+            .copied()
+            .collect::<CoverageTableBuilder>();
+        let mark2_records = self
+            .base_marks
+            .values()
+            .map(|anchors| Mark2Record::new(anchors_for_classes(anchors, &used_classes)))
+            .collect();
+        MarkMarkPosFormat1::new(
+            mark1_coverage.build(),
+            mark2_coverage.build(),
+            mark1_array,
+            Mark2Array::new(mark2_records),
+        )
+    }
+}
+
+/// A builder for [MarkLigPosFormat1] subtables.
+///
+/// This attaches marks to the individual components of ligature glyphs. As
+/// with [`MarkToBaseBuilder`], mark classes are compacted when building.
+#[derive(Clone, Debug, Default)]
+pub struct MarkToLigatureBuilder {
+    marks: MarkList,
+    ligatures: BTreeMap<GlyphId16, Vec<Vec<Option<AnchorTable>>>>,
+}
+
+impl MarkToLigatureBuilder {
+    /// Add a mark glyph with the given class and anchor.
+    ///
+    /// If `mark_class` is [`AUTO_MARK_CLASS`], the mark is given a new class.
+    ///
+    /// Returns the class of the mark, which is the index of its anchor in
+    /// the anchors of each ligature component.
+    pub fn add_mark(&mut self, mark_glyph: GlyphId16, mark_class: u16, anchor: AnchorTable) -> u16 {
+        self.marks.insert(mark_glyph, mark_class, anchor)
+    }
+
+    /// Add a ligature glyph, with the anchors of each of its components
+    /// indexed by mark class.
+    ///
+    /// Components are in logical order, and classes without an anchor can be
+    /// `None`, or omitted at the end.
+    pub fn add_ligature(
+        &mut self,
+        ligature_glyph: GlyphId16,
+        component_anchors: Vec<Vec<Option<AnchorTable>>>,
+    ) {
+        self.ligatures.insert(ligature_glyph, component_anchors);
+    }
+
+    /// Returns `true` if no marks or ligatures have been added.
+    pub fn is_empty(&self) -> bool {
+        self.marks.marks.is_empty() && self.ligatures.is_empty()
+    }
+
+    /// Construct the [MarkLigPosFormat1] subtable.
+    pub fn build(&self) -> MarkLigPosFormat1 {
+        let used_classes = self.marks.used_classes();
+        let (mark_coverage, mark_array) = self.marks.build(&used_classes);
+        let ligature_coverage = self
+            .ligatures
+            .keys()
+            .copied()
+            .collect::<CoverageTableBuilder>();
+        let ligature_attaches = self
+            .ligatures
+            .values()
+            .map(|components| {
+                LigatureAttach::new(
+                    components
+                        .iter()
+                        .map(|anchors| {
+                            ComponentRecord::new(anchors_for_classes(anchors, &used_classes))
+                        })
+                        .collect(),
+                )
+            })
+            .collect();
+        MarkLigPosFormat1::new(
+            mark_coverage.build(),
+            ligature_coverage.build(),
+            mark_array,
+            LigatureArray::new(ligature_attaches),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use read_fonts::{tables::gpos as read_gpos, FontData, FontRead};
+
+    fn gid(id: u16) -> GlyphId16 {
+        GlyphId16::new(id)
+    }
+
+    #[test]
+    fn single_adjust_format_1() {
+        let mut builder = SingleAdjustBuilder::default();
+        let record = ValueRecord::new().with_x_advance(50).with_x_placement(0);
+        builder.insert_all([gid(3), gid(1), gid(2)], record.clone());
+        builder.insert(gid(7), record);
+        let table = builder.build();
+        let SinglePos::Format1(table) = &table else {
+            panic!("expected format 1");
+        };
+        // the zero placement is dropped
+        assert_eq!(table.value_record.format(), ValueFormat::X_ADVANCE);
+
+        let bytes = crate::dump_table(table).unwrap();
+        let table = read_gpos::SinglePos::read(FontData::new(&bytes)).unwrap();
+        for glyph in [1, 2, 3, 7] {
+            let record = table.value_record_for(gid(glyph).into()).unwrap();
+            assert_eq!(record.x_advance(), Some(50));
+            assert_eq!(record.x_placement(), None);
+        }
+        assert!(table.value_record_for(gid(4).into()).is_none());
+    }
+
+    #[test]
+    fn single_adjust_format_2() {
+        let mut builder = SingleAdjustBuilder::default();
+        builder.insert(gid(10), ValueRecord::new().with_x_advance(20));
+        builder.insert(gid(5), ValueRecord::new().with_x_placement(-5));
+        builder.insert(
+            gid(8),
+            ValueRecord::new().with_x_advance(20).with_y_advance(0),
+        );
+        let table = builder.build();
+        let SinglePos::Format2(table) = &table else {
+            panic!("expected format 2");
+        };
+        assert!(table
+            .value_records
+            .iter()
+            .all(|rec| rec.format() == ValueFormat::X_PLACEMENT | ValueFormat::X_ADVANCE));
+
+        let bytes = crate::dump_table(table).unwrap();
+        let table = read_gpos::SinglePos::read(FontData::new(&bytes)).unwrap();
+        let values = [5, 8, 10].map(|glyph| {
+            let record = table.value_record_for(gid(glyph).into()).unwrap();
+            (record.x_placement(), record.x_advance(), record.y_advance())
+        });
+        assert_eq!(
+            values,
+            [
+                (Some(-5), Some(0), None),
+                (Some(0), Some(20), None),
+                (Some(0), Some(20), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn single_adjust_from_coverage() {
+        let coverage = [gid(4), gid(2), gid(9)]
+            .into_iter()
+            .collect::<CoverageTable>();
+        let record = ValueRecord::new().with_y_placement(-30);
+        let builder = SingleAdjustBuilder::from_coverage(&coverage, record);
+        let SinglePos::Format1(table) = builder.build() else {
+            panic!("expected format 1");
+        };
+        assert_eq!(
+            table.coverage.iter().collect::<Vec<_>>(),
+            [gid(2), gid(4), gid(9)]
+        );
+        assert_eq!(table.value_record.y_placement, Some(-30));
+        assert_eq!(table.value_record.format(), ValueFormat::Y_PLACEMENT);
+    }
+
+    #[test]
+    fn single_adjust_from_iter() {
+        let builder = [
+            (gid(3), ValueRecord::new().with_x_advance(10)),
+            (gid(1), ValueRecord::new().with_x_advance(20)),
+            (gid(3), ValueRecord::new().with_x_advance(30)),
+        ]
+        .into_iter()
+        .collect::<SingleAdjustBuilder>();
+        let SinglePos::Format2(table) = builder.build() else {
+            panic!("expected format 2");
+        };
+        assert_eq!(table.coverage.iter().collect::<Vec<_>>(), [gid(1), gid(3)]);
+        // later records replace earlier ones, as with `insert`
+        let advances = table
+            .value_records
+            .iter()
+            .map(|rec| rec.x_advance)
+            .collect::<Vec<_>>();
+        assert_eq!(advances, [Some(20), Some(30)]);
+    }
+
+    #[test]
+    fn mark_to_base() {
+        let mut builder = MarkToBaseBuilder::default();
+        let top = builder.add_mark(gid(10), AUTO_MARK_CLASS, AnchorTable::format_1(100, 500));
+        let bottom = builder.add_mark(gid(11), AUTO_MARK_CLASS, AnchorTable::format_1(100, 0));
+        assert_eq!((top, bottom), (0, 1));
+        assert_eq!(
+            builder.add_mark(gid(12), top, AnchorTable::format_1(120, 500)),
+            top
+        );
+        builder.add_base(gid(2), vec![Some(AnchorTable::format_1(250, 700))]);
+        builder.add_base(
+            gid(1),
+            vec![
+                Some(AnchorTable::format_1(200, 700)),
+                Some(AnchorTable::format_1(200, -10)),
+            ],
+        );
+
+        let bytes = crate::dump_table(&builder.build()).unwrap();
+        let table = read_gpos::MarkBasePosFormat1::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(table.mark_class_count(), 2);
+        let mark_coverage = table.mark_coverage().unwrap();
+        assert_eq!(
+            mark_coverage.iter().map(|g| g.to_u16()).collect::<Vec<_>>(),
+            [10, 11, 12]
+        );
+        let mark_array = table.mark_array().unwrap();
+        let classes = mark_array
+            .mark_records()
+            .iter()
+            .map(|rec| rec.mark_class())
+            .collect::<Vec<_>>();
+        assert_eq!(classes, [0, 1, 0]);
+
+        let base_array = table.base_array().unwrap();
+        let records = base_array.base_records();
+        assert_eq!(records.len(), 2);
+        // bases are in coverage order, and padded to the class count
+        let gid2 = records.get(1).unwrap();
+        let anchors = gid2.base_anchors(base_array.offset_data());
+        assert_eq!(anchors.len(), 2);
+        assert!(anchors.get(0).unwrap().is_ok());
+        assert!(anchors.get(1).is_none());
+    }
+
+    #[test]
+    fn mark_to_base_compacts_classes() {
+        let mut builder = MarkToBaseBuilder::default();
+        builder.add_mark(gid(10), 3, AnchorTable::format_1(0, 0));
+        builder.add_base(
+            gid(1),
+            vec![None, None, None, Some(AnchorTable::format_1(5, 5))],
+        );
+        let table = builder.build();
+        assert_eq!(table.mark_array.mark_records[0].mark_class, 0);
+        assert_eq!(table.base_array.base_records[0].base_anchors.len(), 1);
+        assert!(table.base_array.base_records[0].base_anchors[0]
+            .as_ref()
+            .is_some());
+    }
+
+    #[test]
+    fn mark_to_mark() {
+        let mut builder = MarkToMarkBuilder::default();
+        let above = builder.add_mark(gid(20), 5, AnchorTable::format_1(0, 400));
+        let below = builder.add_mark(gid(21), AUTO_MARK_CLASS, AnchorTable::format_1(0, -50));
+        assert_eq!((above, below), (5, 6));
+        // a mark can both attach and be attached to
+        builder.add_base_mark(gid(20), {
+            let mut anchors = vec![None; 5];
+            anchors.push(Some(AnchorTable::format_1(0, 700)));
+            anchors
+        });
+
+        let bytes = crate::dump_table(&builder.build()).unwrap();
+        let table = read_gpos::MarkMarkPosFormat1::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(table.mark_class_count(), 2);
+        let mark1_array = table.mark1_array().unwrap();
+        let classes = mark1_array
+            .mark_records()
+            .iter()
+            .map(|rec| rec.mark_class())
+            .collect::<Vec<_>>();
+        assert_eq!(classes, [0, 1]);
+        assert_eq!(
+            table
+                .mark2_coverage()
+                .unwrap()
+                .iter()
+                .map(|g| g.to_u16())
+                .collect::<Vec<_>>(),
+            [20]
+        );
+        let mark2_array = table.mark2_array().unwrap();
+        let record = mark2_array.mark2_records().get(0).unwrap();
+        let anchors = record.mark2_anchors(mark2_array.offset_data());
+        assert_eq!(anchors.len(), 2);
+        let read_gpos::AnchorTable::Format1(anchor) = anchors.get(0).unwrap().unwrap() else {
+            panic!("wrong anchor format");
+        };
+        assert_eq!(anchor.y_coordinate(), 700);
+        assert!(anchors.get(1).is_none());
+    }
+
+    #[test]
+    fn mark_to_ligature() {
+        let mut builder = MarkToLigatureBuilder::default();
+        let fatha = builder.add_mark(gid(30), 2, AnchorTable::format_1(0, 300));
+        let kasra = builder.add_mark(gid(31), AUTO_MARK_CLASS, AnchorTable::format_1(0, -100));
+        assert_eq!((fatha, kasra), (2, 3));
+        // lam-alef: the second component has no anchor below
+        builder.add_ligature(
+            gid(5),
+            vec![
+                vec![
+                    None,
+                    None,
+                    Some(AnchorTable::format_1(700, 800)),
+                    Some(AnchorTable::format_1(650, -50)),
+                ],
+                vec![None, None, Some(AnchorTable::format_1(200, 900))],
+            ],
+        );
+        builder.add_ligature(gid(4), vec![vec![]]);
+
+        let bytes = crate::dump_table(&builder.build()).unwrap();
+        let table = read_gpos::MarkLigPosFormat1::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(table.mark_class_count(), 2);
+        let mark_array = table.mark_array().unwrap();
+        let classes = mark_array
+            .mark_records()
+            .iter()
+            .map(|rec| rec.mark_class())
+            .collect::<Vec<_>>();
+        assert_eq!(classes, [0, 1]);
+        assert_eq!(
+            table
+                .ligature_coverage()
+                .unwrap()
+                .iter()
+                .map(|g| g.to_u16())
+                .collect::<Vec<_>>(),
+            [4, 5]
+        );
+
+        let ligature_array = table.ligature_array().unwrap();
+        let attaches = ligature_array.ligature_attaches();
+        assert_eq!(attaches.len(), 2);
+        let empty = attaches.get(0).unwrap();
+        assert_eq!(empty.component_count(), 1);
+        let lam_alef = attaches.get(1).unwrap();
+        assert_eq!(lam_alef.component_count(), 2);
+        let components = lam_alef.component_records();
+        let anchor_ys = (0..2)
+            .map(|i| {
+                let anchors = components
+                    .get(i)
+                    .unwrap()
+                    .ligature_anchors(lam_alef.offset_data());
+                (0..2)
+                    .map(|class| {
+                        anchors.get(class).map(|anchor| match anchor.unwrap() {
+                            read_gpos::AnchorTable::Format1(anchor) => anchor.y_coordinate(),
+                            _ => panic!("wrong anchor format"),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            anchor_ys,
+            [vec![Some(800), Some(-50)], vec![Some(900), None]]
+        );
+    }
+}
@@ -6,7 +6,7 @@ include!("../../generated/generated_gsub.rs");
 
 use super::layout::{
     ChainedSequenceContext, CoverageTable, FeatureList, FeatureVariations, Lookup, LookupList,
-    LookupSubtable, LookupType, ScriptList, SequenceContext,
+    LookupSubtable, LookupType, ScriptList, SequenceContext, SequenceLookupRecord,
 };
 
 #[cfg(test)]
@@ -47,6 +47,70 @@ super::layout::lookup_type!(gsub, SubstitutionChainContext, 6);
 super::layout::lookup_type!(gsub, ExtensionSubtable, 7);
 super::layout::lookup_type!(gsub, ReverseChainSingleSubstFormat1, 8);
 
+/// A builder for format 3 (coverage-based) [SubstitutionChainContext] subtables.
+///
+/// As in the compiled subtable, backtrack coverages start with the glyph
+/// closest to the input sequence.
+#[derive(Clone, Debug, Default)]
+pub struct ChainedContextSubstF3Builder {
+    backtrack_coverages: Vec<CoverageTable>,
+    input_coverages: Vec<CoverageTable>,
+    lookahead_coverages: Vec<CoverageTable>,
+    lookup_records: Vec<SequenceLookupRecord>,
+}
+
+impl ChainedContextSubstF3Builder {
+    /// Set the coverages of the glyphs before the input sequence.
+    pub fn set_backtrack_coverages(&mut self, coverages: Vec<CoverageTable>) {
+        self.backtrack_coverages = coverages;
+    }
+
+    /// Set the coverages of the input sequence.
+    pub fn set_input_coverages(&mut self, coverages: Vec<CoverageTable>) {
+        self.input_coverages = coverages;
+    }
+
+    /// Set the coverages of the glyphs after the input sequence.
+    pub fn set_lookahead_coverages(&mut self, coverages: Vec<CoverageTable>) {
+        self.lookahead_coverages = coverages;
+    }
+
+    /// Apply the lookup at `lookup_list_index` at a position in the input
+    /// sequence.
+    ///
+    /// Lookups are applied in the order they are added.
+    pub fn add_lookup_record(&mut self, input_seq_index: u16, lookup_list_index: u16) {
+        self.lookup_records.push(SequenceLookupRecord::new(
+            input_seq_index,
+            lookup_list_index,
+        ));
+    }
+
+    /// Construct the [SubstitutionChainContext] subtable.
+    pub fn build_table(&self) -> SubstitutionChainContext {
+        ChainedSequenceContext::format_3(
+            self.backtrack_coverages.clone(),
+            self.input_coverages.clone(),
+            self.lookahead_coverages.clone(),
+            self.lookup_records.clone(),
+        )
+        .into()
+    }
+
+    /// Construct the [SubstitutionChainContext] subtable and serialize it.
+    pub fn build(&self) -> Result<Vec<u8>, crate::error::Error> {
+        crate::dump_table(&self.build_table())
+    }
+}
+
+impl SingleSubstFormat2 {
+    fn check_substitute_count(&self, ctx: &mut ValidationCtx) {
+        if self.substitute_glyph_ids.len() != self.coverage.len() {
+            ctx.report("substitute count must match coverage glyph count");
+        }
+    }
+}
+
 impl<T: LookupSubtable + FontWrite> FontWrite for ExtensionSubstFormat1<T> {
     fn write_into(&self, writer: &mut TableWriter) {
         1u16.write_into(writer);
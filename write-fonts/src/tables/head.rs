@@ -1,3 +1,140 @@
 //! The head table
 
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::loca::LocaFormat;
+
 include!("../../generated/generated_head.rs");
+
+/// The number of seconds between the font epoch (1904-01-01) and the unix
+/// epoch (1970-01-01).
+const SECONDS_FROM_1904_TO_1970: i64 = 2_082_844_800;
+
+/// Returns the current time, as a [`LongDateTime`].
+fn now() -> LongDateTime {
+    let unix_secs = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    LongDateTime::new(unix_secs + SECONDS_FROM_1904_TO_1970)
+}
+
+/// A builder for the [`Head`] table.
+///
+/// The `created` and `modified` timestamps are initialized to the current
+/// time, and the magic number is always set. The `checksum_adjustment` is
+/// left as zero; it is computed by [`FontBuilder`](crate::FontBuilder) once
+/// the whole font has been assembled.
+#[derive(Clone, Debug)]
+pub struct HeadBuilder {
+    head: Head,
+}
+
+impl Default for HeadBuilder {
+    fn default() -> Self {
+        let now = now();
+        HeadBuilder {
+            head: Head {
+                created: now,
+                modified: now,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl HeadBuilder {
+    /// Create a builder with the given units per em.
+    pub fn new(units_per_em: u16) -> Self {
+        Self::default().with_units_per_em(units_per_em)
+    }
+
+    /// Set the font revision.
+    pub fn with_font_revision(mut self, font_revision: Fixed) -> Self {
+        self.head.font_revision = font_revision;
+        self
+    }
+
+    /// Set the `flags` field.
+    pub fn with_flags(mut self, flags: u16) -> Self {
+        self.head.flags = flags;
+        self
+    }
+
+    /// Set the units per em.
+    pub fn with_units_per_em(mut self, units_per_em: u16) -> Self {
+        self.head.units_per_em = units_per_em;
+        self
+    }
+
+    /// Set the creation time, overriding the default of the current time.
+    pub fn with_created(mut self, created: LongDateTime) -> Self {
+        self.head.created = created;
+        self
+    }
+
+    /// Set the modification time, overriding the default of the current time.
+    pub fn with_modified(mut self, modified: LongDateTime) -> Self {
+        self.head.modified = modified;
+        self
+    }
+
+    /// Set the bounding box of all glyphs, as `(x_min, y_min, x_max, y_max)`.
+    pub fn with_bbox(mut self, x_min: i16, y_min: i16, x_max: i16, y_max: i16) -> Self {
+        self.head.x_min = x_min;
+        self.head.y_min = y_min;
+        self.head.x_max = x_max;
+        self.head.y_max = y_max;
+        self
+    }
+
+    /// Set the mac style.
+    pub fn with_mac_style(mut self, mac_style: MacStyle) -> Self {
+        self.head.mac_style = mac_style;
+        self
+    }
+
+    /// Set the smallest readable size in pixels.
+    pub fn with_lowest_rec_ppem(mut self, lowest_rec_ppem: u16) -> Self {
+        self.head.lowest_rec_ppem = lowest_rec_ppem;
+        self
+    }
+
+    /// Set the format of the `loca` table.
+    pub fn with_index_to_loc_format(mut self, format: LocaFormat) -> Self {
+        self.head.index_to_loc_format = format as i16;
+        self
+    }
+
+    /// Construct the [`Head`] table.
+    pub fn build(self) -> Head {
+        self.head
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder() {
+        let head = HeadBuilder::new(2048)
+            .with_bbox(-10, -200, 1000, 900)
+            .with_mac_style(MacStyle::BOLD)
+            .with_lowest_rec_ppem(8)
+            .with_index_to_loc_format(LocaFormat::Long)
+            .build();
+        let dumped = crate::dump_table(&head).unwrap();
+        let loaded = read_fonts::tables::head::Head::read(FontData::new(&dumped)).unwrap();
+        assert_eq!(loaded.magic_number(), 0x5F0F3CF5);
+        assert_eq!(loaded.checksum_adjustment(), 0);
+        assert_eq!(loaded.units_per_em(), 2048);
+        assert_eq!(loaded.y_min(), -200);
+        assert_eq!(loaded.mac_style(), MacStyle::BOLD);
+        assert_eq!(loaded.lowest_rec_ppem(), 8);
+        assert_eq!(loaded.index_to_loc_format(), 1);
+        // some time after 2024-01-01
+        assert!(loaded.modified().as_secs() > 3_786_825_600);
+        assert_eq!(loaded.created(), loaded.modified());
+    }
+}
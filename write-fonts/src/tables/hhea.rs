@@ -1,3 +1,166 @@
 //! the [hhea (Horizontal Header)](https://docs.microsoft.com/en-us/typography/opentype/spec/hhea) table
 
+use super::glyf::Bbox;
+
 include!("../../generated/generated_hhea.rs");
+
+/// A builder for the [`Hhea`] table.
+///
+/// The metrics that summarize the `hmtx` table are computed by
+/// [`auto_compute`](Self::auto_compute); the line metrics and caret fields
+/// are set by the caller.
+#[derive(Clone, Debug)]
+pub struct HheaBuilder {
+    hhea: Hhea,
+}
+
+impl Default for HheaBuilder {
+    fn default() -> Self {
+        HheaBuilder {
+            hhea: Hhea {
+                // a vertical caret
+                caret_slope_rise: 1,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl HheaBuilder {
+    /// Create a builder with metrics computed from the glyphs of a font.
+    ///
+    /// Each item is the advance width and bounding box of a glyph, with `None`
+    /// for glyphs without contours. The left side bearing of each glyph is
+    /// taken to be the `x_min` of its bounding box, as it is in fonts with
+    /// TrueType outlines.
+    ///
+    /// This sets `advance_width_max` from all glyphs, and `min_left_side_bearing`,
+    /// `min_right_side_bearing` and `x_max_extent` from the glyphs that have
+    /// contours. `number_of_long_metrics` is set to the number of glyphs.
+    pub fn auto_compute(metrics: &[(u16, Option<Bbox>)]) -> Self {
+        let mut builder = Self::default();
+        let hhea = &mut builder.hhea;
+        hhea.number_of_long_metrics = metrics.len().try_into().unwrap_or(u16::MAX);
+        hhea.advance_width_max = metrics
+            .iter()
+            .map(|(advance, _)| *advance)
+            .max()
+            .unwrap_or_default()
+            .into();
+
+        let mut min_lsb = i16::MAX;
+        let mut min_rsb = i32::MAX;
+        let mut max_extent = i32::MIN;
+        for (advance, bbox) in metrics {
+            let Some(bbox) = bbox else {
+                continue;
+            };
+            let lsb = bbox.x_min as i32;
+            let extent = lsb + (bbox.x_max as i32 - bbox.x_min as i32);
+            min_lsb = min_lsb.min(bbox.x_min);
+            min_rsb = min_rsb.min(*advance as i32 - extent);
+            max_extent = max_extent.max(extent);
+        }
+        if max_extent != i32::MIN {
+            hhea.min_left_side_bearing = min_lsb.into();
+            hhea.min_right_side_bearing = clamp_to_fword(min_rsb);
+            hhea.x_max_extent = clamp_to_fword(max_extent);
+        }
+        builder
+    }
+
+    /// Set the typographic ascender.
+    pub fn with_ascender(mut self, ascender: i16) -> Self {
+        self.hhea.ascender = ascender.into();
+        self
+    }
+
+    /// Set the typographic descender.
+    pub fn with_descender(mut self, descender: i16) -> Self {
+        self.hhea.descender = descender.into();
+        self
+    }
+
+    /// Set the typographic line gap.
+    pub fn with_line_gap(mut self, line_gap: i16) -> Self {
+        self.hhea.line_gap = line_gap.into();
+        self
+    }
+
+    /// Set the slope of the caret, as `rise / run`.
+    ///
+    /// The default is a vertical caret (`1 / 0`).
+    pub fn with_caret_slope(mut self, rise: i16, run: i16) -> Self {
+        self.hhea.caret_slope_rise = rise;
+        self.hhea.caret_slope_run = run;
+        self
+    }
+
+    /// Set the caret offset, for slanted fonts.
+    pub fn with_caret_offset(mut self, caret_offset: i16) -> Self {
+        self.hhea.caret_offset = caret_offset;
+        self
+    }
+
+    /// Set the number of long metrics in the `hmtx` table.
+    ///
+    /// This is needed if the `hmtx` table omits the advances of trailing
+    /// glyphs that have the same advance.
+    pub fn with_number_of_long_metrics(mut self, number_of_long_metrics: u16) -> Self {
+        self.hhea.number_of_long_metrics = number_of_long_metrics;
+        self
+    }
+
+    /// Construct the [`Hhea`] table.
+    pub fn build(self) -> Hhea {
+        self.hhea
+    }
+}
+
+fn clamp_to_fword(value: i32) -> FWord {
+    (value.clamp(i16::MIN as i32, i16::MAX as i32) as i16).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_compute() {
+        let bbox = |x_min, x_max| {
+            Some(Bbox {
+                x_min,
+                y_min: 0,
+                x_max,
+                y_max: 700,
+            })
+        };
+        let hhea = HheaBuilder::auto_compute(&[
+            (500, bbox(50, 450)),
+            // empty glyphs only contribute their advance
+            (1200, None),
+            (600, bbox(-20, 610)),
+            (400, bbox(10, 300)),
+        ])
+        .with_ascender(800)
+        .with_descender(-200)
+        .build();
+        assert_eq!(hhea.advance_width_max, UfWord::new(1200));
+        assert_eq!(hhea.min_left_side_bearing, FWord::new(-20));
+        assert_eq!(hhea.min_right_side_bearing, FWord::new(-10));
+        assert_eq!(hhea.x_max_extent, FWord::new(610));
+        assert_eq!(hhea.ascender, FWord::new(800));
+        assert_eq!(hhea.descender, FWord::new(-200));
+        assert_eq!(hhea.caret_slope_rise, 1);
+        assert_eq!(hhea.number_of_long_metrics, 4);
+    }
+
+    #[test]
+    fn auto_compute_without_contours() {
+        let hhea = HheaBuilder::auto_compute(&[(0, None), (250, None)]).build();
+        assert_eq!(hhea.advance_width_max, UfWord::new(250));
+        assert_eq!(hhea.min_left_side_bearing, FWord::new(0));
+        assert_eq!(hhea.min_right_side_bearing, FWord::new(0));
+        assert_eq!(hhea.x_max_extent, FWord::new(0));
+    }
+}
@@ -107,6 +107,122 @@ impl FontWrite for LookupFlag {
     }
 }
 
+/// Lookup flags, together with the mark filtering set they may refer to.
+///
+/// Setting a mark filtering set also sets [`LookupFlag::USE_MARK_FILTERING_SET`],
+/// so that the flag and the `markFilteringSet` field of the lookup stay in sync.
+///
+/// ```
+/// # use write_fonts::tables::layout::LookupFlags;
+/// let flags = LookupFlags::new().ignore_ligatures().with_mark_filtering_set(2);
+/// assert_eq!(flags.to_bits(), 0x0014);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LookupFlags {
+    flags: LookupFlag,
+    mark_filtering_set: Option<u16>,
+}
+
+impl LookupFlags {
+    /// Return new, empty flags
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set [`LookupFlag::RIGHT_TO_LEFT`].
+    pub fn right_to_left(mut self) -> Self {
+        self.flags |= LookupFlag::RIGHT_TO_LEFT;
+        self
+    }
+
+    /// Set [`LookupFlag::IGNORE_BASE_GLYPHS`].
+    pub fn ignore_base_glyphs(mut self) -> Self {
+        self.flags |= LookupFlag::IGNORE_BASE_GLYPHS;
+        self
+    }
+
+    /// Set [`LookupFlag::IGNORE_LIGATURES`].
+    pub fn ignore_ligatures(mut self) -> Self {
+        self.flags |= LookupFlag::IGNORE_LIGATURES;
+        self
+    }
+
+    /// Set [`LookupFlag::IGNORE_MARKS`].
+    pub fn ignore_marks(mut self) -> Self {
+        self.flags |= LookupFlag::IGNORE_MARKS;
+        self
+    }
+
+    /// Skip over all marks with a mark attachment class different from `class`.
+    pub fn with_mark_attachment_class(mut self, class: u8) -> Self {
+        self.flags.set_mark_attachment_class(class as u16);
+        self
+    }
+
+    /// Skip over all marks not in the GDEF mark glyph set at index `set`.
+    pub fn with_mark_filtering_set(mut self, set: u16) -> Self {
+        self.flags |= LookupFlag::USE_MARK_FILTERING_SET;
+        self.mark_filtering_set = Some(set);
+        self
+    }
+
+    /// The raw `lookupFlag` field.
+    pub fn lookup_flag(&self) -> LookupFlag {
+        self.flags
+    }
+
+    /// The index of the mark filtering set, if one is used.
+    pub fn mark_filtering_set(&self) -> Option<u16> {
+        self.mark_filtering_set
+    }
+
+    /// The encoded `lookupFlag` value.
+    pub fn to_bits(&self) -> u16 {
+        self.flags.to_bits()
+    }
+}
+
+/// Converts a raw `lookupFlag` value.
+///
+/// A raw value has no mark filtering set, so [`LookupFlag::USE_MARK_FILTERING_SET`]
+/// is cleared; use [`LookupFlags::with_mark_filtering_set`] to set it.
+impl From<LookupFlag> for LookupFlags {
+    fn from(flags: LookupFlag) -> Self {
+        let bits = flags.to_bits() & !LookupFlag::USE_MARK_FILTERING_SET.to_bits();
+        LookupFlags {
+            flags: LookupFlag::from_bits_truncate(bits),
+            mark_filtering_set: None,
+        }
+    }
+}
+
+impl<T: Default> Lookup<T> {
+    /// Construct a new `Lookup` with the given flags and mark filtering set.
+    pub fn with_flags(flags: LookupFlags, subtables: Vec<T>) -> Self {
+        Self {
+            lookup_flag: flags.flags,
+            subtables: subtables.into_iter().map(Into::into).collect(),
+            mark_filtering_set: flags.mark_filtering_set,
+        }
+    }
+}
+
+impl<T> Lookup<T> {
+    /// The flags of this lookup, including the mark filtering set.
+    pub fn flags(&self) -> LookupFlags {
+        LookupFlags {
+            flags: self.lookup_flag,
+            mark_filtering_set: self.mark_filtering_set,
+        }
+    }
+
+    /// Replace the flags (and mark filtering set) of this lookup.
+    pub fn set_flags(&mut self, flags: LookupFlags) {
+        self.lookup_flag = flags.flags;
+        self.mark_filtering_set = flags.mark_filtering_set;
+    }
+}
+
 impl<T: LookupSubtable + FontWrite> FontWrite for Lookup<T> {
     fn write_into(&self, writer: &mut TableWriter) {
         T::TYPE.write_into(writer);
@@ -668,10 +784,210 @@ impl From<VariationIndex> for u32 {
     }
 }
 
+/// A range of normalized coordinates on a single axis.
+///
+/// This is used to construct [`ConditionFormat1`] tables with the
+/// [`FeatureVariationsBuilder`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AxisCondition {
+    /// The index of the axis in the 'fvar' table.
+    pub axis_index: u16,
+    /// The minimum normalized coordinate, inclusive.
+    pub min: F2Dot14,
+    /// The maximum normalized coordinate, inclusive.
+    pub max: F2Dot14,
+}
+
+impl AxisCondition {
+    pub fn new(axis_index: u16, min: F2Dot14, max: F2Dot14) -> Self {
+        AxisCondition {
+            axis_index,
+            min,
+            max,
+        }
+    }
+}
+
+impl From<AxisCondition> for Condition {
+    fn from(src: AxisCondition) -> Self {
+        Condition::format_1_axis_range(src.axis_index, src.min, src.max)
+    }
+}
+
+/// A builder for [FeatureVariations] tables.
+///
+/// Each record replaces a set of features whenever all of its conditions
+/// are met. Since only the first matching record is applied at runtime, records
+/// are ordered so that those constraining more axes come first.
+#[derive(Clone, Debug, Default)]
+pub struct FeatureVariationsBuilder {
+    records: Vec<VariationRecord>,
+}
+
+#[derive(Clone, Debug)]
+struct VariationRecord {
+    conditions: Vec<AxisCondition>,
+    substitutions: Vec<(u16, Feature)>,
+}
+
+impl FeatureVariationsBuilder {
+    /// Add a record that substitutes the features at the given indices of
+    /// the feature list when all `conditions` are met.
+    pub fn add_record(
+        &mut self,
+        mut conditions: Vec<AxisCondition>,
+        mut feature_substitutions: Vec<(u16, Feature)>,
+    ) {
+        conditions.sort_by_key(|condition| condition.axis_index);
+        feature_substitutions.sort_by_key(|(feature_index, _)| *feature_index);
+        self.records.push(VariationRecord {
+            conditions,
+            substitutions: feature_substitutions,
+        });
+    }
+
+    /// Returns `true` if no records have been added.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Construct the [FeatureVariations] table.
+    pub fn build_table(&self) -> FeatureVariations {
+        let mut records = self.records.iter().collect::<Vec<_>>();
+        // stable, so records with equal specificity keep their insertion order
+        records.sort_by_key(|record| std::cmp::Reverse(record.conditions.len()));
+        FeatureVariations::new(
+            records
+                .into_iter()
+                .map(|record| {
+                    FeatureVariationRecord::new(
+                        Some(ConditionSet::new(
+                            record.conditions.iter().copied().map(Into::into).collect(),
+                        )),
+                        Some(FeatureTableSubstitution::new(
+                            record
+                                .substitutions
+                                .iter()
+                                .map(|(index, feature)| {
+                                    FeatureTableSubstitutionRecord::new(*index, feature.clone())
+                                })
+                                .collect(),
+                        )),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Construct the [FeatureVariations] table and serialize it.
+    pub fn build(&self) -> Result<Vec<u8>, crate::error::Error> {
+        crate::dump_table(&self.build_table())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn lookup_flags_roundtrip() {
+        use crate::tables::gsub::{SingleSubst, SingleSubstFormat1};
+        use read_fonts::tables::{
+            gsub::SingleSubst as ReadSingleSubst, layout::Lookup as ReadLookup,
+        };
+
+        let subtable = SingleSubst::Format1(SingleSubstFormat1::new(
+            CoverageTable::format_1(vec![GlyphId16::new(2)]),
+            1,
+        ));
+        let flags = LookupFlags::new()
+            .right_to_left()
+            .ignore_marks()
+            .with_mark_filtering_set(3);
+        let lookup = Lookup::with_flags(flags, vec![subtable.clone()]);
+        assert_eq!(lookup.flags(), flags);
+
+        let bytes = crate::dump_table(&lookup).unwrap();
+        let read = ReadLookup::<ReadSingleSubst>::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(read.lookup_flag().to_bits(), 0x0019);
+        assert_eq!(read.mark_filtering_set(), Some(3));
+
+        // without a mark filtering set, the field is omitted
+        let flags = LookupFlags::new()
+            .ignore_base_glyphs()
+            .with_mark_attachment_class(2);
+        let lookup = Lookup::with_flags(flags, vec![subtable]);
+        let bytes = crate::dump_table(&lookup).unwrap();
+        let read = ReadLookup::<ReadSingleSubst>::read(FontData::new(&bytes)).unwrap();
+        assert_eq!(read.lookup_flag().to_bits(), 0x0202);
+        assert_eq!(read.lookup_flag().mark_attachment_class(), Some(2));
+        assert_eq!(read.mark_filtering_set(), None);
+        assert_eq!(read.subtable_offsets().len(), 1);
+    }
+
+    #[test]
+    fn lookup_flags_from_raw() {
+        let raw = LookupFlag::IGNORE_MARKS | LookupFlag::USE_MARK_FILTERING_SET;
+        let flags = LookupFlags::from(raw);
+        // there is no set to filter with, so the flag is dropped
+        assert_eq!(flags.to_bits(), 0x0008);
+        assert_eq!(flags.mark_filtering_set(), None);
+        assert_eq!(flags.with_mark_filtering_set(1).to_bits(), 0x0018);
+
+        let mut raw = LookupFlag::RIGHT_TO_LEFT;
+        raw.set_mark_attachment_class(3);
+        assert_eq!(LookupFlags::from(raw).lookup_flag(), raw);
+    }
+
+    #[test]
+    fn feature_variations_builder() {
+        use read_fonts::tables::layout::FeatureVariations as ReadFeatureVariations;
+
+        let half = F2Dot14::from_f32(0.5);
+        let mut builder = FeatureVariationsBuilder::default();
+        builder.add_record(
+            vec![AxisCondition::new(0, half, F2Dot14::ONE)],
+            vec![(0, Feature::new(None, vec![1]))],
+        );
+        builder.add_record(
+            vec![
+                AxisCondition::new(1, half, F2Dot14::ONE),
+                AxisCondition::new(0, half, F2Dot14::ONE),
+            ],
+            vec![
+                (1, Feature::new(None, vec![3])),
+                (0, Feature::new(None, vec![2])),
+            ],
+        );
+
+        let bytes = builder.build().unwrap();
+        let read = ReadFeatureVariations::read(FontData::new(&bytes)).unwrap();
+        // the more specific record comes first
+        let first = &read.feature_variation_records()[0];
+        let conditions = first
+            .condition_set(read.offset_data())
+            .unwrap()
+            .unwrap()
+            .conditions();
+        assert_eq!(conditions.len(), 2);
+
+        let lookups_at = |coords: &[f32]| {
+            let coords = coords
+                .iter()
+                .copied()
+                .map(F2Dot14::from_f32)
+                .collect::<Vec<_>>();
+            read.substitutions_at(&coords)
+                .unwrap()
+                .iter()
+                .map(|(index, feature)| (*index, feature.lookup_list_indices()[0].get()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lookups_at(&[0.75, 0.75]), [(0, 2), (1, 3)]);
+        assert_eq!(lookups_at(&[0.75, 0.0]), [(0, 1)]);
+        assert!(lookups_at(&[0.0, 0.75]).is_empty());
+    }
+
     #[test]
     #[should_panic(expected = "array exceeds max length")]
     fn array_len_smoke_test() {
@@ -2,6 +2,8 @@
 
 include!("../../generated/generated_maxp.rs");
 
+pub use read_fonts::tables::maxp::TrueTypeLimits;
+
 impl Maxp {
     fn compute_version(&self) -> Version16Dot16 {
         if self.max_points.is_some()
@@ -25,6 +27,65 @@ impl Maxp {
     }
 }
 
+/// A builder for the [`Maxp`] table.
+///
+/// The version of the table is determined by the outline format: fonts with
+/// CFF or CFF2 outlines use version 0.5, which only contains the glyph count,
+/// and fonts with TrueType outlines use version 1.0, which also contains
+/// the [`TrueTypeLimits`].
+#[derive(Clone, Debug, Default)]
+pub struct MaxpBuilder {
+    num_glyphs: u16,
+    limits: Option<TrueTypeLimits>,
+}
+
+impl MaxpBuilder {
+    /// Create a builder for a version 0.5 table, for fonts with CFF outlines.
+    pub fn for_cff(num_glyphs: u16) -> Self {
+        MaxpBuilder {
+            num_glyphs,
+            limits: None,
+        }
+    }
+
+    /// Create a builder for a version 1.0 table, for fonts with TrueType outlines.
+    pub fn for_truetype(num_glyphs: u16, limits: TrueTypeLimits) -> Self {
+        MaxpBuilder {
+            num_glyphs,
+            limits: Some(limits),
+        }
+    }
+
+    /// Set the number of glyphs in the font.
+    pub fn with_num_glyphs(mut self, num_glyphs: u16) -> Self {
+        self.num_glyphs = num_glyphs;
+        self
+    }
+
+    /// Construct the [`Maxp`] table.
+    pub fn build(self) -> Maxp {
+        let Some(limits) = self.limits else {
+            return Maxp::new(self.num_glyphs);
+        };
+        Maxp {
+            num_glyphs: self.num_glyphs,
+            max_points: Some(limits.max_points),
+            max_contours: Some(limits.max_contours),
+            max_composite_points: Some(limits.max_composite_points),
+            max_composite_contours: Some(limits.max_composite_contours),
+            max_zones: Some(limits.max_zones),
+            max_twilight_points: Some(limits.max_twilight_points),
+            max_storage: Some(limits.max_storage),
+            max_function_defs: Some(limits.max_function_defs),
+            max_instruction_defs: Some(limits.max_instruction_defs),
+            max_stack_elements: Some(limits.max_stack_elements),
+            max_size_of_instructions: Some(limits.max_size_of_instructions),
+            max_component_elements: Some(limits.max_component_elements),
+            max_component_depth: Some(limits.max_component_depth),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +133,40 @@ mod tests {
         assert_eq!(loaded.max_zones(), Some(10));
         assert_eq!(loaded.max_component_depth(), Some(18));
     }
+
+    #[test]
+    fn builder_cff() {
+        let maxp = MaxpBuilder::for_cff(12).build();
+        let dumped = crate::write::dump_table(&maxp).unwrap();
+        assert_eq!(dumped.len(), 6);
+        let loaded = read_fonts::tables::maxp::Maxp::read(FontData::new(&dumped)).unwrap();
+        assert_eq!(loaded.version(), Version16Dot16::VERSION_0_5);
+        assert_eq!(loaded.num_glyphs(), 12);
+    }
+
+    #[test]
+    fn builder_truetype() {
+        let limits = TrueTypeLimits {
+            max_points: 40,
+            max_contours: 3,
+            max_zones: 2,
+            max_component_depth: 1,
+            ..Default::default()
+        };
+        let maxp = MaxpBuilder::for_truetype(12, limits)
+            .with_num_glyphs(7)
+            .build();
+        let dumped = crate::write::dump_table(&maxp).unwrap();
+        assert_eq!(dumped.len(), 32);
+        let loaded = read_fonts::tables::maxp::Maxp::read(FontData::new(&dumped)).unwrap();
+        assert_eq!(loaded.version(), Version16Dot16::VERSION_1_0);
+        assert_eq!(loaded.num_glyphs(), 7);
+        assert_eq!(loaded.max_points(), Some(40));
+        assert_eq!(loaded.max_contours(), Some(3));
+        assert_eq!(loaded.max_zones(), Some(2));
+        assert_eq!(loaded.max_composite_points(), Some(0));
+        assert_eq!(loaded.max_component_depth(), Some(1));
+        // the limits read from the table can be passed back to the builder
+        assert_eq!(loaded.truetype_limits(), Some(limits));
+    }
 }
@@ -2,6 +2,10 @@
 
 include!("../../generated/generated_os2.rs");
 
+mod builder;
+mod ranges;
+pub use builder::Os2Builder;
+
 impl Os2 {
     fn compute_version(&self) -> u16 {
         if self.us_lower_optical_point_size.is_some() || self.us_upper_optical_point_size.is_some()
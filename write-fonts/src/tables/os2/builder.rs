@@ -0,0 +1,260 @@
+//! A builder for the OS/2 table
+
+use int_set::IntSet;
+use read_fonts::{
+    tables::{glyf::Glyph, os2::SelectionFlags},
+    types::{GlyphId, Tag},
+    FontRef, ReadError, TableProvider,
+};
+
+use super::{ranges, Os2};
+
+/// A builder for the [`Os2`] table.
+///
+/// In addition to setting fields directly, the builder can derive the
+/// metrics and character ranges from an existing font with
+/// [`auto_calculate`](Os2Builder::auto_calculate). This is useful when
+/// subsetting, where these values must reflect the retained glyphs and
+/// codepoints.
+#[derive(Clone, Debug, Default)]
+pub struct Os2Builder {
+    os2: Os2,
+}
+
+macro_rules! setters {
+    ($($setter:ident => $field:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Set [`Os2::", stringify!($field), "`].")]
+            pub fn $setter(mut self, val: $ty) -> Self {
+                self.os2.$field = val;
+                self
+            }
+        )*
+    };
+}
+
+impl Os2Builder {
+    /// Create a new builder with the default values for all fields.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    setters! {
+        with_x_avg_char_width => x_avg_char_width: i16,
+        with_us_weight_class => us_weight_class: u16,
+        with_us_width_class => us_width_class: u16,
+        with_fs_type => fs_type: u16,
+        with_y_subscript_x_size => y_subscript_x_size: i16,
+        with_y_subscript_y_size => y_subscript_y_size: i16,
+        with_y_subscript_x_offset => y_subscript_x_offset: i16,
+        with_y_subscript_y_offset => y_subscript_y_offset: i16,
+        with_y_superscript_x_size => y_superscript_x_size: i16,
+        with_y_superscript_y_size => y_superscript_y_size: i16,
+        with_y_superscript_x_offset => y_superscript_x_offset: i16,
+        with_y_superscript_y_offset => y_superscript_y_offset: i16,
+        with_y_strikeout_size => y_strikeout_size: i16,
+        with_y_strikeout_position => y_strikeout_position: i16,
+        with_s_family_class => s_family_class: i16,
+        with_panose_10 => panose_10: [u8; 10],
+        with_ul_unicode_range_1 => ul_unicode_range_1: u32,
+        with_ul_unicode_range_2 => ul_unicode_range_2: u32,
+        with_ul_unicode_range_3 => ul_unicode_range_3: u32,
+        with_ul_unicode_range_4 => ul_unicode_range_4: u32,
+        with_ach_vend_id => ach_vend_id: Tag,
+        with_fs_selection => fs_selection: SelectionFlags,
+        with_us_first_char_index => us_first_char_index: u16,
+        with_us_last_char_index => us_last_char_index: u16,
+        with_s_typo_ascender => s_typo_ascender: i16,
+        with_s_typo_descender => s_typo_descender: i16,
+        with_s_typo_line_gap => s_typo_line_gap: i16,
+        with_us_win_ascent => us_win_ascent: u16,
+        with_us_win_descent => us_win_descent: u16,
+        with_ul_code_page_range_1 => ul_code_page_range_1: Option<u32>,
+        with_ul_code_page_range_2 => ul_code_page_range_2: Option<u32>,
+        with_sx_height => sx_height: Option<i16>,
+        with_s_cap_height => s_cap_height: Option<i16>,
+        with_us_default_char => us_default_char: Option<u16>,
+        with_us_break_char => us_break_char: Option<u16>,
+        with_us_max_context => us_max_context: Option<u16>,
+        with_us_lower_optical_point_size => us_lower_optical_point_size: Option<u16>,
+        with_us_upper_optical_point_size => us_upper_optical_point_size: Option<u16>,
+    }
+
+    /// Derive metrics and character ranges from `font`, restricted to the
+    /// retained codepoints and glyphs.
+    ///
+    /// This sets:
+    ///
+    /// - `sTypoAscender`, `sTypoDescender` and `sTypoLineGap`, from the
+    ///   font's OS/2 table if present, otherwise from `hhea`
+    /// - `usWinAscent` and `usWinDescent`, from the bounds of the retained
+    ///   glyphs (or the `head` bounds if the font has no `glyf` table)
+    /// - `sxHeight` and `sCapHeight`, from the heights of the glyphs for
+    ///   'x' and 'H', if they are retained
+    /// - `ulUnicodeRange1-4`, `ulCodePageRange1-2`, `usFirstCharIndex` and
+    ///   `usLastCharIndex` from the retained codepoints
+    ///
+    /// Codepoints that do not map to a retained glyph are ignored.
+    pub fn auto_calculate(
+        mut self,
+        font: &FontRef,
+        retained_unicodes: &IntSet<u32>,
+        retained_glyphs: &IntSet<u32>,
+    ) -> Result<Self, ReadError> {
+        let cmap = font.cmap()?;
+        let unicodes: IntSet<u32> = retained_unicodes
+            .iter()
+            .filter(|cp| {
+                cmap.map_codepoint(*cp)
+                    .is_some_and(|gid| retained_glyphs.contains(gid.to_u32()))
+            })
+            .collect();
+
+        if let Ok(os2) = font.os2() {
+            self.os2.s_typo_ascender = os2.s_typo_ascender();
+            self.os2.s_typo_descender = os2.s_typo_descender();
+            self.os2.s_typo_line_gap = os2.s_typo_line_gap();
+        } else {
+            let hhea = font.hhea()?;
+            self.os2.s_typo_ascender = hhea.ascender().to_i16();
+            self.os2.s_typo_descender = hhea.descender().to_i16();
+            self.os2.s_typo_line_gap = hhea.line_gap().to_i16();
+        }
+
+        let glyph_y_bounds = GlyphYBounds::new(font);
+        let (y_min, y_max) = match glyph_y_bounds.as_ref() {
+            Some(bounds) => retained_glyphs
+                .iter()
+                .filter_map(|gid| bounds.get(GlyphId::new(gid)))
+                .fold((0, 0), |(lo, hi), (y_min, y_max)| {
+                    (lo.min(y_min), hi.max(y_max))
+                }),
+            None => {
+                let head = font.head()?;
+                (head.y_min().min(0), head.y_max().max(0))
+            }
+        };
+        self.os2.us_win_ascent = y_max.max(0) as u16;
+        self.os2.us_win_descent = y_min.unsigned_abs();
+
+        let height_of = |c: char| {
+            let gid = cmap.map_codepoint(c)?;
+            if !unicodes.contains(c as u32) {
+                return None;
+            }
+            glyph_y_bounds.as_ref()?.get(gid).map(|(_, y_max)| y_max)
+        };
+        if let Some(x_height) = height_of('x') {
+            self.os2.sx_height = Some(x_height);
+        }
+        if let Some(cap_height) = height_of('H') {
+            self.os2.s_cap_height = Some(cap_height);
+        }
+
+        let [range_1, range_2, range_3, range_4] = ranges::unicode_ranges(&unicodes);
+        self.os2.ul_unicode_range_1 = range_1;
+        self.os2.ul_unicode_range_2 = range_2;
+        self.os2.ul_unicode_range_3 = range_3;
+        self.os2.ul_unicode_range_4 = range_4;
+        let [code_page_1, code_page_2] = ranges::code_page_ranges(&unicodes);
+        self.os2.ul_code_page_range_1 = Some(code_page_1);
+        self.os2.ul_code_page_range_2 = Some(code_page_2);
+
+        // these fields are limited to the BMP
+        self.os2.us_first_char_index = unicodes.first().unwrap_or(0).min(0xFFFF) as u16;
+        self.os2.us_last_char_index = unicodes.last().unwrap_or(0).min(0xFFFF) as u16;
+        Ok(self)
+    }
+
+    /// Construct the [`Os2`] table.
+    pub fn build(self) -> Os2 {
+        self.os2
+    }
+}
+
+impl From<Os2> for Os2Builder {
+    fn from(os2: Os2) -> Self {
+        Os2Builder { os2 }
+    }
+}
+
+/// Looks up the vertical bounds of glyphs in the `glyf` table.
+struct GlyphYBounds<'a> {
+    loca: read_fonts::tables::loca::Loca<'a>,
+    glyf: read_fonts::tables::glyf::Glyf<'a>,
+}
+
+impl<'a> GlyphYBounds<'a> {
+    fn new(font: &FontRef<'a>) -> Option<Self> {
+        Some(GlyphYBounds {
+            loca: font.loca(None).ok()?,
+            glyf: font.glyf().ok()?,
+        })
+    }
+
+    /// Returns (yMin, yMax) for a glyph with an outline.
+    fn get(&self, gid: GlyphId) -> Option<(i16, i16)> {
+        let glyph: Glyph = self.loca.get_glyf(gid, &self.glyf).ok()??;
+        Some((glyph.y_min(), glyph.y_max()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setters() {
+        let os2 = Os2Builder::new()
+            .with_us_weight_class(700)
+            .with_ach_vend_id(Tag::new(b"ABCD"))
+            .with_sx_height(Some(500))
+            .build();
+        assert_eq!(os2.us_weight_class, 700);
+        assert_eq!(os2.ach_vend_id, Tag::new(b"ABCD"));
+        assert_eq!(os2.sx_height, Some(500));
+        assert_eq!(os2.us_width_class, Os2::default().us_width_class);
+    }
+
+    #[test]
+    fn auto_calculate() {
+        let font = FontRef::new(font_test_data::SIMPLE_GLYF).unwrap();
+        let cmap = font.cmap().unwrap();
+        let all_glyphs: IntSet<u32> = (0..font.maxp().unwrap().num_glyphs() as u32).collect();
+        let unicodes: IntSet<u32> = (0..0x10000)
+            .filter(|cp| cmap.map_codepoint(*cp).is_some())
+            .collect();
+        assert!(!unicodes.is_empty());
+
+        let os2 = Os2Builder::new()
+            .auto_calculate(&font, &unicodes, &all_glyphs)
+            .unwrap()
+            .build();
+        let expected = font.os2().unwrap();
+        assert_eq!(os2.s_typo_ascender, expected.s_typo_ascender());
+        assert_eq!(os2.s_typo_descender, expected.s_typo_descender());
+        assert_eq!(os2.us_first_char_index, unicodes.first().unwrap() as u16);
+        assert_eq!(os2.us_last_char_index, unicodes.last().unwrap() as u16);
+        assert!(os2.us_win_ascent > 0);
+
+        // unmapped and unretained codepoints are ignored
+        let mut extra_unicodes = unicodes.clone();
+        extra_unicodes.insert(0x10FFF0);
+        let os2_extra = Os2Builder::new()
+            .auto_calculate(&font, &extra_unicodes, &all_glyphs)
+            .unwrap()
+            .build();
+        assert_eq!(os2_extra.ul_unicode_range_2, os2.ul_unicode_range_2);
+
+        let no_glyphs = IntSet::empty();
+        let os2 = Os2Builder::new()
+            .auto_calculate(&font, &unicodes, &no_glyphs)
+            .unwrap()
+            .build();
+        assert_eq!(os2.us_first_char_index, 0);
+        assert_eq!(os2.us_last_char_index, 0);
+        assert_eq!(os2.ul_unicode_range_1, 0);
+        assert_eq!((os2.us_win_ascent, os2.us_win_descent), (0, 0));
+        assert_eq!(os2.sx_height, None);
+    }
+}
@@ -0,0 +1,5 @@
+//! The [vvar](https://learn.microsoft.com/en-us/typography/opentype/spec/vvar) table
+
+include!("../../generated/generated_vvar.rs");
+
+use super::variations::{DeltaSetIndexMap, ItemVariationStore};
@@ -174,6 +174,30 @@ fn anchorformat3() {
     assert_eq!(table, read_back)
 }
 
+#[test]
+fn construct_anchors() {
+    let format1 = AnchorTable::format_1(189, -103);
+    assert_hex_eq!(
+        test_data::ANCHORFORMAT1,
+        &crate::write::dump_table(&format1).unwrap()
+    );
+
+    let format2 = AnchorTable::format_2(322, 900, 13);
+    assert_hex_eq!(
+        test_data::ANCHORFORMAT2,
+        &crate::write::dump_table(&format2).unwrap()
+    );
+
+    let device = || Some(DeviceOrVariationIndex::device(12, 17, &[1, 1, 1, 1, 2, 2]));
+    let format3 = AnchorTable::format_3(279, 1301, device(), device());
+    let dumped = crate::write::dump_table(&format3).unwrap();
+    let read_back = AnchorTable::read(dumped.as_slice().into()).unwrap();
+    assert_eq!(format3, read_back);
+    // we can't compare bytes since we deduplicate the device tables on write
+    let expected = AnchorTable::read(test_data::ANCHORFORMAT3.into()).unwrap();
+    assert_eq!(format3, expected);
+}
+
 // not from the spec; this is a general test that we don't write out versioned
 // fields inappropriately.
 #[test]
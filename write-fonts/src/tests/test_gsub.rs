@@ -18,6 +18,46 @@ fn singlesubstformat2() {
     assert_hex_eq!(test_data::SINGLESUBSTFORMAT2_TABLE, &dumped);
 }
 
+#[test]
+fn construct_single_subst() {
+    let coverage = || CoverageTable::format_1(vec![GlyphId16::new(2), GlyphId16::new(7)]);
+
+    let format1 = SingleSubst::format_1(coverage(), 3);
+    let bytes = crate::dump_table(&format1).unwrap();
+    let read_back = read_fonts::tables::gsub::SingleSubst::read(bytes.as_slice().into()).unwrap();
+    let read_fonts::tables::gsub::SingleSubst::Format1(read_back) = read_back else {
+        panic!("expected format 1");
+    };
+    assert_eq!(read_back.delta_glyph_id(), 3);
+    assert_eq!(read_back.coverage().unwrap().iter().count(), 2);
+
+    let substitutes = vec![GlyphId16::new(20), GlyphId16::new(4)];
+    let format2 = SingleSubst::format_2(coverage(), substitutes.clone());
+    let bytes = crate::dump_table(&format2).unwrap();
+    let read_back = read_fonts::tables::gsub::SingleSubst::read(bytes.as_slice().into()).unwrap();
+    let read_fonts::tables::gsub::SingleSubst::Format2(read_back) = read_back else {
+        panic!("expected format 2");
+    };
+    assert_eq!(
+        read_back
+            .substitute_glyph_ids()
+            .iter()
+            .map(|gid| gid.get())
+            .collect::<Vec<_>>(),
+        substitutes
+    );
+}
+
+#[test]
+fn single_subst_format2_count_mismatch() {
+    let coverage = CoverageTable::format_1(vec![GlyphId16::new(2), GlyphId16::new(7)]);
+    let subtable = SingleSubst::format_2(coverage, vec![GlyphId16::new(20)]);
+    assert!(matches!(
+        crate::dump_table(&subtable),
+        Err(crate::error::Error::ValidationFailed(_))
+    ));
+}
+
 #[test]
 fn multiplesubstformat1() {
     // https://learn.microsoft.com/en-us/typography/opentype/spec/gsub#example-4-multiplesubstformat1-subtable
@@ -41,3 +81,235 @@ fn ligaturesubstformat1() {
     let dumped = crate::write::dump_table(&table).unwrap();
     assert_hex_eq!(test_data::LIGATURESUBSTFORMAT1_TABLE, &dumped);
 }
+
+#[test]
+fn identical_subtables_are_shared() {
+    use read_fonts::tables::layout::LookupFlag;
+
+    let make_subtable =
+        || SingleSubst::format_1(CoverageTable::format_1(vec![GlyphId16::new(5)]), 10);
+    // lookups differ (so are not themselves shared) but have identical subtables
+    let lookup_list = SubstitutionLookupList::new(vec![
+        SubstitutionLookup::Single(Lookup::new(LookupFlag::empty(), vec![make_subtable()])),
+        SubstitutionLookup::Single(Lookup::new(LookupFlag::IGNORE_MARKS, vec![make_subtable()])),
+    ]);
+    let bytes = crate::dump_table(&lookup_list).unwrap();
+    // lookup list (6) + two lookups (8 each) + one subtable (6) + one coverage (6)
+    assert_eq!(bytes.len(), 34);
+
+    let read_list =
+        read_fonts::tables::gsub::SubstitutionLookupList::read(bytes.as_slice().into()).unwrap();
+    let subtable_positions = read_list
+        .lookup_offsets()
+        .iter()
+        .map(|lookup_off| {
+            let lookup_pos = lookup_off.get().to_u32() as usize;
+            let lookup =
+                read_fonts::tables::layout::Lookup::<()>::read(bytes[lookup_pos..].into()).unwrap();
+            lookup_pos + lookup.subtable_offsets()[0].get().to_u32() as usize
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(subtable_positions[0], subtable_positions[1]);
+    assert_eq!(crate::estimated_size(&lookup_list), bytes.len());
+}
+
+fn gsub_with_lookups(lookups: Vec<SubstitutionLookup>) -> Gsub {
+    Gsub::new(
+        Default::default(),
+        Default::default(),
+        SubstitutionLookupList::new(lookups),
+    )
+}
+
+#[test]
+fn validate_offsets_ok() {
+    use read_fonts::tables::layout::LookupFlag;
+
+    let subtable = SingleSubst::format_1(CoverageTable::format_1(vec![GlyphId16::new(5)]), 10);
+    let gsub = gsub_with_lookups(vec![SubstitutionLookup::Single(Lookup::new(
+        LookupFlag::empty(),
+        vec![subtable],
+    ))]);
+    assert!(crate::validate_offsets(&gsub).is_ok());
+}
+
+#[test]
+fn validate_offsets_overflow() {
+    use read_fonts::tables::layout::LookupFlag;
+
+    // a single ligature subtable whose ligature sets can't all be reached
+    // via 16-bit offsets, regardless of how they are ordered.
+    let ligature_sets = (0..4000u16)
+        .map(|i| {
+            LigatureSet::new(vec![Ligature::new(
+                GlyphId16::new(i),
+                (0..10).map(GlyphId16::new).collect(),
+            )])
+        })
+        .collect::<Vec<_>>();
+    let coverage = CoverageTable::format_1((0..4000).map(GlyphId16::new).collect());
+    let subtable = LigatureSubstFormat1::new(coverage, ligature_sets);
+    // the overflow can be predicted from the size of the subtable
+    assert!(crate::estimated_size(&subtable) > u16::MAX as usize);
+    let gsub = gsub_with_lookups(vec![SubstitutionLookup::Ligature(Lookup::new(
+        LookupFlag::empty(),
+        vec![subtable],
+    ))]);
+
+    let err = crate::validate_offsets(&gsub).unwrap_err();
+    let crate::error::Error::OffsetOverflow {
+        table,
+        offset_sites,
+    } = &err
+    else {
+        panic!("expected offset overflow");
+    };
+    assert_eq!(*table, Gsub::TAG);
+    // every overflowing offset is reported, not just the first
+    assert!(offset_sites.len() > 1, "{offset_sites:?}");
+    for offset_site in offset_sites {
+        assert!(offset_site.contains("Offset16"), "{offset_site}");
+    }
+    assert!(err.to_string().starts_with(&format!(
+        "{} offset overflows in 'GSUB'",
+        offset_sites.len()
+    )));
+}
+
+#[test]
+fn single_subst_round_trip() {
+    use read_fonts::tables::gsub as read_gsub;
+
+    // a sparse mapping, so that coverage format 1 is used, with a constant delta
+    let inputs = [3u16, 10, 11, 12, 40];
+    let coverage = || {
+        inputs
+            .iter()
+            .copied()
+            .map(GlyphId16::new)
+            .collect::<CoverageTable>()
+    };
+    let format1 = SingleSubst::format_1(coverage(), -2);
+    // a mapping that needs an array of substitutes
+    let substitutes = [7u16, 100, 8, 0xffff, 9];
+    let format2 = SingleSubst::format_2(
+        coverage(),
+        substitutes.iter().copied().map(GlyphId16::new).collect(),
+    );
+
+    let substitute = |table: &read_gsub::SingleSubst, gid: u16| match table {
+        read_gsub::SingleSubst::Format1(table) => {
+            table.coverage().unwrap().get(GlyphId16::new(gid))?;
+            Some((gid as i32 + table.delta_glyph_id() as i32) as u16)
+        }
+        read_gsub::SingleSubst::Format2(table) => {
+            let index = table.coverage().unwrap().get(GlyphId16::new(gid))?;
+            Some(table.substitute_glyph_ids()[index as usize].get().to_u16())
+        }
+    };
+
+    let bytes = crate::dump_table(&format1).unwrap();
+    let read_back = read_gsub::SingleSubst::read(bytes.as_slice().into()).unwrap();
+    for gid in inputs {
+        assert_eq!(substitute(&read_back, gid), Some(gid - 2));
+    }
+    let bytes = crate::dump_table(&format2).unwrap();
+    let read_back = read_gsub::SingleSubst::read(bytes.as_slice().into()).unwrap();
+    for (gid, expected) in inputs.iter().zip(substitutes) {
+        assert_eq!(substitute(&read_back, *gid), Some(expected));
+    }
+    // glyphs that are not covered are not substituted
+    for gid in [0, 4, 13, 39, 41, 0xffff] {
+        assert_eq!(substitute(&read_back, gid), None);
+    }
+}
+
+#[test]
+fn ligature_subst_round_trip() {
+    // (components, ligature), with some ligatures sharing their first glyph
+    let rules: &[(&[u16], u16)] = &[
+        (&[5, 6, 7], 100),
+        (&[5, 6], 101),
+        (&[5, 8], 102),
+        (&[9, 9], 103),
+        (&[300, 5], 104),
+    ];
+    let mut sets = std::collections::BTreeMap::<u16, Vec<Ligature>>::new();
+    for (components, ligature) in rules {
+        sets.entry(components[0]).or_default().push(Ligature::new(
+            GlyphId16::new(*ligature),
+            components[1..]
+                .iter()
+                .copied()
+                .map(GlyphId16::new)
+                .collect(),
+        ));
+    }
+    let coverage = sets.keys().copied().map(GlyphId16::new).collect();
+    let table =
+        LigatureSubstFormat1::new(coverage, sets.into_values().map(LigatureSet::new).collect());
+
+    let bytes = crate::dump_table(&table).unwrap();
+    let read_back =
+        read_fonts::tables::gsub::LigatureSubstFormat1::read(bytes.as_slice().into()).unwrap();
+    let gids = |ids: &[u16]| ids.iter().copied().map(GlyphId::from).collect::<Vec<_>>();
+    for (components, ligature) in rules {
+        assert_eq!(
+            read_back.match_sequence(&gids(components)),
+            Some(GlyphId::from(*ligature)),
+            "{components:?}"
+        );
+    }
+    // the longest ligature wins when it comes first, and trailing glyphs are ignored
+    assert_eq!(
+        read_back.match_sequence(&gids(&[5, 6, 7, 1])),
+        Some(GlyphId::new(100))
+    );
+    assert_eq!(read_back.match_sequence(&gids(&[5, 7])), None);
+    assert_eq!(read_back.match_sequence(&gids(&[9])), None);
+    assert_eq!(read_back.match_sequence(&gids(&[6, 7])), None);
+}
+
+#[test]
+fn chained_context_subst_format3_builder() {
+    use read_fonts::tables::layout as read_layout;
+
+    let coverage = |gids: &[u16]| {
+        gids.iter()
+            .copied()
+            .map(GlyphId16::new)
+            .collect::<CoverageTable>()
+    };
+    let mut builder = ChainedContextSubstF3Builder::default();
+    builder.set_backtrack_coverages(vec![coverage(&[1, 2])]);
+    builder.set_input_coverages(vec![coverage(&[10]), coverage(&[11, 12])]);
+    builder.set_lookahead_coverages(vec![coverage(&[20]), coverage(&[21]), coverage(&[22])]);
+    builder.add_lookup_record(1, 4);
+    builder.add_lookup_record(0, 2);
+
+    let bytes = builder.build().unwrap();
+    let read_back = read_layout::ChainedSequenceContext::read(bytes.as_slice().into()).unwrap();
+    let read_layout::ChainedSequenceContext::Format3(table) = read_back else {
+        panic!("expected format 3");
+    };
+    fn glyphs<'a>(
+        coverages: read_fonts::ArrayOfOffsets<'a, read_layout::CoverageTable<'a>>,
+    ) -> Vec<Vec<u16>> {
+        coverages
+            .iter()
+            .map(|cov| cov.unwrap().iter().map(|gid| gid.to_u16()).collect())
+            .collect()
+    }
+    assert_eq!(glyphs(table.backtrack_coverages()), [vec![1, 2]]);
+    assert_eq!(glyphs(table.input_coverages()), [vec![10], vec![11, 12]]);
+    assert_eq!(
+        glyphs(table.lookahead_coverages()),
+        [vec![20], vec![21], vec![22]]
+    );
+    let records = table
+        .seq_lookup_records()
+        .iter()
+        .map(|rec| (rec.sequence_index(), rec.lookup_list_index()))
+        .collect::<Vec<_>>();
+    assert_eq!(records, [(1, 4), (0, 2)]);
+}
@@ -5,7 +5,7 @@ use crate::graph::{Graph, ObjectId, ObjectStore, OffsetLen};
 use crate::table_type::TableType;
 use crate::validate::Validate;
 use font_types::{FixedSize, Scalar};
-use read_fonts::{FontData, FontRead, FontReadWithArgs, ReadError};
+use read_fonts::{FontData, FontRead, FontReadWithArgs, ReadError, TopLevelTable};
 
 /// A type that that can be written out as part of a font file.
 ///
@@ -62,6 +62,52 @@ pub fn dump_table<T: FontWrite + Validate>(table: &T) -> Result<Vec<u8>, Error>
     Ok(graph.serialize())
 }
 
+/// Check that all the offsets in a top-level table can be resolved.
+///
+/// This performs the same offset resolution as [`dump_table`], including any
+/// attempts to repack the table (such as promoting GSUB/GPOS lookups to
+/// extension lookups) but without producing the final bytes.
+///
+/// If offsets remain that cannot be represented, an [`Error::OffsetOverflow`]
+/// is returned describing each of them.
+pub fn validate_offsets<T: FontWrite + TopLevelTable>(table: &T) -> Result<(), Error> {
+    let mut graph = TableWriter::make_graph(table);
+    if graph.pack_objects() {
+        return Ok(());
+    }
+    let offset_sites = graph
+        .find_overflows()
+        .iter()
+        .map(|overflow| graph.describe_overflow(overflow))
+        .collect();
+    Err(Error::OffsetOverflow {
+        table: T::TAG,
+        offset_sites,
+    })
+}
+
+/// Estimate the number of bytes needed to serialize a table and its subtables.
+///
+/// This is the combined size of all the distinct objects reachable from
+/// `table`, with identical subtables counted once. It is computed without
+/// resolving offsets, and so it is cheaper than [`dump_table`]; the final
+/// size may be larger if subtables have to be duplicated or wrapped in
+/// extension lookups to avoid offset overflows.
+///
+/// This can be used to detect ahead of time that a subtable (such as a
+/// GSUB or GPOS lookup subtable) is too large to be addressed with 16-bit
+/// offsets, and should be split.
+pub fn estimated_size(table: &impl FontWrite) -> usize {
+    let mut writer = TableWriter::default();
+    writer.add_table(table);
+    writer
+        .tables
+        .objects
+        .keys()
+        .map(|object| object.bytes.len())
+        .sum()
+}
+
 impl TableWriter {
     /// A convenience method for generating a graph with the provided root object.
     pub(crate) fn make_graph(root: &impl FontWrite) -> Graph {